@@ -0,0 +1,85 @@
+use escrow_bytemuck::idl::{IdlType, IdlTypeDef, ACCOUNTS};
+
+use crate::{rpc::Account, Result};
+
+
+fn packed_size(def: &IdlTypeDef) -> usize {
+    def.fields.iter().map(|f| f.ty.size()).sum()
+}
+
+// Picks the state layout by name (`vire`, `uni`, `subject`, `student`, `transcript`, `scholarship_pool`,
+// `scholarship_award`, `discount`, `waitlist`) or by data length
+pub fn find_layout(kind: Option<&str>, data: &[u8]) -> Result<&'static IdlTypeDef> {
+    if let Some(kind) = kind {
+        let name = kind.replace('_', "").to_lowercase();
+        return ACCOUNTS
+            .iter()
+            .find(|def| [name.clone(), format!("{name}account")].contains(&def.name.to_lowercase()))
+            .ok_or_else(|| format!("unknown account kind `{kind}`").into());
+    }
+
+    let matches: Vec<_> = ACCOUNTS.iter().filter(|def| packed_size(def) == data.len()).collect();
+    match matches.as_slice() {
+        [def] => Ok(def),
+        [] => Err(format!("no Vire account has a length of {} bytes", data.len()).into()),
+        _ => Err(format!(
+            "{} bytes matches several accounts ({}), pass --kind",
+            data.len(),
+            matches.iter().map(|def| def.name).collect::<Vec<_>>().join(", ")
+        )
+        .into()),
+    }
+}
+
+fn format_value(ty: IdlType, bytes: &[u8]) -> String {
+    match ty {
+        IdlType::Bool => (bytes[0] != 0).to_string(),
+        IdlType::U8 => bytes[0].to_string(),
+        IdlType::U64 => u64::from_le_bytes(bytes.try_into().unwrap()).to_string(),
+        IdlType::I64 => i64::from_le_bytes(bytes.try_into().unwrap()).to_string(),
+        IdlType::Pubkey => bs58::encode(bytes).into_string(),
+        IdlType::Array(ty, _) => {
+            let items: Vec<_> = bytes.chunks(ty.size()).map(|item| format_value(*ty, item)).collect();
+            format!("[{}]", items.join(", "))
+        }
+        IdlType::Defined(def) => {
+            let mut offset = 0;
+            let fields: Vec<_> = def
+                .fields
+                .iter()
+                .map(|f| {
+                    let value = format_value(f.ty, &bytes[offset..offset + f.ty.size()]);
+                    offset += f.ty.size();
+                    format!("{}: {}", f.name, value)
+                })
+                .collect();
+            format!("{{ {} }}", fields.join(", "))
+        }
+    }
+}
+
+pub fn print_account(address: Option<&str>, account: &Account, kind: Option<&str>) -> Result<()> {
+    if account.owner != escrow_bytemuck::ID {
+        eprintln!("warning: account is owned by {}, not the Vire program", bs58::encode(account.owner).into_string());
+    }
+
+    let def = find_layout(kind, &account.data)?;
+    if account.data.len() != packed_size(def) {
+        return Err(format!("{} is {} bytes, got {}", def.name, packed_size(def), account.data.len()).into());
+    }
+
+    println!("{}", def.name);
+    if let Some(address) = address {
+        println!("  {:<26} {}", "address", address);
+    }
+    println!("  {:<26} {}", "lamports", account.lamports);
+
+    let mut offset = 0;
+    for field in def.fields {
+        let bytes = &account.data[offset..offset + field.ty.size()];
+        println!("  {:<26} {}", field.name, format_value(field.ty, bytes));
+        offset += field.ty.size();
+    }
+
+    Ok(())
+}
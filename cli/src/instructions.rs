@@ -0,0 +1,66 @@
+use escrow_bytemuck::{idl::INSTRUCTIONS, instructions::vire_instructions::VireInstruction};
+use pinocchio::pubkey::Pubkey;
+
+use crate::{
+    pda::ATA_PROGRAM_ID,
+    transaction::{AccountMeta, Instruction},
+    Result,
+};
+
+
+// Builds a program instruction, the account order and signer/writable flags come from the IDL.
+// Optional accounts are added up to the last one given (plus fixed addresses right after it),
+// left-out ones in between are filled with the program id.
+pub fn vire_instruction(
+    program_id: &Pubkey,
+    instruction: VireInstruction,
+    accounts: &[(&str, Pubkey)],
+    args: &[u8],
+) -> Result<Instruction> {
+    let ix = INSTRUCTIONS
+        .iter()
+        .find(|ix| ix.instruction == instruction)
+        .ok_or("instruction is missing from the IDL")?;
+
+    let given = |name: &str| accounts.iter().find(|(given, _)| *given == name).map(|(_, key)| *key);
+    let mut end = ix
+        .accounts
+        .iter()
+        .rposition(|item| !item.optional || given(item.name).is_some())
+        .map_or(0, |last| last + 1);
+    while ix.accounts.get(end).is_some_and(|item| item.address.is_some()) {
+        end += 1;
+    }
+
+    let accounts = ix.accounts[..end]
+        .iter()
+        .map(|item| {
+            match (item.address.or_else(|| given(item.name)), item.optional) {
+                (Some(pubkey), _) => Ok(AccountMeta { pubkey, is_signer: item.signer, is_writable: item.writable }),
+                (None, true) => Ok(AccountMeta { pubkey: *program_id, is_signer: false, is_writable: false }),
+                (None, false) => Err(format!("missing account `{}` for {}", item.name, ix.name).into()),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut data = vec![instruction as u8];
+    data.extend_from_slice(args);
+
+    Ok(Instruction { program_id: *program_id, accounts, data })
+}
+
+// Associated token account program `CreateIdempotent`
+pub fn create_ata_idempotent(payer: &Pubkey, ata: &Pubkey, owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: ATA_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta { pubkey: *payer, is_signer: true, is_writable: true },
+            AccountMeta { pubkey: *ata, is_signer: false, is_writable: true },
+            AccountMeta { pubkey: *owner, is_signer: false, is_writable: false },
+            AccountMeta { pubkey: *mint, is_signer: false, is_writable: false },
+            AccountMeta { pubkey: pinocchio_system::ID, is_signer: false, is_writable: false },
+            AccountMeta { pubkey: *token_program, is_signer: false, is_writable: false },
+        ],
+        data: vec![1],
+    }
+}
@@ -0,0 +1,1311 @@
+use std::{error::Error, fs, path::PathBuf};
+
+use bytemuck::{Pod, Zeroable};
+use clap::{ArgGroup, Args, Parser, Subcommand};
+use ed25519_dalek::SigningKey;
+use escrow_bytemuck::{
+    discount_account::DiscountKind,
+    instructions::{
+        add_intake_cohort::CohortArgs, add_role::RoleArgs, add_subjects::SubjectArgs, approve_semester::ApproveSemesterArgs, award_scholarship::AwardArgs,
+        create_discount::DiscountArgs, create_waitlist::WaitlistArgs, deposit_scholarship::DepositArgs, expel_student::ExpelArgs, initialize_scholarship_pool::PoolArgs, initialize_student::StudentArgs, initialize_uni::UniArgs,
+        initialize_vire::VireArgs, record_grades::GradeArgs, revoke_credential::RevokeArgs, suspend_student::SuspendArgs, update_payment_mint::PaymentMintArgs,
+        verify_credential::{CredentialState, CredentialStatus}, vire_instructions::VireInstruction,
+    },
+    scholarship_pool::ScholarshipPool,
+    student_account::StudentAccount,
+    subject_account::SubjectAccount,
+    token_interface::{MintMode, TOKEN_2022_PROGRAM_ID},
+    token_metadata::{MetadataArgs, MetadataMode, MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH},
+    uni_account::{UniAccount, ROLE_FINANCE, ROLE_GRADER, ROLE_REGISTRAR},
+};
+use pinocchio::pubkey::Pubkey;
+
+use instructions::{create_ata_idempotent, vire_instruction};
+use rpc::RpcClient;
+use transaction::{AccountMeta, Instruction};
+
+mod display;
+mod instructions;
+mod pda;
+mod rpc;
+mod transaction;
+
+pub type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
+
+
+#[derive(Parser)]
+#[command(name = "vire-cli", about = "Admin tool for the Vire tuition protocol")]
+struct Cli {
+    /// JSON-RPC endpoint of the cluster
+    #[arg(long, global = true, default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// Keypair file of the signer and fee payer (defaults to ~/.config/solana/id.json)
+    #[arg(long, global = true)]
+    keypair: Option<PathBuf>,
+
+    /// Vire program id
+    #[arg(long, global = true, value_parser = parse_pubkey)]
+    program_id: Option<Pubkey>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create the protocol VireAccount with the signer as admin
+    InitVire {
+        #[arg(long, value_parser = parse_pubkey)]
+        mint: Pubkey,
+        /// Protocol treasury token account
+        #[arg(long, value_parser = parse_pubkey)]
+        treasury: Pubkey,
+        /// Protocol fee charged to universities (percent)
+        #[arg(long)]
+        fee_uni: u64,
+        /// Protocol fee charged to students (percent)
+        #[arg(long)]
+        fee_student: u64,
+    },
+    /// Add (or with --remove, remove) a mint on the protocol's payment mint allowlist
+    UpdatePaymentMint {
+        #[arg(long, value_parser = parse_pubkey)]
+        mint: Pubkey,
+        #[arg(long)]
+        remove: bool,
+    },
+    /// Let a pending university operate as the Vire admin
+    ApproveUni {
+        #[arg(long, value_parser = parse_pubkey)]
+        uni: Pubkey,
+    },
+    /// Suspend an active university as the Vire admin
+    SuspendUni {
+        #[arg(long, value_parser = parse_pubkey)]
+        uni: Pubkey,
+    },
+    /// Reinstate a suspended university as the Vire admin
+    ReinstateUni {
+        #[arg(long, value_parser = parse_pubkey)]
+        uni: Pubkey,
+    },
+    /// Register the signer as a university (pending until the Vire admin approves it)
+    InitUni {
+        #[arg(long, value_parser = parse_pubkey)]
+        vire: Pubkey,
+    },
+    /// Add a subject to the signer's university, charging tuition in --mint
+    AddSubject {
+        #[arg(long, value_parser = parse_pubkey)]
+        vire: Pubkey,
+        /// University account, when signing as its registrar instead of the admin
+        #[arg(long, value_parser = parse_pubkey)]
+        uni: Option<Pubkey>,
+        #[arg(long, value_parser = parse_pubkey)]
+        mint: Pubkey,
+        #[arg(long, value_parser = parse_pubkey)]
+        treasury: Pubkey,
+        /// Collection mint, already initialised with the subject PDA as authority
+        #[arg(long, value_parser = parse_pubkey, required_unless_present = "collection_mint_keypair")]
+        collection_mint: Option<Pubkey>,
+        /// Keypair of a new collection mint for the program to create
+        #[arg(long, conflicts_with = "collection_mint")]
+        collection_mint_keypair: Option<PathBuf>,
+        /// Create the new collection mint under Token-2022
+        #[arg(long, requires = "collection_mint_keypair")]
+        token_2022: bool,
+        #[arg(long)]
+        tution_fee: u64,
+        #[arg(long)]
+        max_semester: u64,
+        #[arg(long)]
+        semester_months: u64,
+        /// Minimum cumulative transcript grade needed to unstake (0 = none)
+        #[arg(long, default_value_t = 0)]
+        min_grade: u64,
+        /// Require ApproveSemester for each semester before the next payment
+        #[arg(long)]
+        require_approval: bool,
+        /// Days after each semester starts to pay without a late fee (0 = pay any time)
+        #[arg(long, default_value_t = 0)]
+        payment_window_days: u64,
+        /// Late fee (percent of the semester fee) after the payment window
+        #[arg(long, default_value_t = 0)]
+        late_fee: u64,
+        /// Monthly installments per semester (at most --semester-months)
+        #[arg(long, default_value_t = 1)]
+        installments: u8,
+        /// Maximum number of enrolled students (0 = unlimited)
+        #[arg(long, default_value_t = 0)]
+        max_students: u64,
+        /// Subject students have to graduate from first (repeat for up to 4)
+        #[arg(long = "prerequisite", value_parser = parse_pubkey)]
+        prerequisites: Vec<Pubkey>,
+        /// Percent of the semesters paid in another subject credited to students transferring in
+        #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(u8).range(..=100))]
+        transfer_credit: u8,
+        /// Percent of the current semester refunded to the sponsor on suspension or expulsion
+        #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(u8).range(..=100))]
+        refund_percent: u8,
+        #[command(flatten)]
+        metadata: MetadataOpts,
+    },
+    /// Enrol the signer in a subject
+    Enrol {
+        #[arg(long, value_parser = parse_pubkey)]
+        subject: Pubkey,
+        /// Card mint, already initialised with the student PDA as authority
+        #[arg(long, value_parser = parse_pubkey, required_unless_present = "card_mint_keypair")]
+        card_mint: Option<Pubkey>,
+        /// Keypair of a new card mint for the program to create
+        #[arg(long, conflicts_with = "card_mint")]
+        card_mint_keypair: Option<PathBuf>,
+        /// Create the new card mint under Token-2022
+        #[arg(long, requires = "card_mint_keypair")]
+        token_2022: bool,
+        #[command(flatten)]
+        metadata: MetadataOpts,
+    },
+    /// Move the signer's enrolment to another subject of the same university, re-issuing the card
+    Transfer {
+        /// Subject the signer is enrolled in
+        #[arg(long, value_parser = parse_pubkey)]
+        from: Pubkey,
+        #[arg(long, value_parser = parse_pubkey)]
+        to: Pubkey,
+        /// Current card mint, burned by the transfer
+        #[arg(long, value_parser = parse_pubkey)]
+        card_mint: Pubkey,
+        /// New card mint, already initialised with the new student PDA as authority
+        #[arg(long, value_parser = parse_pubkey, required_unless_present = "new_card_mint_keypair")]
+        new_card_mint: Option<Pubkey>,
+        /// Keypair of a new card mint for the program to create
+        #[arg(long, conflicts_with = "new_card_mint")]
+        new_card_mint_keypair: Option<PathBuf>,
+        /// Create the new card mint under Token-2022
+        #[arg(long, requires = "new_card_mint_keypair")]
+        token_2022: bool,
+        #[command(flatten)]
+        metadata: MetadataOpts,
+    },
+    /// Pay the signer's tuition for the next semester (or installment), or with --student sponsor another student's
+    Pay {
+        #[arg(long, value_parser = parse_pubkey)]
+        subject: Pubkey,
+        #[arg(long, value_parser = parse_pubkey)]
+        uni_admin: Pubkey,
+        /// Protocol treasury: the Vire admin's associated token account for the subject's payment mint
+        #[arg(long, value_parser = parse_pubkey)]
+        treasury: Pubkey,
+        /// Student wallet to pay for from the signer's token account (SponsorPayTuition)
+        #[arg(long, value_parser = parse_pubkey)]
+        student: Option<Pubkey>,
+        /// Scholarship pool the student has an award in, drawn from before the payer's tokens
+        #[arg(long, value_parser = parse_pubkey)]
+        scholarship_pool: Option<Pubkey>,
+        /// Discount account to apply to this payment
+        #[arg(long, value_parser = parse_pubkey)]
+        discount: Option<Pubkey>,
+    },
+    /// Release the signer's card after graduation
+    Unstake {
+        #[arg(long, value_parser = parse_pubkey)]
+        subject: Pubkey,
+        #[arg(long, value_parser = parse_pubkey)]
+        card_mint: Pubkey,
+        /// Keypair for the new diploma mint (a fresh one is generated if omitted)
+        #[arg(long)]
+        diploma_mint_keypair: Option<PathBuf>,
+    },
+    /// Check on-chain (VerifyCredential, simulated) whether a wallet graduated from a subject
+    Verify {
+        #[arg(long, value_parser = parse_pubkey)]
+        student: Pubkey,
+        #[arg(long, value_parser = parse_pubkey)]
+        subject: Pubkey,
+    },
+    /// Revoke a student's credential as the university admin (freezes the diploma)
+    Revoke {
+        #[arg(long, value_parser = parse_pubkey)]
+        subject: Pubkey,
+        /// Student wallet
+        #[arg(long, value_parser = parse_pubkey)]
+        student: Pubkey,
+        /// University-defined reason code (1-255)
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..))]
+        reason: u8,
+    },
+    /// Record a student's semester results as a grader of the university
+    RecordGrades {
+        #[arg(long, value_parser = parse_pubkey)]
+        subject: Pubkey,
+        /// Student wallet
+        #[arg(long, value_parser = parse_pubkey)]
+        student: Pubkey,
+        #[arg(long)]
+        semester: u64,
+        /// GPA x100 (350 = 3.50) or credit points, as the university defines it
+        #[arg(long)]
+        grade: u64,
+        #[arg(long)]
+        credits: u64,
+        /// SHA-256 of the off-chain transcript document (hex)
+        #[arg(long, value_parser = parse_hash)]
+        transcript_hash: [u8; 32],
+    },
+    /// Sign off a student's paid semester as the university admin
+    ApproveSemester {
+        #[arg(long, value_parser = parse_pubkey)]
+        subject: Pubkey,
+        /// Student wallet
+        #[arg(long, value_parser = parse_pubkey)]
+        student: Pubkey,
+        #[arg(long)]
+        semester: u64,
+    },
+    /// Create a scholarship pool for the signer's university, or with --subject for one of its subjects
+    InitScholarship {
+        #[arg(long, value_parser = parse_pubkey)]
+        vire: Pubkey,
+        /// University account, when signing as its finance staff instead of the admin
+        #[arg(long, value_parser = parse_pubkey)]
+        uni: Option<Pubkey>,
+        #[arg(long, value_parser = parse_pubkey)]
+        subject: Option<Pubkey>,
+        /// Mint donations are made in (the subject's payment mint for subject pools)
+        #[arg(long, value_parser = parse_pubkey)]
+        mint: Pubkey,
+    },
+    /// Donate tokens from the signer's token account to a scholarship pool
+    Donate {
+        #[arg(long, value_parser = parse_pubkey)]
+        pool: Pubkey,
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Award a grant from a scholarship pool to a student as the university admin
+    Award {
+        #[arg(long, value_parser = parse_pubkey)]
+        pool: Pubkey,
+        #[arg(long, value_parser = parse_pubkey)]
+        subject: Pubkey,
+        /// Student wallet
+        #[arg(long, value_parser = parse_pubkey)]
+        student: Pubkey,
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Issue a discount on a subject's tuition payments as the university admin
+    #[command(group(ArgGroup::new("amount").required(true).args(["percent", "fixed"])))]
+    CreateDiscount {
+        #[arg(long, value_parser = parse_pubkey)]
+        subject: Pubkey,
+        /// Number identifying the discount within the subject
+        #[arg(long)]
+        id: u64,
+        /// Percentage taken off each payment (100 = fee waiver)
+        #[arg(long, value_parser = clap::value_parser!(u64).range(..=100))]
+        percent: Option<u64>,
+        /// Amount taken off each payment
+        #[arg(long)]
+        fixed: Option<u64>,
+        /// Unix timestamp after which the discount can't be used (0 = never)
+        #[arg(long, default_value_t = 0)]
+        expires_at: i64,
+        /// Number of payments it can be applied to (0 = unlimited)
+        #[arg(long, default_value_t = 0)]
+        max_uses: u64,
+        /// Only this student wallet can use it
+        #[arg(long, value_parser = parse_pubkey)]
+        student: Option<Pubkey>,
+    },
+    /// Open a waitlist for a full subject as the university admin
+    CreateWaitlist {
+        #[arg(long, value_parser = parse_pubkey)]
+        subject: Pubkey,
+        /// Seconds the next wallet has to enrol once a spot is offered
+        #[arg(long, default_value_t = 2 * 24 * 60 * 60, value_parser = clap::value_parser!(i64).range(1..))]
+        offer_seconds: i64,
+    },
+    /// Queue the signer for a spot in a full subject
+    JoinWaitlist {
+        #[arg(long, value_parser = parse_pubkey)]
+        subject: Pubkey,
+    },
+    /// Offer a freed spot to the next wallet on the waitlist
+    OfferSpot {
+        #[arg(long, value_parser = parse_pubkey)]
+        subject: Pubkey,
+    },
+    /// Schedule an intake cohort for a subject as the university admin
+    AddCohort {
+        #[arg(long, value_parser = parse_pubkey)]
+        subject: Pubkey,
+        /// Unix timestamp enrolment opens at
+        #[arg(long)]
+        opens_at: i64,
+        /// Unix timestamp enrolment closes at
+        #[arg(long)]
+        closes_at: i64,
+        /// Unix timestamp the cohort's studies start at
+        #[arg(long)]
+        starts_at: i64,
+    },
+    /// Start a leave of absence for the signer, co-signed by the university admin
+    Leave {
+        #[arg(long, value_parser = parse_pubkey)]
+        subject: Pubkey,
+        /// Keypair of the university admin approving the leave
+        #[arg(long)]
+        uni_admin_keypair: PathBuf,
+    },
+    /// End the signer's leave of absence, co-signed by the university admin
+    Resume {
+        #[arg(long, value_parser = parse_pubkey)]
+        subject: Pubkey,
+        /// Keypair of the university admin approving the return
+        #[arg(long)]
+        uni_admin_keypair: PathBuf,
+    },
+    /// Suspend a student as the university admin, or lift the suspension with --lift
+    Suspend {
+        #[arg(long, value_parser = parse_pubkey)]
+        subject: Pubkey,
+        /// Student wallet
+        #[arg(long, value_parser = parse_pubkey)]
+        student: Pubkey,
+        #[arg(long)]
+        lift: bool,
+        /// Refund the sponsor under the subject's refund policy
+        #[arg(long, conflicts_with = "lift")]
+        refund: bool,
+        /// University admin whose token account pays the refund, the signer by default (staff sign as its delegate)
+        #[arg(long, value_parser = parse_pubkey, requires = "refund")]
+        uni_admin: Option<Pubkey>,
+    },
+    /// Expel a student as the university admin, burning their card
+    Expel {
+        #[arg(long, value_parser = parse_pubkey)]
+        subject: Pubkey,
+        /// Student wallet
+        #[arg(long, value_parser = parse_pubkey)]
+        student: Pubkey,
+        #[arg(long, value_parser = parse_pubkey)]
+        card_mint: Pubkey,
+        /// Refund the sponsor under the subject's refund policy
+        #[arg(long)]
+        refund: bool,
+        /// University admin whose token account pays the refund, the signer by default (staff sign as its delegate)
+        #[arg(long, value_parser = parse_pubkey, requires = "refund")]
+        uni_admin: Option<Pubkey>,
+    },
+    /// Grant staff roles in the signer's university to a wallet
+    AddRole {
+        #[arg(long, value_parser = parse_pubkey)]
+        vire: Pubkey,
+        #[arg(long, value_parser = parse_pubkey)]
+        member: Pubkey,
+        #[command(flatten)]
+        roles: RoleOpts,
+    },
+    /// Take staff roles in the signer's university away from a wallet
+    RemoveRole {
+        #[arg(long, value_parser = parse_pubkey)]
+        vire: Pubkey,
+        #[arg(long, value_parser = parse_pubkey)]
+        member: Pubkey,
+        #[command(flatten)]
+        roles: RoleOpts,
+    },
+    /// Decode and print a Vire, Uni, Subject, Student, Transcript, ScholarshipPool, ScholarshipAward, Discount or Waitlist account
+    Show(ShowArgs),
+}
+
+// Metaplex metadata for the collection or card NFT (none when --name is left out)
+#[derive(Args)]
+struct MetadataOpts {
+    /// Metadata name, creates Metaplex metadata for the NFT
+    #[arg(long, requires_all = ["symbol", "uri"])]
+    name: Option<String>,
+    /// Metadata symbol
+    #[arg(long, requires = "name")]
+    symbol: Option<String>,
+    /// Metadata URI (off-chain JSON)
+    #[arg(long, requires = "name")]
+    uri: Option<String>,
+    /// Store the metadata in Token-2022 extensions on the new mint instead of Metaplex
+    #[arg(long, requires = "name")]
+    token_metadata: bool,
+}
+
+impl MetadataOpts {
+    fn args(&self) -> Result<(MetadataMode, MetadataArgs)> {
+        let (Some(name), Some(symbol), Some(uri)) = (&self.name, &self.symbol, &self.uri) else {
+            return Ok((MetadataMode::None, MetadataArgs::zeroed()));
+        };
+
+        for (field, value, max) in [("name", name, MAX_NAME_LENGTH), ("symbol", symbol, MAX_SYMBOL_LENGTH), ("uri", uri, MAX_URI_LENGTH)] {
+            if value.len() > max {
+                return Err(format!("metadata {field} is longer than {max} bytes").into());
+            }
+        }
+        let mode = if self.token_metadata { MetadataMode::TokenMetadata } else { MetadataMode::Metaplex };
+        Ok((mode, MetadataArgs::new(name, symbol, uri)))
+    }
+}
+
+// Staff roles of AddRole and RemoveRole
+#[derive(Args)]
+#[group(required = true, multiple = true)]
+struct RoleOpts {
+    /// Discounts, scholarships and refunds
+    #[arg(long)]
+    finance: bool,
+    /// Subjects, intakes and student records
+    #[arg(long)]
+    registrar: bool,
+    /// Transcripts
+    #[arg(long)]
+    grader: bool,
+}
+
+impl RoleOpts {
+    fn args(&self) -> RoleArgs {
+        let roles = [(self.finance, ROLE_FINANCE), (self.registrar, ROLE_REGISTRAR), (self.grader, ROLE_GRADER)]
+            .into_iter()
+            .filter(|(set, _)| *set)
+            .fold(0, |roles, (_, role)| roles | role);
+        RoleArgs::new(roles)
+    }
+}
+
+#[derive(Args)]
+#[command(group(ArgGroup::new("source").required(true).args(["address", "file"])))]
+struct ShowArgs {
+    /// Fetch the account from the RPC endpoint
+    #[arg(long, value_parser = parse_pubkey)]
+    address: Option<Pubkey>,
+
+    /// Read the account from a JSON dump (`solana account <ADDRESS> --output json`)
+    #[arg(long)]
+    file: Option<PathBuf>,
+
+    /// vire, uni, subject, student, transcript, scholarship_pool, scholarship_award, discount or waitlist (detected from the data length if omitted)
+    #[arg(long)]
+    kind: Option<String>,
+}
+
+
+pub fn parse_pubkey(value: &str) -> Result<Pubkey> {
+    bs58::decode(value)
+        .into_vec()?
+        .try_into()
+        .map_err(|_| format!("`{value}` is not a 32 byte base58 key").into())
+}
+
+pub fn parse_hash(value: &str) -> Result<[u8; 32]> {
+    let value = value.strip_prefix("0x").unwrap_or(value);
+    if value.len() != 64 || !value.is_ascii() {
+        return Err(format!("`{value}` is not a 32 byte hex hash").into());
+    }
+    let mut hash = [0u8; 32];
+    for (byte, chunk) in hash.iter_mut().zip(value.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(chunk)?, 16)?;
+    }
+    Ok(hash)
+}
+
+fn read_keypair(path: Option<&PathBuf>) -> Result<SigningKey> {
+    let path = match path {
+        Some(path) => path.clone(),
+        None => PathBuf::from(std::env::var("HOME")?).join(".config/solana/id.json"),
+    };
+    let bytes: Vec<u8> = serde_json::from_str(&fs::read_to_string(&path)?)?;
+    let bytes: [u8; 64] = bytes.try_into().map_err(|_| format!("{} is not a 64 byte keypair", path.display()))?;
+    Ok(SigningKey::from_keypair_bytes(&bytes)?)
+}
+
+// New random keypair (for mints the program creates)
+fn generate_keypair() -> Result<SigningKey> {
+    let mut secret = [0u8; 32];
+    fs::File::open("/dev/urandom").and_then(|mut file| std::io::Read::read_exact(&mut file, &mut secret))?;
+    Ok(SigningKey::from_bytes(&secret))
+}
+
+fn fetch_state<T: Pod>(rpc: &RpcClient, address: &Pubkey) -> Result<T> {
+    let account = rpc
+        .get_account(address)?
+        .ok_or_else(|| format!("account {} not found", bs58::encode(address).into_string()))?;
+    bytemuck::try_pod_read_unaligned(&account.data).map_err(|_| "unexpected account data".into())
+}
+
+// Token or Token-2022, whichever owns the mint
+fn token_program_of(rpc: &RpcClient, mint: &Pubkey) -> Result<Pubkey> {
+    let account = rpc
+        .get_account(mint)?
+        .ok_or_else(|| format!("mint {} not found", bs58::encode(mint).into_string()))?;
+    Ok(account.owner)
+}
+
+// Credential NFT mint, either existing (frontend-created) or a new keypair for the program to create
+struct NftMint {
+    pubkey: Pubkey,
+    keypair: Option<SigningKey>,
+    token_program: Pubkey,
+}
+
+impl NftMint {
+    fn resolve(rpc: &RpcClient, mint: Option<Pubkey>, keypair: Option<&PathBuf>, token_2022: bool) -> Result<Self> {
+        match (mint, keypair) {
+            (Some(mint), _) => Ok(Self { pubkey: mint, keypair: None, token_program: token_program_of(rpc, &mint)? }),
+            (_, Some(path)) => {
+                let keypair = read_keypair(Some(path))?;
+                let token_program = if token_2022 { TOKEN_2022_PROGRAM_ID } else { pinocchio_token::ID };
+                Ok(Self { pubkey: keypair.verifying_key().to_bytes(), keypair: Some(keypair), token_program })
+            }
+            _ => Err("missing NFT mint".into()),
+        }
+    }
+
+    // Token-2022 metadata extensions can only be added while the program creates the mint
+    fn check_metadata_mode(&self, mode: MetadataMode) -> Result<()> {
+        if mode == MetadataMode::TokenMetadata && (self.keypair.is_none() || self.token_program != TOKEN_2022_PROGRAM_ID) {
+            return Err("--token-metadata needs a new mint keypair and --token-2022".into());
+        }
+        Ok(())
+    }
+
+    fn mode(&self) -> MintMode {
+        if self.keypair.is_some() { MintMode::OnChain } else { MintMode::Frontend }
+    }
+
+    // On-chain mode: the new mint signs, the program creates the owner's ATA itself
+    fn prepare(&self, ix: &mut Instruction, payer: &Pubkey, owner_ata: &Pubkey) -> Vec<Instruction> {
+        if self.keypair.is_some() {
+            for meta in ix.accounts.iter_mut().filter(|meta| meta.pubkey == self.pubkey) {
+                meta.is_signer = true;
+            }
+            return vec![];
+        }
+        vec![create_ata_idempotent(payer, owner_ata, payer, &self.pubkey, &self.token_program)]
+    }
+}
+
+// Prerequisites are proven by the wallet's StudentAccount in each of them
+fn prerequisite_proofs(subject: &SubjectAccount, wallet: &Pubkey, program_id: &Pubkey) -> Vec<AccountMeta> {
+    subject.prerequisites[..subject.prerequisite_count as usize]
+        .iter()
+        .map(|prerequisite| {
+            let (proof, _) = pda::student_account(wallet, prerequisite, program_id);
+            AccountMeta { pubkey: proof, is_signer: false, is_writable: false }
+        })
+        .collect()
+}
+
+fn send(rpc: &RpcClient, signers: &[&SigningKey], instructions: &[Instruction]) -> Result<()> {
+    let blockhash = rpc.get_latest_blockhash()?;
+    let transaction = transaction::sign_transaction(instructions, signers, &blockhash)?;
+    println!("signature: {}", rpc.send_and_confirm(&transaction)?);
+    Ok(())
+}
+
+// Refunds come out of the university admin's token account and go to the refunded semester's sponsor
+fn refund_accounts(rpc: &RpcClient, subject: &SubjectAccount, student_account: &Pubkey, uni_admin: &Pubkey) -> Result<Vec<(&'static str, Pubkey)>> {
+    let student_data: StudentAccount = fetch_state(rpc, student_account)?;
+    let mint = subject.payment_mint;
+    let token_program = token_program_of(rpc, &mint)?;
+    Ok(vec![
+        ("mint_usdc", mint),
+        ("uni_ata_usdc", pda::associated_token_address(uni_admin, &mint, &token_program)),
+        ("sponsor_ata", pda::associated_token_address(&student_data.refundable_payment().sponsor, &mint, &token_program)),
+        ("token_program", token_program),
+    ])
+}
+
+// RequestLeave and ResumeStudies, signed by the student and the university admin
+fn send_leave(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    signer: &SigningKey,
+    subject: &Pubkey,
+    uni_admin_keypair: &PathBuf,
+    instruction: VireInstruction,
+) -> Result<()> {
+    let wallet = signer.verifying_key().to_bytes();
+    let subject_data: SubjectAccount = fetch_state(rpc, subject)?;
+    let uni_admin = read_keypair(Some(uni_admin_keypair))?;
+    let ix = vire_instruction(
+        program_id,
+        instruction,
+        &[
+            ("student", wallet),
+            ("uni_admin", uni_admin.verifying_key().to_bytes()),
+            ("uni_account", subject_data.uni_key),
+            ("subject_account", *subject),
+            ("student_account", pda::student_account(&wallet, subject, program_id).0),
+        ],
+        &[],
+    )?;
+    send(rpc, &[signer, &uni_admin], &[ix])
+}
+
+// ApproveUni, SuspendUni and ReinstateUni, signed by the Vire admin
+fn send_uni_status(rpc: &RpcClient, program_id: &Pubkey, signer: &SigningKey, uni: &Pubkey, instruction: VireInstruction) -> Result<()> {
+    let uni_data: UniAccount = fetch_state(rpc, uni)?;
+    let ix = vire_instruction(
+        program_id,
+        instruction,
+        &[("admin", signer.verifying_key().to_bytes()), ("vire_account", uni_data.vire_key), ("uni_account", *uni)],
+        &[],
+    )?;
+    send(rpc, &[signer], &[ix])
+}
+
+// AddRole and RemoveRole, signed by the university admin
+fn send_role(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    signer: &SigningKey,
+    vire: &Pubkey,
+    member: &Pubkey,
+    roles: &RoleOpts,
+    instruction: VireInstruction,
+) -> Result<()> {
+    let wallet = signer.verifying_key().to_bytes();
+    let (uni_account, _) = pda::uni_account(&wallet, vire, program_id);
+    let ix = vire_instruction(
+        program_id,
+        instruction,
+        &[("uni_admin", wallet), ("uni_account", uni_account), ("member", *member)],
+        bytemuck::bytes_of(&roles.args()),
+    )?;
+    send(rpc, &[signer], &[ix])
+}
+
+// Client side of VerifyCredential: simulates the instruction and decodes its return data
+fn verify_credential(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    payer: &SigningKey,
+    student: &Pubkey,
+    subject: &Pubkey,
+) -> Result<CredentialStatus> {
+    let (student_account, _) = pda::student_account(student, subject, program_id);
+    let diploma_mint = match rpc.get_account(&student_account)? {
+        Some(account) if account.owner == *program_id => {
+            bytemuck::try_pod_read_unaligned::<StudentAccount>(&account.data)
+                .map_err(|_| "unexpected student account data")?
+                .diploma_mint
+        }
+        _ => Pubkey::default(),
+    };
+
+    let ix = vire_instruction(
+        program_id,
+        VireInstruction::VerifyCredential,
+        &[
+            ("student", *student),
+            ("student_account", student_account),
+            ("subject_account", *subject),
+            ("student_diploma_ata", pda::associated_token_address(student, &diploma_mint, &TOKEN_2022_PROGRAM_ID)),
+        ],
+        &[],
+    )?;
+    let transaction = transaction::sign_transaction(&[ix], &[payer], &rpc.get_latest_blockhash()?)?;
+    let data = rpc.simulate_return_data(&transaction)?.ok_or("VerifyCredential returned no data")?;
+    bytemuck::try_pod_read_unaligned(&data).map_err(|_| "unexpected return data".into())
+}
+
+fn print_address(label: &str, address: &Pubkey) {
+    println!("{label}: {}", bs58::encode(address).into_string());
+}
+
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let rpc = RpcClient::new(cli.url);
+    let program_id = cli.program_id.unwrap_or(escrow_bytemuck::ID);
+
+    if let Command::Show(args) = &cli.command {
+        let (address, account) = match (&args.address, &args.file) {
+            (Some(address), _) => (
+                Some(bs58::encode(address).into_string()),
+                rpc.get_account(address)?.ok_or("account not found")?,
+            ),
+            (_, Some(file)) => {
+                let dump: serde_json::Value = serde_json::from_str(&fs::read_to_string(file)?)?;
+                (dump["pubkey"].as_str().map(str::to_string), rpc::parse_account(&dump)?)
+            }
+            _ => unreachable!(),
+        };
+        return display::print_account(address.as_deref(), &account, args.kind.as_deref());
+    }
+
+    let signer = read_keypair(cli.keypair.as_ref())?;
+    let wallet = signer.verifying_key().to_bytes();
+
+    match cli.command {
+        Command::InitVire { mint, treasury, fee_uni, fee_student } => {
+            let (vire_account, bump) = pda::vire_account(&wallet, &program_id);
+            let args = VireArgs::new(fee_uni, fee_student, bump);
+            let ix = vire_instruction(
+                &program_id,
+                VireInstruction::InitializeVire,
+                &[
+                    ("admin", wallet),
+                    ("mint_usdc", mint),
+                    ("vire_account", vire_account),
+                    ("treasury", treasury),
+                    ("token_program", token_program_of(&rpc, &mint)?),
+                ],
+                bytemuck::bytes_of(&args),
+            )?;
+            send(&rpc, &[&signer], &[ix])?;
+            print_address("vire_account", &vire_account);
+        }
+        Command::UpdatePaymentMint { mint, remove } => {
+            let (vire_account, _) = pda::vire_account(&wallet, &program_id);
+            let ix = vire_instruction(
+                &program_id,
+                VireInstruction::UpdatePaymentMint,
+                &[("admin", wallet), ("vire_account", vire_account), ("payment_mint", mint)],
+                bytemuck::bytes_of(&PaymentMintArgs::new(!remove)),
+            )?;
+            send(&rpc, &[&signer], &[ix])?;
+        }
+        Command::ApproveUni { uni } => {
+            send_uni_status(&rpc, &program_id, &signer, &uni, VireInstruction::ApproveUni)?;
+        }
+        Command::SuspendUni { uni } => {
+            send_uni_status(&rpc, &program_id, &signer, &uni, VireInstruction::SuspendUni)?;
+        }
+        Command::ReinstateUni { uni } => {
+            send_uni_status(&rpc, &program_id, &signer, &uni, VireInstruction::ReinstateUni)?;
+        }
+        Command::InitUni { vire } => {
+            let (uni_account, bump) = pda::uni_account(&wallet, &vire, &program_id);
+            let ix = vire_instruction(
+                &program_id,
+                VireInstruction::InitializeUni,
+                &[("uni_admin", wallet), ("uni_account", uni_account), ("vire_account", vire)],
+                bytemuck::bytes_of(&UniArgs::new(bump)),
+            )?;
+            send(&rpc, &[&signer], &[ix])?;
+            print_address("uni_account", &uni_account);
+        }
+        Command::AddSubject {
+            vire,
+            uni,
+            mint,
+            treasury,
+            collection_mint,
+            collection_mint_keypair,
+            token_2022,
+            tution_fee,
+            max_semester,
+            semester_months,
+            min_grade,
+            require_approval,
+            payment_window_days,
+            late_fee,
+            installments,
+            max_students,
+            prerequisites,
+            transfer_credit,
+            refund_percent,
+            metadata,
+        } => {
+            let uni_account = uni.unwrap_or_else(|| pda::uni_account(&wallet, &vire, &program_id).0);
+            let uni: UniAccount = fetch_state(&rpc, &uni_account)?;
+            let (subject_account, bump) =
+                pda::subject_account(&uni_account, u64::from_le_bytes(uni.subject_number), &program_id);
+            let token_program = token_program_of(&rpc, &mint)?;
+            let collection_mint = NftMint::resolve(&rpc, collection_mint, collection_mint_keypair.as_ref(), token_2022)?;
+            let collection_token_program = collection_mint.token_program;
+            let uni_collection_ata =
+                pda::associated_token_address(&wallet, &collection_mint.pubkey, &collection_token_program);
+
+            let (metadata_mode, metadata) = metadata.args()?;
+            collection_mint.check_metadata_mode(metadata_mode)?;
+
+            let args =
+                SubjectArgs::new(tution_fee, max_semester, semester_months, collection_mint.mode(), metadata_mode, metadata, bump)
+                    .with_min_grade(min_grade)
+                    .with_semester_approval(require_approval)
+                    .with_payment_window(payment_window_days, late_fee)
+                    .with_installments(installments)
+                    .with_capacity(max_students)
+                    .with_prerequisites(&prerequisites)
+                    .with_transfer_credit(transfer_credit)
+                    .with_refund_policy(refund_percent);
+            let mut accounts = vec![
+                ("uni_admin", wallet),
+                ("mint_usdc", mint),
+                ("subject_account", subject_account),
+                ("uni_account", uni_account),
+                ("uni_ata_usdc", pda::associated_token_address(&wallet, &mint, &token_program)),
+                ("vire_account", vire),
+                ("treasury", treasury),
+                ("collection_mint", collection_mint.pubkey),
+                ("uni_collection_ata", uni_collection_ata),
+                ("token_program", token_program),
+                ("collection_token_program", collection_token_program),
+            ];
+            if metadata_mode == MetadataMode::Metaplex {
+                accounts.extend([
+                    ("collection_metadata", pda::metadata_account(&collection_mint.pubkey)),
+                    ("collection_master_edition", pda::master_edition(&collection_mint.pubkey)),
+                ]);
+            }
+            let mut ix = vire_instruction(&program_id, VireInstruction::AddSubjects, &accounts, bytemuck::bytes_of(&args))?;
+            let mut instructions = collection_mint.prepare(&mut ix, &wallet, &uni_collection_ata);
+            instructions.push(ix);
+            let signers: Vec<&SigningKey> = [Some(&signer), collection_mint.keypair.as_ref()].into_iter().flatten().collect();
+            send(&rpc, &signers, &instructions)?;
+            print_address("subject_account", &subject_account);
+        }
+        Command::Enrol { subject, card_mint, card_mint_keypair, token_2022, metadata } => {
+            let subject_data: SubjectAccount = fetch_state(&rpc, &subject)?;
+            let uni: UniAccount = fetch_state(&rpc, &subject_data.uni_key)?;
+            let (student_account, bump) = pda::student_account(&wallet, &subject, &program_id);
+            let card_mint = NftMint::resolve(&rpc, card_mint, card_mint_keypair.as_ref(), token_2022)?;
+            let token_program = card_mint.token_program;
+            let student_card_ata = pda::associated_token_address(&wallet, &card_mint.pubkey, &token_program);
+            let (metadata_mode, metadata) = metadata.args()?;
+            card_mint.check_metadata_mode(metadata_mode)?;
+
+            let mut accounts = vec![
+                ("student", wallet),
+                ("student_account", student_account),
+                ("subject_account", subject),
+                ("uni_account", subject_data.uni_key),
+                ("vire_account", uni.vire_key),
+                ("card_mint", card_mint.pubkey),
+                ("student_card_ata", student_card_ata),
+                ("token_program", token_program),
+            ];
+            if metadata_mode == MetadataMode::Metaplex {
+                let collection_mint = subject_data.collection_mint;
+                accounts.extend([
+                    ("card_metadata", pda::metadata_account(&card_mint.pubkey)),
+                    ("collection_mint", collection_mint),
+                    ("collection_metadata", pda::metadata_account(&collection_mint)),
+                    ("collection_master_edition", pda::master_edition(&collection_mint)),
+                ]);
+            }
+            let args = StudentArgs::new(card_mint.mode(), metadata_mode, metadata, bump);
+            let mut ix = vire_instruction(&program_id, VireInstruction::InitializeStudent, &accounts, bytemuck::bytes_of(&args))?;
+            ix.accounts.extend(prerequisite_proofs(&subject_data, &wallet, &program_id));
+            let mut instructions = card_mint.prepare(&mut ix, &wallet, &student_card_ata);
+            instructions.push(ix);
+            let signers: Vec<&SigningKey> = [Some(&signer), card_mint.keypair.as_ref()].into_iter().flatten().collect();
+            send(&rpc, &signers, &instructions)?;
+            print_address("student_account", &student_account);
+        }
+        Command::Transfer { from, to, card_mint, new_card_mint, new_card_mint_keypair, token_2022, metadata } => {
+            let subject_data: SubjectAccount = fetch_state(&rpc, &to)?;
+            let (student_account, _) = pda::student_account(&wallet, &from, &program_id);
+            let (new_student_account, bump) = pda::student_account(&wallet, &to, &program_id);
+            let card_token_program = token_program_of(&rpc, &card_mint)?;
+            let new_card_mint = NftMint::resolve(&rpc, new_card_mint, new_card_mint_keypair.as_ref(), token_2022)?;
+            let token_program = new_card_mint.token_program;
+            let new_student_card_ata = pda::associated_token_address(&wallet, &new_card_mint.pubkey, &token_program);
+            let (metadata_mode, metadata) = metadata.args()?;
+            new_card_mint.check_metadata_mode(metadata_mode)?;
+
+            let mut accounts = vec![
+                ("student", wallet),
+                ("student_account", student_account),
+                ("subject_account", from),
+                ("new_student_account", new_student_account),
+                ("new_subject_account", to),
+                ("uni_account", subject_data.uni_key),
+                ("card_mint", card_mint),
+                ("student_card_ata", pda::associated_token_address(&wallet, &card_mint, &card_token_program)),
+                ("new_card_mint", new_card_mint.pubkey),
+                ("new_student_card_ata", new_student_card_ata),
+                ("card_token_program", card_token_program),
+                ("token_program", token_program),
+                ("transcript_account", pda::transcript_account(&student_account, &program_id).0),
+                ("new_transcript_account", pda::transcript_account(&new_student_account, &program_id).0),
+            ];
+            if metadata_mode == MetadataMode::Metaplex {
+                let collection_mint = subject_data.collection_mint;
+                accounts.extend([
+                    ("card_metadata", pda::metadata_account(&new_card_mint.pubkey)),
+                    ("collection_mint", collection_mint),
+                    ("collection_metadata", pda::metadata_account(&collection_mint)),
+                    ("collection_master_edition", pda::master_edition(&collection_mint)),
+                ]);
+            }
+            let args = StudentArgs::new(new_card_mint.mode(), metadata_mode, metadata, bump);
+            let mut ix = vire_instruction(&program_id, VireInstruction::TransferEnrollment, &accounts, bytemuck::bytes_of(&args))?;
+            ix.accounts.extend(prerequisite_proofs(&subject_data, &wallet, &program_id));
+            let mut instructions = new_card_mint.prepare(&mut ix, &wallet, &new_student_card_ata);
+            instructions.push(ix);
+            let signers: Vec<&SigningKey> = [Some(&signer), new_card_mint.keypair.as_ref()].into_iter().flatten().collect();
+            send(&rpc, &signers, &instructions)?;
+            print_address("student_account", &new_student_account);
+        }
+        Command::Pay { subject, uni_admin, treasury, student, scholarship_pool, discount } => {
+            let subject_data: SubjectAccount = fetch_state(&rpc, &subject)?;
+            let mint = subject_data.payment_mint;
+            let token_program = token_program_of(&rpc, &mint)?;
+            let uni: UniAccount = fetch_state(&rpc, &subject_data.uni_key)?;
+            let (student_account, _) = pda::student_account(&student.unwrap_or(wallet), &subject, &program_id);
+            let payer_ata = pda::associated_token_address(&wallet, &mint, &token_program);
+
+            let mut accounts = vec![
+                ("mint_usdc", mint),
+                ("student_account", student_account),
+                ("subject_account", subject),
+                ("uni_account", subject_data.uni_key),
+                ("uni_ata_usdc", pda::associated_token_address(&uni_admin, &mint, &token_program)),
+                ("vire_account", uni.vire_key),
+                ("treasury", treasury),
+                ("token_program", token_program),
+            ];
+            if let Some(discount) = discount {
+                accounts.push(("discount_account", discount));
+            }
+            if let Some(pool) = scholarship_pool {
+                let pool_data: ScholarshipPool = fetch_state(&rpc, &pool)?;
+                accounts.extend([
+                    ("scholarship_pool", pool),
+                    ("scholarship_award", pda::scholarship_award(&pool, &student_account, &program_id).0),
+                    ("scholarship_vault", pool_data.vault),
+                ]);
+            }
+            let instruction = match student {
+                Some(_) => {
+                    accounts.extend([("sponsor", wallet), ("sponsor_ata_usdc", payer_ata)]);
+                    VireInstruction::SponsorPayTuition
+                }
+                None => {
+                    accounts.extend([("student", wallet), ("uni_admin", uni_admin), ("student_ata_usdc", payer_ata)]);
+                    VireInstruction::PayTutionFee
+                }
+            };
+            let ix = vire_instruction(&program_id, instruction, &accounts, &[])?;
+            send(&rpc, &[&signer], &[ix])?;
+        }
+        Command::Unstake { subject, card_mint, diploma_mint_keypair } => {
+            let subject_data: SubjectAccount = fetch_state(&rpc, &subject)?;
+            let (student_account, _) = pda::student_account(&wallet, &subject, &program_id);
+            let token_program = token_program_of(&rpc, &card_mint)?;
+            let diploma_mint = match diploma_mint_keypair {
+                Some(path) => read_keypair(Some(&path))?,
+                None => generate_keypair()?,
+            };
+            let diploma_mint_key = diploma_mint.verifying_key().to_bytes();
+
+            let mut accounts = vec![
+                ("student", wallet),
+                ("student_account", student_account),
+                ("subject_account", subject),
+                ("card_mint", card_mint),
+                ("student_card_ata", pda::associated_token_address(&wallet, &card_mint, &token_program)),
+                ("diploma_mint", diploma_mint_key),
+                (
+                    "student_diploma_ata",
+                    pda::associated_token_address(&wallet, &diploma_mint_key, &TOKEN_2022_PROGRAM_ID),
+                ),
+                ("token_program", token_program),
+            ];
+            if u64::from_le_bytes(subject_data.min_grade) > 0 {
+                accounts.push(("transcript_account", pda::transcript_account(&student_account, &program_id).0));
+            }
+            let ix = vire_instruction(&program_id, VireInstruction::UnStake, &accounts, &[])?;
+            send(&rpc, &[&signer, &diploma_mint], &[ix])?;
+            print_address("diploma_mint", &diploma_mint_key);
+        }
+        Command::Verify { student, subject } => {
+            let status = verify_credential(&rpc, &program_id, &signer, &student, &subject)?;
+            let state = match status.state().map_err(|_| "unknown credential state")? {
+                CredentialState::NotEnrolled => "not enrolled",
+                CredentialState::Enrolled => "enrolled",
+                CredentialState::Graduated => "graduated",
+                CredentialState::Revoked => "revoked",
+                CredentialState::Expelled => "expelled",
+            };
+            println!("state: {state}");
+            println!(
+                "paid semesters: {}/{}",
+                u64::from_le_bytes(status.semesters).saturating_sub(1),
+                u64::from_le_bytes(status.max_semester)
+            );
+            println!("graduated_at: {}", i64::from_le_bytes(status.graduated_at));
+            print_address("diploma_mint", &status.diploma_mint);
+            println!("holds_diploma: {}", status.holds_diploma != 0);
+            if status.state == CredentialState::Revoked as u8 {
+                println!("revoked_at: {}", i64::from_le_bytes(status.revoked_at));
+                println!("revocation_reason: {}", status.revocation_reason);
+            }
+        }
+        Command::Revoke { subject, student, reason } => {
+            let subject_data: SubjectAccount = fetch_state(&rpc, &subject)?;
+            let (student_account, _) = pda::student_account(&student, &subject, &program_id);
+            let student_data: StudentAccount = fetch_state(&rpc, &student_account)?;
+            let diploma_mint = student_data.diploma_mint;
+
+            let ix = vire_instruction(
+                &program_id,
+                VireInstruction::RevokeCredential,
+                &[
+                    ("uni_admin", wallet),
+                    ("uni_account", subject_data.uni_key),
+                    ("subject_account", subject),
+                    ("student_account", student_account),
+                    ("diploma_mint", diploma_mint),
+                    ("student_diploma_ata", pda::associated_token_address(&student, &diploma_mint, &TOKEN_2022_PROGRAM_ID)),
+                ],
+                bytemuck::bytes_of(&RevokeArgs::new(reason)),
+            )?;
+            send(&rpc, &[&signer], &[ix])?;
+        }
+        Command::RecordGrades { subject, student, semester, grade, credits, transcript_hash } => {
+            let subject_data: SubjectAccount = fetch_state(&rpc, &subject)?;
+            let (student_account, _) = pda::student_account(&student, &subject, &program_id);
+            let (transcript_account, bump) = pda::transcript_account(&student_account, &program_id);
+
+            let ix = vire_instruction(
+                &program_id,
+                VireInstruction::RecordGrades,
+                &[
+                    ("grader", wallet),
+                    ("uni_account", subject_data.uni_key),
+                    ("subject_account", subject),
+                    ("student_account", student_account),
+                    ("transcript_account", transcript_account),
+                ],
+                bytemuck::bytes_of(&GradeArgs::new(semester, grade, credits, transcript_hash, bump)),
+            )?;
+            send(&rpc, &[&signer], &[ix])?;
+            print_address("transcript_account", &transcript_account);
+        }
+        Command::ApproveSemester { subject, student, semester } => {
+            let subject_data: SubjectAccount = fetch_state(&rpc, &subject)?;
+            let (student_account, _) = pda::student_account(&student, &subject, &program_id);
+
+            let ix = vire_instruction(
+                &program_id,
+                VireInstruction::ApproveSemester,
+                &[
+                    ("uni_admin", wallet),
+                    ("uni_account", subject_data.uni_key),
+                    ("subject_account", subject),
+                    ("student_account", student_account),
+                ],
+                bytemuck::bytes_of(&ApproveSemesterArgs::new(semester)),
+            )?;
+            send(&rpc, &[&signer], &[ix])?;
+        }
+        Command::InitScholarship { vire, uni, subject, mint } => {
+            let uni_account = uni.unwrap_or_else(|| pda::uni_account(&wallet, &vire, &program_id).0);
+            let scope = subject.unwrap_or(uni_account);
+            let (scholarship_pool, bump) = pda::scholarship_pool(&scope, &program_id);
+            let token_program = token_program_of(&rpc, &mint)?;
+            let vault = pda::associated_token_address(&scholarship_pool, &mint, &token_program);
+
+            let ix = vire_instruction(
+                &program_id,
+                VireInstruction::InitializeScholarshipPool,
+                &[
+                    ("uni_admin", wallet),
+                    ("uni_account", uni_account),
+                    ("scope", scope),
+                    ("scholarship_pool", scholarship_pool),
+                    ("mint", mint),
+                    ("vault", vault),
+                    ("token_program", token_program),
+                ],
+                bytemuck::bytes_of(&PoolArgs::new(bump)),
+            )?;
+            send(&rpc, &[&signer], &[ix])?;
+            print_address("scholarship_pool", &scholarship_pool);
+            print_address("vault", &vault);
+        }
+        Command::Donate { pool, amount } => {
+            let pool_data: ScholarshipPool = fetch_state(&rpc, &pool)?;
+            let token_program = token_program_of(&rpc, &pool_data.mint)?;
+
+            let ix = vire_instruction(
+                &program_id,
+                VireInstruction::DepositScholarship,
+                &[
+                    ("donor", wallet),
+                    ("uni_account", pool_data.uni_key),
+                    ("scholarship_pool", pool),
+                    ("mint", pool_data.mint),
+                    ("donor_ata", pda::associated_token_address(&wallet, &pool_data.mint, &token_program)),
+                    ("vault", pool_data.vault),
+                    ("token_program", token_program),
+                ],
+                bytemuck::bytes_of(&DepositArgs::new(amount)),
+            )?;
+            send(&rpc, &[&signer], &[ix])?;
+        }
+        Command::Award { pool, subject, student, amount } => {
+            let pool_data: ScholarshipPool = fetch_state(&rpc, &pool)?;
+            let (student_account, _) = pda::student_account(&student, &subject, &program_id);
+            let (scholarship_award, bump) = pda::scholarship_award(&pool, &student_account, &program_id);
+
+            let ix = vire_instruction(
+                &program_id,
+                VireInstruction::AwardScholarship,
+                &[
+                    ("uni_admin", wallet),
+                    ("uni_account", pool_data.uni_key),
+                    ("scholarship_pool", pool),
+                    ("subject_account", subject),
+                    ("student_account", student_account),
+                    ("scholarship_award", scholarship_award),
+                ],
+                bytemuck::bytes_of(&AwardArgs::new(amount, bump)),
+            )?;
+            send(&rpc, &[&signer], &[ix])?;
+            print_address("scholarship_award", &scholarship_award);
+        }
+        Command::CreateDiscount { subject, id, percent, fixed, expires_at, max_uses, student } => {
+            let subject_data: SubjectAccount = fetch_state(&rpc, &subject)?;
+            let (discount_account, bump) = pda::discount_account(&subject, id, &program_id);
+            let (kind, value) = match (percent, fixed) {
+                (Some(percent), _) => (DiscountKind::Percent, percent),
+                (_, Some(fixed)) => (DiscountKind::Fixed, fixed),
+                _ => unreachable!(),
+            };
+            let args = DiscountArgs::new(id, kind, value, expires_at, max_uses, student.unwrap_or_default(), bump);
+
+            let ix = vire_instruction(
+                &program_id,
+                VireInstruction::CreateDiscount,
+                &[
+                    ("uni_admin", wallet),
+                    ("uni_account", subject_data.uni_key),
+                    ("subject_account", subject),
+                    ("discount_account", discount_account),
+                ],
+                bytemuck::bytes_of(&args),
+            )?;
+            send(&rpc, &[&signer], &[ix])?;
+            print_address("discount_account", &discount_account);
+        }
+        Command::CreateWaitlist { subject, offer_seconds } => {
+            let subject_data: SubjectAccount = fetch_state(&rpc, &subject)?;
+            let (waitlist_account, bump) = pda::waitlist_account(&subject, &program_id);
+
+            let ix = vire_instruction(
+                &program_id,
+                VireInstruction::CreateWaitlist,
+                &[
+                    ("uni_admin", wallet),
+                    ("uni_account", subject_data.uni_key),
+                    ("subject_account", subject),
+                    ("waitlist_account", waitlist_account),
+                ],
+                bytemuck::bytes_of(&WaitlistArgs::new(offer_seconds, bump)),
+            )?;
+            send(&rpc, &[&signer], &[ix])?;
+            print_address("waitlist_account", &waitlist_account);
+        }
+        Command::JoinWaitlist { subject } => {
+            let subject_data: SubjectAccount = fetch_state(&rpc, &subject)?;
+            let ix = vire_instruction(
+                &program_id,
+                VireInstruction::JoinWaitlist,
+                &[
+                    ("student", wallet),
+                    ("uni_account", subject_data.uni_key),
+                    ("subject_account", subject),
+                    ("waitlist_account", pda::waitlist_account(&subject, &program_id).0),
+                ],
+                &[],
+            )?;
+            send(&rpc, &[&signer], &[ix])?;
+        }
+        Command::OfferSpot { subject } => {
+            let subject_data: SubjectAccount = fetch_state(&rpc, &subject)?;
+            let ix = vire_instruction(
+                &program_id,
+                VireInstruction::OfferWaitlistSpot,
+                &[
+                    ("uni_account", subject_data.uni_key),
+                    ("subject_account", subject),
+                    ("waitlist_account", pda::waitlist_account(&subject, &program_id).0),
+                ],
+                &[],
+            )?;
+            send(&rpc, &[&signer], &[ix])?;
+            let subject_data: SubjectAccount = fetch_state(&rpc, &subject)?;
+            print_address("offered_student", &subject_data.offered_student);
+            println!("offer_expires_at: {}", i64::from_le_bytes(subject_data.offer_expires_at));
+        }
+        Command::AddCohort { subject, opens_at, closes_at, starts_at } => {
+            let subject_data: SubjectAccount = fetch_state(&rpc, &subject)?;
+            let ix = vire_instruction(
+                &program_id,
+                VireInstruction::AddIntakeCohort,
+                &[("uni_admin", wallet), ("uni_account", subject_data.uni_key), ("subject_account", subject)],
+                bytemuck::bytes_of(&CohortArgs::new(opens_at, closes_at, starts_at)),
+            )?;
+            send(&rpc, &[&signer], &[ix])?;
+        }
+        Command::Leave { subject, uni_admin_keypair } => {
+            send_leave(&rpc, &program_id, &signer, &subject, &uni_admin_keypair, VireInstruction::RequestLeave)?;
+        }
+        Command::Resume { subject, uni_admin_keypair } => {
+            send_leave(&rpc, &program_id, &signer, &subject, &uni_admin_keypair, VireInstruction::ResumeStudies)?;
+        }
+        Command::Suspend { subject, student, lift, refund, uni_admin } => {
+            let subject_data: SubjectAccount = fetch_state(&rpc, &subject)?;
+            let (student_account, _) = pda::student_account(&student, &subject, &program_id);
+
+            let mut accounts = vec![
+                ("uni_admin", wallet),
+                ("uni_account", subject_data.uni_key),
+                ("subject_account", subject),
+                ("student_account", student_account),
+            ];
+            if refund {
+                accounts.extend(refund_accounts(&rpc, &subject_data, &student_account, &uni_admin.unwrap_or(wallet))?);
+            }
+            let ix = vire_instruction(
+                &program_id,
+                VireInstruction::SuspendStudent,
+                &accounts,
+                bytemuck::bytes_of(&SuspendArgs::new(!lift, refund)),
+            )?;
+            send(&rpc, &[&signer], &[ix])?;
+        }
+        Command::Expel { subject, student, card_mint, refund, uni_admin } => {
+            let subject_data: SubjectAccount = fetch_state(&rpc, &subject)?;
+            let (student_account, _) = pda::student_account(&student, &subject, &program_id);
+            let card_token_program = token_program_of(&rpc, &card_mint)?;
+
+            let mut accounts = vec![
+                ("uni_admin", wallet),
+                ("uni_account", subject_data.uni_key),
+                ("subject_account", subject),
+                ("student_account", student_account),
+                ("card_mint", card_mint),
+                ("student_card_ata", pda::associated_token_address(&student, &card_mint, &card_token_program)),
+                ("card_token_program", card_token_program),
+            ];
+            if refund {
+                accounts.extend(refund_accounts(&rpc, &subject_data, &student_account, &uni_admin.unwrap_or(wallet))?);
+            }
+            let ix = vire_instruction(&program_id, VireInstruction::ExpelStudent, &accounts, bytemuck::bytes_of(&ExpelArgs::new(refund)))?;
+            send(&rpc, &[&signer], &[ix])?;
+        }
+        Command::AddRole { vire, member, roles } => {
+            send_role(&rpc, &program_id, &signer, &vire, &member, &roles, VireInstruction::AddRole)?;
+        }
+        Command::RemoveRole { vire, member, roles } => {
+            send_role(&rpc, &program_id, &signer, &vire, &member, &roles, VireInstruction::RemoveRole)?;
+        }
+        Command::Show(_) => unreachable!(),
+    }
+
+    Ok(())
+}
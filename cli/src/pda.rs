@@ -0,0 +1,76 @@
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use escrow_bytemuck::token_metadata::TOKEN_METADATA_PROGRAM_ID;
+use pinocchio::pubkey::Pubkey;
+use sha2::{Digest, Sha256};
+
+pub const ATA_PROGRAM_ID: Pubkey = pinocchio_pubkey::pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+// Off-chain version of pubkey::find_program_address (the pinocchio one is only available on-chain)
+pub fn find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8) {
+    for bump in (0..=u8::MAX).rev() {
+        let mut hasher = Sha256::new();
+        for seed in seeds {
+            hasher.update(seed);
+        }
+        hasher.update([bump]);
+        hasher.update(program_id);
+        hasher.update(b"ProgramDerivedAddress");
+        let address: Pubkey = hasher.finalize().into();
+
+        // a valid PDA must not be a point on the ed25519 curve
+        if CompressedEdwardsY(address).decompress().is_none() {
+            return (address, bump);
+        }
+    }
+    panic!("no viable bump seed found");
+}
+
+pub fn vire_account(admin: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[b"vire", admin], program_id)
+}
+
+pub fn uni_account(uni_admin: &Pubkey, vire_account: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[uni_admin, vire_account], program_id)
+}
+
+pub fn subject_account(uni_account: &Pubkey, subject_number: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+    // the program uses the subject number truncated to a single byte as seed
+    find_program_address(&[uni_account, &[subject_number as u8]], program_id)
+}
+
+pub fn student_account(student: &Pubkey, subject_account: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[student, subject_account], program_id)
+}
+
+pub fn transcript_account(student_account: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[b"transcript", student_account], program_id)
+}
+
+// scope is the uni_account (university-wide pool) or a subject_account
+pub fn scholarship_pool(scope: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[b"scholarship", scope], program_id)
+}
+
+pub fn scholarship_award(scholarship_pool: &Pubkey, student_account: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[b"award", scholarship_pool, student_account], program_id)
+}
+
+pub fn discount_account(subject_account: &Pubkey, discount_id: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[b"discount", subject_account, &discount_id.to_le_bytes()], program_id)
+}
+
+pub fn waitlist_account(subject_account: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[b"waitlist", subject_account], program_id)
+}
+
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    find_program_address(&[owner, token_program, mint], &ATA_PROGRAM_ID).0
+}
+
+pub fn metadata_account(mint: &Pubkey) -> Pubkey {
+    find_program_address(&[b"metadata", &TOKEN_METADATA_PROGRAM_ID, mint], &TOKEN_METADATA_PROGRAM_ID).0
+}
+
+pub fn master_edition(mint: &Pubkey) -> Pubkey {
+    find_program_address(&[b"metadata", &TOKEN_METADATA_PROGRAM_ID, mint, b"edition"], &TOKEN_METADATA_PROGRAM_ID).0
+}
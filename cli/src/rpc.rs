@@ -0,0 +1,116 @@
+use std::{thread, time::Duration};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use pinocchio::pubkey::Pubkey;
+use serde_json::{json, Value};
+
+use crate::Result;
+
+
+pub struct Account {
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+}
+
+// Minimal JSON-RPC client for a (local) validator
+pub struct RpcClient {
+    url: String,
+}
+
+impl RpcClient {
+    pub fn new(url: String) -> Self {
+        Self { url }
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let response: Value = ureq::post(&self.url)
+            .send_json(json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))?
+            .into_json()?;
+
+        if let Some(error) = response.get("error") {
+            return Err(format!("{method} failed: {error}").into());
+        }
+        Ok(response["result"].clone())
+    }
+
+    pub fn get_latest_blockhash(&self) -> Result<[u8; 32]> {
+        let result = self.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))?;
+        let blockhash = result["value"]["blockhash"].as_str().ok_or("missing blockhash")?;
+        crate::parse_pubkey(blockhash)
+    }
+
+    pub fn get_account(&self, address: &Pubkey) -> Result<Option<Account>> {
+        let result = self.call(
+            "getAccountInfo",
+            json!([bs58::encode(address).into_string(), { "encoding": "base64", "commitment": "confirmed" }]),
+        )?;
+        match &result["value"] {
+            Value::Null => Ok(None),
+            value => parse_account(value).map(Some),
+        }
+    }
+
+    // Simulates the transaction (no signature check) and returns the program's return data
+    pub fn simulate_return_data(&self, transaction: &[u8]) -> Result<Option<Vec<u8>>> {
+        let result = self.call(
+            "simulateTransaction",
+            json!([
+                STANDARD.encode(transaction),
+                { "encoding": "base64", "sigVerify": false, "replaceRecentBlockhash": true, "commitment": "confirmed" },
+            ]),
+        )?;
+        let value = &result["value"];
+        if !value["err"].is_null() {
+            return Err(format!("simulation failed: {} {}", value["err"], value["logs"]).into());
+        }
+        match value["returnData"]["data"][0].as_str() {
+            Some(data) => Ok(Some(STANDARD.decode(data)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn send_and_confirm(&self, transaction: &[u8]) -> Result<String> {
+        let signature = self.call(
+            "sendTransaction",
+            json!([STANDARD.encode(transaction), { "encoding": "base64", "preflightCommitment": "confirmed" }]),
+        )?;
+        let signature = signature.as_str().ok_or("missing signature")?.to_string();
+
+        for _ in 0..30 {
+            let result = self.call("getSignatureStatuses", json!([[signature]]))?;
+            let status = &result["value"][0];
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    return Err(format!("transaction {signature} failed: {}", status["err"]).into());
+                }
+                if matches!(status["confirmationStatus"].as_str(), Some("confirmed" | "finalized")) {
+                    return Ok(signature);
+                }
+            }
+            thread::sleep(Duration::from_secs(1));
+        }
+        Err(format!("transaction {signature} was not confirmed in time").into())
+    }
+}
+
+// Parses an account as returned by getAccountInfo or dumped by `solana account --output json`
+pub fn parse_account(value: &Value) -> Result<Account> {
+    let value = value.get("account").unwrap_or(value);
+
+    let data = match &value["data"] {
+        Value::Array(parts) if parts.get(1).and_then(Value::as_str) == Some("base64") => {
+            STANDARD.decode(parts[0].as_str().unwrap_or_default())?
+        }
+        Value::Array(parts) if parts.get(1).and_then(Value::as_str) == Some("base58") => {
+            bs58::decode(parts[0].as_str().unwrap_or_default()).into_vec()?
+        }
+        _ => return Err("account data must be base64 or base58 encoded".into()),
+    };
+
+    Ok(Account {
+        owner: crate::parse_pubkey(value["owner"].as_str().ok_or("missing owner")?)?,
+        lamports: value["lamports"].as_u64().unwrap_or_default(),
+        data,
+    })
+}
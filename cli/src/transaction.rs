@@ -0,0 +1,105 @@
+use ed25519_dalek::{Signer, SigningKey};
+use pinocchio::pubkey::Pubkey;
+
+use crate::Result;
+
+
+pub struct AccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+pub struct Instruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+}
+
+
+fn write_compact_u16(buf: &mut Vec<u8>, mut value: usize) {
+    loop {
+        let mut byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf.push(byte);
+            return;
+        }
+        byte |= 0x80;
+        buf.push(byte);
+    }
+}
+
+// Compiles a legacy message, returns the serialized message and its signer keys in signature order
+fn compile_message(payer: &Pubkey, instructions: &[Instruction], blockhash: &[u8; 32]) -> (Vec<u8>, Vec<Pubkey>) {
+    // (pubkey, is_signer, is_writable), payer always first
+    let mut keys: Vec<(Pubkey, bool, bool)> = vec![(*payer, true, true)];
+    let mut add_key = |pubkey: &Pubkey, is_signer: bool, is_writable: bool| {
+        match keys.iter_mut().find(|(key, _, _)| key == pubkey) {
+            Some(entry) => {
+                entry.1 |= is_signer;
+                entry.2 |= is_writable;
+            }
+            None => keys.push((*pubkey, is_signer, is_writable)),
+        }
+    };
+    for ix in instructions {
+        for meta in &ix.accounts {
+            add_key(&meta.pubkey, meta.is_signer, meta.is_writable);
+        }
+        add_key(&ix.program_id, false, false);
+    }
+
+    // writable signers, readonly signers, writable non-signers, readonly non-signers
+    keys.sort_by_key(|(_, is_signer, is_writable)| match (is_signer, is_writable) {
+        (true, true) => 0,
+        (true, false) => 1,
+        (false, true) => 2,
+        (false, false) => 3,
+    });
+
+    let num_signers = keys.iter().filter(|(_, s, _)| *s).count();
+    let num_readonly_signed = keys.iter().filter(|(_, s, w)| *s && !*w).count();
+    let num_readonly_unsigned = keys.iter().filter(|(_, s, w)| !*s && !*w).count();
+    let index_of = |pubkey: &Pubkey| keys.iter().position(|(key, _, _)| key == pubkey).unwrap() as u8;
+
+    let mut message = vec![num_signers as u8, num_readonly_signed as u8, num_readonly_unsigned as u8];
+    write_compact_u16(&mut message, keys.len());
+    for (key, _, _) in &keys {
+        message.extend_from_slice(key);
+    }
+    message.extend_from_slice(blockhash);
+
+    write_compact_u16(&mut message, instructions.len());
+    for ix in instructions {
+        message.push(index_of(&ix.program_id));
+        write_compact_u16(&mut message, ix.accounts.len());
+        for meta in &ix.accounts {
+            message.push(index_of(&meta.pubkey));
+        }
+        write_compact_u16(&mut message, ix.data.len());
+        message.extend_from_slice(&ix.data);
+    }
+
+    let signers = keys.iter().take(num_signers).map(|(key, _, _)| *key).collect();
+    (message, signers)
+}
+
+// Builds and signs a wire-format legacy transaction, the first signer pays the fees
+pub fn sign_transaction(instructions: &[Instruction], signers: &[&SigningKey], blockhash: &[u8; 32]) -> Result<Vec<u8>> {
+    let payer = signers.first().ok_or("transaction needs at least one signer")?.verifying_key().to_bytes();
+    let (message, required) = compile_message(&payer, instructions, blockhash);
+
+    let mut transaction = Vec::new();
+    write_compact_u16(&mut transaction, required.len());
+    for key in &required {
+        let signer = signers
+            .iter()
+            .find(|signer| &signer.verifying_key().to_bytes() == key)
+            .ok_or_else(|| format!("missing signer {}", bs58::encode(key).into_string()))?;
+        transaction.extend_from_slice(&signer.sign(&message).to_bytes());
+    }
+    transaction.extend_from_slice(&message);
+
+    Ok(transaction)
+}
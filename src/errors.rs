@@ -0,0 +1,281 @@
+use pinocchio::program_error::ProgramError;
+
+
+// Custom errors are returned as ProgramError::Custom(code) (codes are listed in the IDL)
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VireError {
+    SemesterLimitExceeded = 0,
+    SemestersIncomplete,
+    DegreeDurationIncomplete,
+    Unauthorized,
+    PaymentMintNotAllowed,
+    PaymentMintMismatch,
+    PaymentMintListFull,
+    InvalidMintAuthority,
+    InvalidMintDecimals,
+    MintSupplyNotZero,
+    CollectionMintMismatch,
+    InvalidMetadataMode,
+    AlreadyGraduated,
+    CredentialRevoked,
+    InvalidRevocationReason,
+    InvalidSemesterRecord,
+    TranscriptFull,
+    GradeRequirementNotMet,
+    SemesterNotApproved,
+    InvalidSemesterApproval,
+    PaymentTooEarly,
+    InvalidInstallmentPlan,
+    InsufficientScholarshipFunds,
+    InvalidDiscount,
+    DiscountExpired,
+    DiscountUsedUp,
+    DiscountNotForStudent,
+    SubjectFull,
+    SubjectNotFull,
+    WaitlistFull,
+    AlreadyWaitlisted,
+    WaitlistEmpty,
+    WaitlistOfferActive,
+    EnrolmentClosed,
+    InvalidIntakeCohort,
+    IntakeCohortsFull,
+    TooManyPrerequisites,
+    PrerequisiteNotMet,
+    InvalidTransferCredit,
+    InvalidTransfer,
+    SemesterPartlyPaid,
+    StudentOnLeave,
+    NotOnLeave,
+    TooManyLeaves,
+    InvalidRefundPolicy,
+    StudentSuspended,
+    StudentNotSuspended,
+    StudentExpelled,
+    NoRefundDue,
+    RefundAlreadyIssued,
+    InvalidRole,
+    StaffListFull,
+    RoleNotHeld,
+    UniPending,
+    UniSuspended,
+    InvalidUniStatus,
+    DiscountNotForSemester,
+    SponsorMismatch,
+    InvalidPaymentDestination,
+    ScholarshipNotSpent,
+    InvalidSemesterCount,
+    InvalidLateFee,
+    InvalidWaitlistOffer,
+}
+
+impl VireError {
+    pub const ALL: &'static [VireError] = &[
+        VireError::SemesterLimitExceeded,
+        VireError::SemestersIncomplete,
+        VireError::DegreeDurationIncomplete,
+        VireError::Unauthorized,
+        VireError::PaymentMintNotAllowed,
+        VireError::PaymentMintMismatch,
+        VireError::PaymentMintListFull,
+        VireError::InvalidMintAuthority,
+        VireError::InvalidMintDecimals,
+        VireError::MintSupplyNotZero,
+        VireError::CollectionMintMismatch,
+        VireError::InvalidMetadataMode,
+        VireError::AlreadyGraduated,
+        VireError::CredentialRevoked,
+        VireError::InvalidRevocationReason,
+        VireError::InvalidSemesterRecord,
+        VireError::TranscriptFull,
+        VireError::GradeRequirementNotMet,
+        VireError::SemesterNotApproved,
+        VireError::InvalidSemesterApproval,
+        VireError::PaymentTooEarly,
+        VireError::InvalidInstallmentPlan,
+        VireError::InsufficientScholarshipFunds,
+        VireError::InvalidDiscount,
+        VireError::DiscountExpired,
+        VireError::DiscountUsedUp,
+        VireError::DiscountNotForStudent,
+        VireError::SubjectFull,
+        VireError::SubjectNotFull,
+        VireError::WaitlistFull,
+        VireError::AlreadyWaitlisted,
+        VireError::WaitlistEmpty,
+        VireError::WaitlistOfferActive,
+        VireError::EnrolmentClosed,
+        VireError::InvalidIntakeCohort,
+        VireError::IntakeCohortsFull,
+        VireError::TooManyPrerequisites,
+        VireError::PrerequisiteNotMet,
+        VireError::InvalidTransferCredit,
+        VireError::InvalidTransfer,
+        VireError::SemesterPartlyPaid,
+        VireError::StudentOnLeave,
+        VireError::NotOnLeave,
+        VireError::TooManyLeaves,
+        VireError::InvalidRefundPolicy,
+        VireError::StudentSuspended,
+        VireError::StudentNotSuspended,
+        VireError::StudentExpelled,
+        VireError::NoRefundDue,
+        VireError::RefundAlreadyIssued,
+        VireError::InvalidRole,
+        VireError::StaffListFull,
+        VireError::RoleNotHeld,
+        VireError::UniPending,
+        VireError::UniSuspended,
+        VireError::InvalidUniStatus,
+        VireError::DiscountNotForSemester,
+        VireError::SponsorMismatch,
+        VireError::InvalidPaymentDestination,
+        VireError::ScholarshipNotSpent,
+        VireError::InvalidSemesterCount,
+        VireError::InvalidLateFee,
+        VireError::InvalidWaitlistOffer,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            VireError::SemesterLimitExceeded => "SemesterLimitExceeded",
+            VireError::SemestersIncomplete => "SemestersIncomplete",
+            VireError::DegreeDurationIncomplete => "DegreeDurationIncomplete",
+            VireError::Unauthorized => "Unauthorized",
+            VireError::PaymentMintNotAllowed => "PaymentMintNotAllowed",
+            VireError::PaymentMintMismatch => "PaymentMintMismatch",
+            VireError::PaymentMintListFull => "PaymentMintListFull",
+            VireError::InvalidMintAuthority => "InvalidMintAuthority",
+            VireError::InvalidMintDecimals => "InvalidMintDecimals",
+            VireError::MintSupplyNotZero => "MintSupplyNotZero",
+            VireError::CollectionMintMismatch => "CollectionMintMismatch",
+            VireError::InvalidMetadataMode => "InvalidMetadataMode",
+            VireError::AlreadyGraduated => "AlreadyGraduated",
+            VireError::CredentialRevoked => "CredentialRevoked",
+            VireError::InvalidRevocationReason => "InvalidRevocationReason",
+            VireError::InvalidSemesterRecord => "InvalidSemesterRecord",
+            VireError::TranscriptFull => "TranscriptFull",
+            VireError::GradeRequirementNotMet => "GradeRequirementNotMet",
+            VireError::SemesterNotApproved => "SemesterNotApproved",
+            VireError::InvalidSemesterApproval => "InvalidSemesterApproval",
+            VireError::PaymentTooEarly => "PaymentTooEarly",
+            VireError::InvalidInstallmentPlan => "InvalidInstallmentPlan",
+            VireError::InsufficientScholarshipFunds => "InsufficientScholarshipFunds",
+            VireError::InvalidDiscount => "InvalidDiscount",
+            VireError::DiscountExpired => "DiscountExpired",
+            VireError::DiscountUsedUp => "DiscountUsedUp",
+            VireError::DiscountNotForStudent => "DiscountNotForStudent",
+            VireError::SubjectFull => "SubjectFull",
+            VireError::SubjectNotFull => "SubjectNotFull",
+            VireError::WaitlistFull => "WaitlistFull",
+            VireError::AlreadyWaitlisted => "AlreadyWaitlisted",
+            VireError::WaitlistEmpty => "WaitlistEmpty",
+            VireError::WaitlistOfferActive => "WaitlistOfferActive",
+            VireError::EnrolmentClosed => "EnrolmentClosed",
+            VireError::InvalidIntakeCohort => "InvalidIntakeCohort",
+            VireError::IntakeCohortsFull => "IntakeCohortsFull",
+            VireError::TooManyPrerequisites => "TooManyPrerequisites",
+            VireError::PrerequisiteNotMet => "PrerequisiteNotMet",
+            VireError::InvalidTransferCredit => "InvalidTransferCredit",
+            VireError::InvalidTransfer => "InvalidTransfer",
+            VireError::SemesterPartlyPaid => "SemesterPartlyPaid",
+            VireError::StudentOnLeave => "StudentOnLeave",
+            VireError::NotOnLeave => "NotOnLeave",
+            VireError::TooManyLeaves => "TooManyLeaves",
+            VireError::InvalidRefundPolicy => "InvalidRefundPolicy",
+            VireError::StudentSuspended => "StudentSuspended",
+            VireError::StudentNotSuspended => "StudentNotSuspended",
+            VireError::StudentExpelled => "StudentExpelled",
+            VireError::NoRefundDue => "NoRefundDue",
+            VireError::RefundAlreadyIssued => "RefundAlreadyIssued",
+            VireError::InvalidRole => "InvalidRole",
+            VireError::StaffListFull => "StaffListFull",
+            VireError::RoleNotHeld => "RoleNotHeld",
+            VireError::UniPending => "UniPending",
+            VireError::UniSuspended => "UniSuspended",
+            VireError::InvalidUniStatus => "InvalidUniStatus",
+            VireError::DiscountNotForSemester => "DiscountNotForSemester",
+            VireError::SponsorMismatch => "SponsorMismatch",
+            VireError::InvalidPaymentDestination => "InvalidPaymentDestination",
+            VireError::ScholarshipNotSpent => "ScholarshipNotSpent",
+            VireError::InvalidSemesterCount => "InvalidSemesterCount",
+            VireError::InvalidLateFee => "InvalidLateFee",
+            VireError::InvalidWaitlistOffer => "InvalidWaitlistOffer",
+        }
+    }
+
+    pub fn msg(&self) -> &'static str {
+        match self {
+            VireError::SemesterLimitExceeded => "Student has already paid for every semester of the subject",
+            VireError::SemestersIncomplete => "Student has not paid for every semester of the subject",
+            VireError::DegreeDurationIncomplete => "Degree duration has not been completed yet",
+            VireError::Unauthorized => "Signer is not the admin of this account",
+            VireError::PaymentMintNotAllowed => "Payment mint is not on the protocol allowlist",
+            VireError::PaymentMintMismatch => "Payment mint does not match the subject's payment mint",
+            VireError::PaymentMintListFull => "Payment mint allowlist is full",
+            VireError::InvalidMintAuthority => "NFT mint and freeze authority must be the program PDA",
+            VireError::InvalidMintDecimals => "NFT mint must have 0 decimals",
+            VireError::MintSupplyNotZero => "NFT mint has already been minted",
+            VireError::CollectionMintMismatch => "Collection mint does not match the subject's collection",
+            VireError::InvalidMetadataMode => "Token-2022 metadata needs a Token-2022 NFT mint created on-chain",
+            VireError::AlreadyGraduated => "Student has already graduated from this subject",
+            VireError::CredentialRevoked => "Credential has been revoked by the university",
+            VireError::InvalidRevocationReason => "Revocation reason code must not be 0",
+            VireError::InvalidSemesterRecord => "Semester is not paid yet or already recorded",
+            VireError::TranscriptFull => "Transcript has no room for more semesters",
+            VireError::GradeRequirementNotMet => "Cumulative grade is below the subject's minimum",
+            VireError::SemesterNotApproved => "The previous semester has not been approved by the university",
+            VireError::InvalidSemesterApproval => "Semesters are approved in order, once they have been paid",
+            VireError::PaymentTooEarly => "The semester's payment window has not opened yet",
+            VireError::InvalidInstallmentPlan => "Installments have to fit in the semester's months",
+            VireError::InsufficientScholarshipFunds => "Scholarship pool has not enough unawarded funds",
+            VireError::InvalidDiscount => "Percentage discounts can't exceed 100",
+            VireError::DiscountExpired => "Discount has expired",
+            VireError::DiscountUsedUp => "Discount has no uses left",
+            VireError::DiscountNotForStudent => "Discount is bound to another student or subject",
+            VireError::SubjectFull => "The subject has no open spots",
+            VireError::SubjectNotFull => "The subject still has open spots, enrol directly",
+            VireError::WaitlistFull => "The waitlist is full",
+            VireError::AlreadyWaitlisted => "The wallet is already on the waitlist",
+            VireError::WaitlistEmpty => "Nobody is waiting for a spot",
+            VireError::WaitlistOfferActive => "A waitlist offer is still open",
+            VireError::EnrolmentClosed => "No intake cohort of the subject is open for enrolment",
+            VireError::InvalidIntakeCohort => "Cohort has to open before it closes and start in the future",
+            VireError::IntakeCohortsFull => "Subject has no room for another intake cohort",
+            VireError::TooManyPrerequisites => "A subject can have at most 4 prerequisites",
+            VireError::PrerequisiteNotMet => "A prerequisite subject has not been completed",
+            VireError::InvalidTransferCredit => "Transfer credit is a percentage up to 100",
+            VireError::InvalidTransfer => "Transfers are between two subjects of the same university",
+            VireError::SemesterPartlyPaid => "The current semester is only partly paid",
+            VireError::StudentOnLeave => "Student is on a leave of absence",
+            VireError::NotOnLeave => "Student is not on a leave of absence",
+            VireError::TooManyLeaves => "Student has used all leaves of absence",
+            VireError::InvalidRefundPolicy => "Refund policy is a percentage up to 100",
+            VireError::StudentSuspended => "Student is suspended",
+            VireError::StudentNotSuspended => "Student is not suspended",
+            VireError::StudentExpelled => "Student was expelled",
+            VireError::NoRefundDue => "Nothing is refundable under the subject's refund policy",
+            VireError::RefundAlreadyIssued => "Student was already refunded",
+            VireError::InvalidRole => "Roles must be a non-empty set of finance, registrar and grader bits",
+            VireError::StaffListFull => "University staff list is full",
+            VireError::RoleNotHeld => "Wallet doesn't hold the role",
+            VireError::UniPending => "University is waiting for approval by the Vire admin",
+            VireError::UniSuspended => "University is suspended",
+            VireError::InvalidUniStatus => "University is not in the status this requires",
+            VireError::DiscountNotForSemester => "Later installments only take the discount the semester was started with",
+            VireError::SponsorMismatch => "The semester is being paid by another wallet",
+            VireError::InvalidPaymentDestination => "Tuition only goes to the university admin's and the Vire admin's associated token accounts",
+            VireError::ScholarshipNotSpent => "The student's scholarship award still has funds, it can't leave the enrolment",
+            VireError::InvalidSemesterCount => "A subject has at least one semester",
+            VireError::InvalidLateFee => "Late fee is a percentage up to 100",
+            VireError::InvalidWaitlistOffer => "Waitlist offers need a positive time to enrol",
+        }
+    }
+}
+
+impl From<VireError> for ProgramError {
+    fn from(e: VireError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,932 @@
+use pinocchio::pubkey::Pubkey;
+
+use crate::{
+    instructions::vire_instructions::VireInstruction,
+    token_interface::{ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID},
+    student_account::MAX_LEAVES,
+    subject_account::{MAX_INTAKE_COHORTS, MAX_PREREQUISITES},
+    token_metadata::{MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH, TOKEN_METADATA_PROGRAM_ID},
+    transcript_account::MAX_TRANSCRIPT_RECORDS,
+    uni_account::MAX_STAFF,
+    vire_account::MAX_PAYMENT_MINTS,
+    waitlist_account::MAX_WAITLIST,
+};
+
+
+// Static description of the program interface.
+// tests/idl.rs turns this into idl/vire_protocol.json and checks it against the Rust definitions.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdlType {
+    Bool,
+    U8,
+    U64,
+    I64,
+    Pubkey,
+    Array(&'static IdlType, usize),
+    Defined(&'static IdlTypeDef),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct IdlField {
+    pub name: &'static str,
+    pub ty: IdlType,
+}
+
+pub struct IdlAccountItem {
+    pub name: &'static str,
+    pub writable: bool,
+    pub signer: bool,
+    pub address: Option<Pubkey>,
+    // Trailing accounts that are only passed in some modes
+    pub optional: bool,
+}
+
+pub struct IdlInstruction {
+    pub name: &'static str,
+    pub instruction: VireInstruction,
+    pub accounts: &'static [IdlAccountItem],
+    pub args_type: Option<&'static str>,
+    pub args: &'static [IdlField],
+    // Type written with set_return_data
+    pub returns: Option<&'static IdlTypeDef>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct IdlTypeDef {
+    pub name: &'static str,
+    pub fields: &'static [IdlField],
+}
+
+
+const fn field(name: &'static str, ty: IdlType) -> IdlField {
+    IdlField { name, ty }
+}
+
+const fn account(name: &'static str) -> IdlAccountItem {
+    IdlAccountItem { name, writable: false, signer: false, address: None, optional: false }
+}
+
+const fn writable(name: &'static str) -> IdlAccountItem {
+    IdlAccountItem { name, writable: true, signer: false, address: None, optional: false }
+}
+
+const fn signer(name: &'static str) -> IdlAccountItem {
+    IdlAccountItem { name, writable: false, signer: true, address: None, optional: false }
+}
+
+const fn payer(name: &'static str) -> IdlAccountItem {
+    IdlAccountItem { name, writable: true, signer: true, address: None, optional: false }
+}
+
+const fn optional(item: IdlAccountItem) -> IdlAccountItem {
+    IdlAccountItem { optional: true, ..item }
+}
+
+const SYSTEM_PROGRAM: IdlAccountItem = IdlAccountItem {
+    name: "system_program",
+    writable: false,
+    signer: false,
+    address: Some(pinocchio_system::ID),
+    optional: false,
+};
+
+// Token or Token-2022, whichever owns the instruction's mint
+const TOKEN_PROGRAM: IdlAccountItem = account("token_program");
+
+const ASSOCIATED_TOKEN_PROGRAM: IdlAccountItem = IdlAccountItem {
+    name: "associated_token_program",
+    writable: false,
+    signer: false,
+    address: Some(ASSOCIATED_TOKEN_PROGRAM_ID),
+    optional: false,
+};
+
+// Diplomas are always soulbound Token-2022 mints
+const DIPLOMA_TOKEN_PROGRAM: IdlAccountItem = IdlAccountItem {
+    name: "diploma_token_program",
+    writable: false,
+    signer: false,
+    address: Some(TOKEN_2022_PROGRAM_ID),
+    optional: false,
+};
+
+const TOKEN_METADATA_PROGRAM: IdlAccountItem = IdlAccountItem {
+    name: "token_metadata_program",
+    writable: false,
+    signer: false,
+    address: Some(TOKEN_METADATA_PROGRAM_ID),
+    optional: true,
+};
+
+
+// <---Instructions (account order is the order the handlers destructure them in)--->
+// collection_mint / card_mint also have to sign when mint_mode = 1 (created on-chain)
+// optional accounts are only passed when metadata_mode = 1 (Metaplex), metadata_mode = 2 stores it on the Token-2022 mint
+// optional accounts left out in front of a passed one are filled with the program id
+
+pub const INSTRUCTIONS: &[IdlInstruction] = &[
+    IdlInstruction {
+        name: "initialize_vire",
+        instruction: VireInstruction::InitializeVire,
+        accounts: &[
+            payer("admin"),
+            account("mint_usdc"),
+            writable("vire_account"),
+            account("treasury"),
+            SYSTEM_PROGRAM,
+            TOKEN_PROGRAM,
+        ],
+        args_type: Some("VireArgs"),
+        args: &[
+            field("transaction_fee_uni", IdlType::U64),
+            field("transaction_fee_student", IdlType::U64),
+            field("bump", IdlType::U8),
+        ],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "initialize_uni",
+        instruction: VireInstruction::InitializeUni,
+        accounts: &[
+            payer("uni_admin"),
+            writable("uni_account"),
+            writable("vire_account"),
+            SYSTEM_PROGRAM,
+        ],
+        args_type: Some("UniArgs"),
+        args: &[
+            field("bump", IdlType::U8),
+        ],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "add_subjects",
+        instruction: VireInstruction::AddSubjects,
+        accounts: &[
+            payer("uni_admin"),
+            account("mint_usdc"),
+            writable("subject_account"),
+            writable("uni_account"),
+            writable("uni_ata_usdc"),
+            account("vire_account"),
+            writable("treasury"),
+            writable("collection_mint"),
+            writable("uni_collection_ata"),
+            SYSTEM_PROGRAM,
+            TOKEN_PROGRAM,
+            account("collection_token_program"),
+            ASSOCIATED_TOKEN_PROGRAM,
+            optional(writable("collection_metadata")),
+            optional(writable("collection_master_edition")),
+            TOKEN_METADATA_PROGRAM,
+        ],
+        args_type: Some("SubjectArgs"),
+        args: &[
+            field("tution_fee", IdlType::U64),
+            field("max_semester", IdlType::U64),
+            field("semester_months", IdlType::U64),
+            field("min_grade", IdlType::U64),
+            field("requires_approval", IdlType::Bool),
+            field("payment_window_days", IdlType::U64),
+            field("late_fee", IdlType::U64),
+            field("installments", IdlType::U8),
+            field("max_students", IdlType::U64),
+            field("prerequisite_count", IdlType::U8),
+            field("prerequisites", IdlType::Array(&IdlType::Pubkey, MAX_PREREQUISITES)),
+            field("transfer_credit", IdlType::U8),
+            field("refund_percent", IdlType::U8),
+            field("mint_mode", IdlType::U8),
+            field("metadata_mode", IdlType::U8),
+            field("name", IdlType::Array(&IdlType::U8, MAX_NAME_LENGTH)),
+            field("symbol", IdlType::Array(&IdlType::U8, MAX_SYMBOL_LENGTH)),
+            field("uri", IdlType::Array(&IdlType::U8, MAX_URI_LENGTH)),
+            field("bump", IdlType::U8),
+        ],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "initialize_student",
+        instruction: VireInstruction::InitializeStudent,
+        accounts: &[
+            payer("student"),
+            writable("student_account"),
+            writable("subject_account"),
+            writable("uni_account"),
+            account("vire_account"),
+            writable("card_mint"),
+            writable("student_card_ata"),
+            SYSTEM_PROGRAM,
+            TOKEN_PROGRAM,
+            ASSOCIATED_TOKEN_PROGRAM,
+            optional(writable("card_metadata")),
+            optional(account("collection_mint")),
+            optional(writable("collection_metadata")),
+            optional(account("collection_master_edition")),
+            TOKEN_METADATA_PROGRAM,
+            // followed by one account per subject prerequisite: the student's StudentAccount in it
+        ],
+        args_type: Some("StudentArgs"),
+        args: &[
+            field("mint_mode", IdlType::U8),
+            field("metadata_mode", IdlType::U8),
+            field("name", IdlType::Array(&IdlType::U8, MAX_NAME_LENGTH)),
+            field("symbol", IdlType::Array(&IdlType::U8, MAX_SYMBOL_LENGTH)),
+            field("uri", IdlType::Array(&IdlType::U8, MAX_URI_LENGTH)),
+            field("bump", IdlType::U8),
+        ],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "pay_tution_fee",
+        instruction: VireInstruction::PayTutionFee,
+        accounts: &[
+            signer("student"),
+            account("mint_usdc"),
+            account("uni_admin"),
+            writable("student_account"),
+            writable("student_ata_usdc"),
+            account("subject_account"),
+            account("uni_account"),
+            writable("uni_ata_usdc"),
+            account("vire_account"),
+            writable("treasury"),
+            SYSTEM_PROGRAM,
+            TOKEN_PROGRAM,
+            // a discount and/or the student's scholarship award
+            optional(writable("discount_account")),
+            optional(writable("scholarship_pool")),
+            optional(writable("scholarship_award")),
+            optional(writable("scholarship_vault")),
+        ],
+        args_type: None,
+        args: &[],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "unstake",
+        instruction: VireInstruction::UnStake,
+        accounts: &[
+            payer("student"),
+            writable("student_account"),
+            writable("subject_account"),
+            account("card_mint"),
+            writable("student_card_ata"),
+            payer("diploma_mint"),
+            writable("student_diploma_ata"),
+            SYSTEM_PROGRAM,
+            TOKEN_PROGRAM,
+            DIPLOMA_TOKEN_PROGRAM,
+            ASSOCIATED_TOKEN_PROGRAM,
+            // only needed when the subject has a min_grade
+            optional(account("transcript_account")),
+        ],
+        args_type: None,
+        args: &[],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "update_payment_mint",
+        instruction: VireInstruction::UpdatePaymentMint,
+        accounts: &[
+            signer("admin"),
+            writable("vire_account"),
+            account("payment_mint"),
+        ],
+        args_type: Some("PaymentMintArgs"),
+        args: &[
+            field("allowed", IdlType::Bool),
+        ],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "verify_credential",
+        instruction: VireInstruction::VerifyCredential,
+        accounts: &[
+            account("student"),
+            account("student_account"),
+            account("subject_account"),
+            account("student_diploma_ata"),
+        ],
+        args_type: None,
+        args: &[],
+        returns: Some(&CREDENTIAL_STATUS),
+    },
+    IdlInstruction {
+        name: "revoke_credential",
+        instruction: VireInstruction::RevokeCredential,
+        accounts: &[
+            signer("uni_admin"),
+            account("uni_account"),
+            account("subject_account"),
+            writable("student_account"),
+            account("diploma_mint"),
+            writable("student_diploma_ata"),
+            DIPLOMA_TOKEN_PROGRAM,
+        ],
+        args_type: Some("RevokeArgs"),
+        args: &[
+            field("reason", IdlType::U8),
+        ],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "record_grades",
+        instruction: VireInstruction::RecordGrades,
+        accounts: &[
+            payer("grader"),
+            account("uni_account"),
+            account("subject_account"),
+            account("student_account"),
+            writable("transcript_account"),
+            SYSTEM_PROGRAM,
+        ],
+        args_type: Some("GradeArgs"),
+        args: &[
+            field("semester", IdlType::U64),
+            field("grade", IdlType::U64),
+            field("credits", IdlType::U64),
+            field("transcript_hash", IdlType::Array(&IdlType::U8, 32)),
+            field("bump", IdlType::U8),
+        ],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "approve_semester",
+        instruction: VireInstruction::ApproveSemester,
+        accounts: &[
+            signer("uni_admin"),
+            account("uni_account"),
+            account("subject_account"),
+            writable("student_account"),
+        ],
+        args_type: Some("ApproveSemesterArgs"),
+        args: &[
+            field("semester", IdlType::U64),
+        ],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "sponsor_pay_tuition",
+        instruction: VireInstruction::SponsorPayTuition,
+        accounts: &[
+            signer("sponsor"),
+            account("mint_usdc"),
+            writable("student_account"),
+            writable("sponsor_ata_usdc"),
+            account("subject_account"),
+            account("uni_account"),
+            writable("uni_ata_usdc"),
+            account("vire_account"),
+            writable("treasury"),
+            SYSTEM_PROGRAM,
+            TOKEN_PROGRAM,
+            // a discount and/or the student's scholarship award
+            optional(writable("discount_account")),
+            optional(writable("scholarship_pool")),
+            optional(writable("scholarship_award")),
+            optional(writable("scholarship_vault")),
+        ],
+        args_type: None,
+        args: &[],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "initialize_scholarship_pool",
+        instruction: VireInstruction::InitializeScholarshipPool,
+        accounts: &[
+            payer("uni_admin"),
+            account("uni_account"),
+            // uni_account for a university-wide pool, or one of its subject_accounts
+            account("scope"),
+            writable("scholarship_pool"),
+            account("mint"),
+            writable("vault"),
+            SYSTEM_PROGRAM,
+            TOKEN_PROGRAM,
+            ASSOCIATED_TOKEN_PROGRAM,
+        ],
+        args_type: Some("PoolArgs"),
+        args: &[
+            field("bump", IdlType::U8),
+        ],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "deposit_scholarship",
+        instruction: VireInstruction::DepositScholarship,
+        accounts: &[
+            signer("donor"),
+            account("uni_account"),
+            writable("scholarship_pool"),
+            account("mint"),
+            writable("donor_ata"),
+            writable("vault"),
+            TOKEN_PROGRAM,
+        ],
+        args_type: Some("DepositArgs"),
+        args: &[
+            field("amount", IdlType::U64),
+        ],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "award_scholarship",
+        instruction: VireInstruction::AwardScholarship,
+        accounts: &[
+            payer("uni_admin"),
+            account("uni_account"),
+            writable("scholarship_pool"),
+            account("subject_account"),
+            writable("student_account"),
+            writable("scholarship_award"),
+            SYSTEM_PROGRAM,
+        ],
+        args_type: Some("AwardArgs"),
+        args: &[
+            field("amount", IdlType::U64),
+            field("bump", IdlType::U8),
+        ],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "create_discount",
+        instruction: VireInstruction::CreateDiscount,
+        accounts: &[
+            payer("uni_admin"),
+            account("uni_account"),
+            account("subject_account"),
+            writable("discount_account"),
+            SYSTEM_PROGRAM,
+        ],
+        args_type: Some("DiscountArgs"),
+        args: &[
+            field("discount_id", IdlType::U64),
+            field("kind", IdlType::U8),
+            field("value", IdlType::U64),
+            field("expires_at", IdlType::I64),
+            field("max_uses", IdlType::U64),
+            field("student_key", IdlType::Pubkey),
+            field("bump", IdlType::U8),
+        ],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "create_waitlist",
+        instruction: VireInstruction::CreateWaitlist,
+        accounts: &[
+            payer("uni_admin"),
+            account("uni_account"),
+            account("subject_account"),
+            writable("waitlist_account"),
+            SYSTEM_PROGRAM,
+        ],
+        args_type: Some("WaitlistArgs"),
+        args: &[
+            field("offer_seconds", IdlType::I64),
+            field("bump", IdlType::U8),
+        ],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "join_waitlist",
+        instruction: VireInstruction::JoinWaitlist,
+        accounts: &[
+            signer("student"),
+            account("uni_account"),
+            writable("subject_account"),
+            writable("waitlist_account"),
+        ],
+        args_type: None,
+        args: &[],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "offer_waitlist_spot",
+        instruction: VireInstruction::OfferWaitlistSpot,
+        accounts: &[
+            account("uni_account"),
+            writable("subject_account"),
+            writable("waitlist_account"),
+        ],
+        args_type: None,
+        args: &[],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "add_intake_cohort",
+        instruction: VireInstruction::AddIntakeCohort,
+        accounts: &[
+            signer("uni_admin"),
+            account("uni_account"),
+            writable("subject_account"),
+        ],
+        args_type: Some("CohortArgs"),
+        args: &[
+            field("opens_at", IdlType::I64),
+            field("closes_at", IdlType::I64),
+            field("starts_at", IdlType::I64),
+        ],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "transfer_enrollment",
+        instruction: VireInstruction::TransferEnrollment,
+        accounts: &[
+            payer("student"),
+            writable("student_account"),
+            writable("subject_account"),
+            writable("new_student_account"),
+            writable("new_subject_account"),
+            account("uni_account"),
+            writable("card_mint"),
+            writable("student_card_ata"),
+            writable("new_card_mint"),
+            writable("new_student_card_ata"),
+            SYSTEM_PROGRAM,
+            account("card_token_program"),
+            TOKEN_PROGRAM,
+            ASSOCIATED_TOKEN_PROGRAM,
+            writable("transcript_account"),
+            writable("new_transcript_account"),
+            optional(writable("card_metadata")),
+            optional(account("collection_mint")),
+            optional(writable("collection_metadata")),
+            optional(account("collection_master_edition")),
+            TOKEN_METADATA_PROGRAM,
+            // followed by the prerequisite proofs of the new subject, as in initialize_student
+        ],
+        args_type: Some("StudentArgs"),
+        args: &[
+            field("mint_mode", IdlType::U8),
+            field("metadata_mode", IdlType::U8),
+            field("name", IdlType::Array(&IdlType::U8, MAX_NAME_LENGTH)),
+            field("symbol", IdlType::Array(&IdlType::U8, MAX_SYMBOL_LENGTH)),
+            field("uri", IdlType::Array(&IdlType::U8, MAX_URI_LENGTH)),
+            field("bump", IdlType::U8),
+        ],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "request_leave",
+        instruction: VireInstruction::RequestLeave,
+        accounts: &[
+            signer("student"),
+            signer("uni_admin"),
+            account("uni_account"),
+            account("subject_account"),
+            writable("student_account"),
+        ],
+        args_type: None,
+        args: &[],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "resume_studies",
+        instruction: VireInstruction::ResumeStudies,
+        accounts: &[
+            signer("student"),
+            signer("uni_admin"),
+            account("uni_account"),
+            account("subject_account"),
+            writable("student_account"),
+        ],
+        args_type: None,
+        args: &[],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "suspend_student",
+        instruction: VireInstruction::SuspendStudent,
+        accounts: &[
+            signer("uni_admin"),
+            account("uni_account"),
+            account("subject_account"),
+            writable("student_account"),
+            // refund accounts, only needed with refund = true
+            optional(account("mint_usdc")),
+            optional(writable("uni_ata_usdc")),
+            optional(writable("sponsor_ata")),
+            optional(account("token_program")),
+        ],
+        args_type: Some("SuspendArgs"),
+        args: &[
+            field("suspended", IdlType::Bool),
+            field("refund", IdlType::Bool),
+        ],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "expel_student",
+        instruction: VireInstruction::ExpelStudent,
+        accounts: &[
+            signer("uni_admin"),
+            account("uni_account"),
+            writable("subject_account"),
+            writable("student_account"),
+            writable("card_mint"),
+            writable("student_card_ata"),
+            account("card_token_program"),
+            // refund accounts, only needed with refund = true
+            optional(account("mint_usdc")),
+            optional(writable("uni_ata_usdc")),
+            optional(writable("sponsor_ata")),
+            optional(account("token_program")),
+        ],
+        args_type: Some("ExpelArgs"),
+        args: &[
+            field("refund", IdlType::Bool),
+        ],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "add_role",
+        instruction: VireInstruction::AddRole,
+        accounts: &[
+            signer("uni_admin"),
+            writable("uni_account"),
+            account("member"),
+        ],
+        args_type: Some("RoleArgs"),
+        args: &[
+            field("roles", IdlType::U8),
+        ],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "remove_role",
+        instruction: VireInstruction::RemoveRole,
+        accounts: &[
+            signer("uni_admin"),
+            writable("uni_account"),
+            account("member"),
+        ],
+        args_type: Some("RoleArgs"),
+        args: &[
+            field("roles", IdlType::U8),
+        ],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "approve_uni",
+        instruction: VireInstruction::ApproveUni,
+        accounts: &[
+            signer("admin"),
+            account("vire_account"),
+            writable("uni_account"),
+        ],
+        args_type: None,
+        args: &[],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "suspend_uni",
+        instruction: VireInstruction::SuspendUni,
+        accounts: &[
+            signer("admin"),
+            account("vire_account"),
+            writable("uni_account"),
+        ],
+        args_type: None,
+        args: &[],
+        returns: None,
+    },
+    IdlInstruction {
+        name: "reinstate_uni",
+        instruction: VireInstruction::ReinstateUni,
+        accounts: &[
+            signer("admin"),
+            account("vire_account"),
+            writable("uni_account"),
+        ],
+        args_type: None,
+        args: &[],
+        returns: None,
+    },
+];
+
+// state: 0 = not enrolled, 1 = enrolled, 2 = graduated, 3 = revoked, 4 = expelled
+pub const CREDENTIAL_STATUS: IdlTypeDef = IdlTypeDef {
+    name: "CredentialStatus",
+    fields: &[
+        field("state", IdlType::U8),
+        field("holds_diploma", IdlType::Bool),
+        field("semesters", IdlType::U64),
+        field("max_semester", IdlType::U64),
+        field("graduated_at", IdlType::I64),
+        field("diploma_mint", IdlType::Pubkey),
+        field("revoked_at", IdlType::I64),
+        field("revocation_reason", IdlType::U8),
+    ],
+};
+
+
+// <---State accounts (u64/i64 fields are stored as little-endian [u8; 8])--->
+
+pub const ACCOUNTS: &[IdlTypeDef] = &[
+    IdlTypeDef {
+        name: "VireAccount",
+        fields: &[
+            field("admin_key", IdlType::Pubkey),
+            field("uni_number", IdlType::U64),
+            field("transaction_fee_uni", IdlType::U64),
+            field("transaction_fee_student", IdlType::U64),
+            field("payment_mint_count", IdlType::U8),
+            field("payment_mints", IdlType::Array(&IdlType::Pubkey, MAX_PAYMENT_MINTS)),
+            field("vire_bump", IdlType::U8),
+        ],
+    },
+    IdlTypeDef {
+        name: "UniAccount",
+        fields: &[
+            field("uni_key", IdlType::Pubkey),
+            field("vire_key", IdlType::Pubkey),
+            field("uni_id", IdlType::U64),
+            field("subject_number", IdlType::U64),
+            field("student_number", IdlType::U64),
+            field("staff_count", IdlType::U8),
+            field("staff", IdlType::Array(&IdlType::Defined(&STAFF_MEMBER), MAX_STAFF)),
+            field("status", IdlType::U8),
+            field("uni_bump", IdlType::U8),
+        ],
+    },
+    IdlTypeDef {
+        name: "SubjectAccount",
+        fields: &[
+            field("uni_key", IdlType::Pubkey),
+            field("subject_code", IdlType::U64),
+            field("tution_fee", IdlType::U64),
+            field("max_semester", IdlType::U64),
+            field("semester_months", IdlType::U64),
+            field("payment_mint", IdlType::Pubkey),
+            field("collection_mint", IdlType::Pubkey),
+            field("min_grade", IdlType::U64),
+            field("requires_approval", IdlType::Bool),
+            field("payment_window_days", IdlType::U64),
+            field("late_fee", IdlType::U64),
+            field("installments", IdlType::U8),
+            field("max_students", IdlType::U64),
+            field("enrolled_count", IdlType::U64),
+            field("offered_student", IdlType::Pubkey),
+            field("offer_expires_at", IdlType::I64),
+            field("cohort_count", IdlType::U8),
+            field("cohorts", IdlType::Array(&IdlType::Defined(&INTAKE_COHORT), MAX_INTAKE_COHORTS)),
+            field("prerequisite_count", IdlType::U8),
+            field("prerequisites", IdlType::Array(&IdlType::Pubkey, MAX_PREREQUISITES)),
+            field("transfer_credit", IdlType::U8),
+            field("refund_percent", IdlType::U8),
+            field("waitlist_len", IdlType::U8),
+            field("subject_bump", IdlType::U8),
+        ],
+    },
+    IdlTypeDef {
+        name: "StudentAccount",
+        fields: &[
+            field("student_key", IdlType::Pubkey),
+            field("student_id", IdlType::U64),
+            field("time_start", IdlType::I64),
+            field("semesters", IdlType::U64),
+            field("semester_paid", IdlType::U64),
+            field("approved_semesters", IdlType::U64),
+            field("payment", IdlType::Defined(&SEMESTER_PAYMENT)),
+            field("last_payment", IdlType::Defined(&SEMESTER_PAYMENT)),
+            field("graduated_at", IdlType::I64),
+            field("diploma_mint", IdlType::Pubkey),
+            field("revoked_at", IdlType::I64),
+            field("revocation_reason", IdlType::U8),
+            field("cohort_start", IdlType::Bool),
+            field("leave_count", IdlType::U8),
+            field("leaves", IdlType::Array(&IdlType::Defined(&LEAVE_INTERVAL), MAX_LEAVES)),
+            field("suspended_at", IdlType::I64),
+            field("suspended_seconds", IdlType::I64),
+            field("expelled_at", IdlType::I64),
+            field("refunded", IdlType::U64),
+            field("semester_discount", IdlType::Pubkey),
+            field("award_remaining", IdlType::U64),
+            field("student_bump", IdlType::U8),
+        ],
+    },
+    IdlTypeDef {
+        name: "TranscriptAccount",
+        fields: &[
+            field("student_account", IdlType::Pubkey),
+            field("record_count", IdlType::U8),
+            field("total_credits", IdlType::U64),
+            field("weighted_grade_sum", IdlType::U64),
+            field("records", IdlType::Array(&IdlType::Defined(&SEMESTER_RECORD), MAX_TRANSCRIPT_RECORDS)),
+            field("carried_over", IdlType::U8),
+            field("transcript_bump", IdlType::U8),
+        ],
+    },
+    IdlTypeDef {
+        name: "ScholarshipPool",
+        fields: &[
+            field("uni_key", IdlType::Pubkey),
+            field("subject_key", IdlType::Pubkey),
+            field("mint", IdlType::Pubkey),
+            field("vault", IdlType::Pubkey),
+            field("total_deposited", IdlType::U64),
+            field("total_awarded", IdlType::U64),
+            field("total_used", IdlType::U64),
+            field("pool_bump", IdlType::U8),
+        ],
+    },
+    IdlTypeDef {
+        name: "ScholarshipAward",
+        fields: &[
+            field("pool", IdlType::Pubkey),
+            field("student_account", IdlType::Pubkey),
+            field("awarded", IdlType::U64),
+            field("used", IdlType::U64),
+            field("award_bump", IdlType::U8),
+        ],
+    },
+    // kind: 0 = percentage, 1 = fixed amount
+    IdlTypeDef {
+        name: "DiscountAccount",
+        fields: &[
+            field("subject_key", IdlType::Pubkey),
+            field("discount_id", IdlType::U64),
+            field("kind", IdlType::U8),
+            field("value", IdlType::U64),
+            field("expires_at", IdlType::I64),
+            field("max_uses", IdlType::U64),
+            field("uses", IdlType::U64),
+            field("student_key", IdlType::Pubkey),
+            field("discount_bump", IdlType::U8),
+        ],
+    },
+    // entries is a ring buffer of len wallets starting at head
+    IdlTypeDef {
+        name: "WaitlistAccount",
+        fields: &[
+            field("subject_key", IdlType::Pubkey),
+            field("offer_seconds", IdlType::I64),
+            field("head", IdlType::U8),
+            field("len", IdlType::U8),
+            field("entries", IdlType::Array(&IdlType::Pubkey, MAX_WAITLIST)),
+            field("waitlist_bump", IdlType::U8),
+        ],
+    },
+];
+
+// grade is GPA x100 or credit points, transcript_hash is the hash of the off-chain document
+pub const SEMESTER_RECORD: IdlTypeDef = IdlTypeDef {
+    name: "SemesterRecord",
+    fields: &[
+        field("semester", IdlType::U64),
+        field("grade", IdlType::U64),
+        field("credits", IdlType::U64),
+        field("transcript_hash", IdlType::Array(&IdlType::U8, 32)),
+        field("recorded_at", IdlType::I64),
+    ],
+};
+
+// ended_at is 0 while the student is on leave
+pub const LEAVE_INTERVAL: IdlTypeDef = IdlTypeDef {
+    name: "LeaveInterval",
+    fields: &[
+        field("started_at", IdlType::I64),
+        field("ended_at", IdlType::I64),
+    ],
+};
+
+// tuition the sponsor paid out of their own token account, refunds go back to it
+pub const SEMESTER_PAYMENT: IdlTypeDef = IdlTypeDef {
+    name: "SemesterPayment",
+    fields: &[
+        field("sponsor", IdlType::Pubkey),
+        field("paid", IdlType::U64),
+    ],
+};
+
+// roles: 1 = finance, 2 = registrar, 4 = grader
+pub const STAFF_MEMBER: IdlTypeDef = IdlTypeDef {
+    name: "StaffMember",
+    fields: &[
+        field("member", IdlType::Pubkey),
+        field("roles", IdlType::U8),
+    ],
+};
+
+pub const INTAKE_COHORT: IdlTypeDef = IdlTypeDef {
+    name: "IntakeCohort",
+    fields: &[
+        field("opens_at", IdlType::I64),
+        field("closes_at", IdlType::I64),
+        field("starts_at", IdlType::I64),
+    ],
+};
+
+
+impl IdlType {
+    pub fn size(&self) -> usize {
+        match self {
+            IdlType::Bool | IdlType::U8 => 1,
+            IdlType::U64 | IdlType::I64 => 8,
+            IdlType::Pubkey => 32,
+            IdlType::Array(ty, len) => ty.size() * len,
+            IdlType::Defined(def) => def.fields.iter().map(|f| f.ty.size()).sum(),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, sysvars::{clock::Clock, Sysvar}, ProgramResult};
+
+use crate::{errors::VireError, subject_account::{IntakeCohort, SubjectAccount}, uni_account::{UniAccount, ROLE_REGISTRAR}};
+
+
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct CohortArgs {
+    cohort: IntakeCohort,
+}
+
+impl CohortArgs {
+    pub fn new(opens_at: i64, closes_at: i64, starts_at: i64) -> Self {
+        Self {
+            cohort: IntakeCohort {
+                opens_at: opens_at.to_le_bytes(),
+                closes_at: closes_at.to_le_bytes(),
+                starts_at: starts_at.to_le_bytes(),
+            },
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for CohortArgs {
+    type Error = ProgramError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+            bytemuck::try_from_bytes::<Self>(value)
+                .copied()
+                .map_err(|_| ProgramError::InvalidInstructionData)
+    }
+}
+
+pub trait AddIntakeCohortContext<'a> {
+    fn add_intake_cohort(&self, args: &CohortArgs) -> ProgramResult;
+}
+
+impl <'a> AddIntakeCohortContext <'a> for &[AccountInfo] {
+    fn add_intake_cohort(&self, args: &CohortArgs) -> ProgramResult {
+        // all the required accounts for the this instruction
+        let [
+            uni_admin,
+            uni_account,
+            subject_account,
+            ] = self
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Only the university's registrar (or the admin) schedules intakes
+        if !uni_admin.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if !uni_account.is_owned_by(&crate::ID) || !subject_account.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let uni_data_ref = uni_account.try_borrow_data()?;
+        let uni_account_data = bytemuck::try_from_bytes::<UniAccount>(&uni_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if !uni_account_data.has_role(uni_account.key(), uni_admin.key(), ROLE_REGISTRAR) {
+            return Err(VireError::Unauthorized.into());
+        }
+
+        uni_account_data.check_active()?;
+
+        let mut subject_data_ref = subject_account.try_borrow_mut_data()?;
+        let subject_account_data = bytemuck::try_from_bytes_mut::<SubjectAccount>(&mut subject_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if subject_account_data.uni_key != *uni_account.key() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        subject_account_data.add_cohort(args.cohort, Clock::get()?.unix_timestamp)
+    }
+}
@@ -0,0 +1,80 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use crate::{errors::VireError, uni_account::{UniAccount, ALL_ROLES}};
+
+
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct RoleArgs {
+    roles: u8, // ROLE_FINANCE | ROLE_REGISTRAR | ROLE_GRADER bits
+}
+
+impl RoleArgs {
+    pub fn new(roles: u8) -> Self {
+        Self { roles }
+    }
+
+    pub fn roles(&self) -> u8 {
+        self.roles
+    }
+
+    pub fn check(&self) -> ProgramResult {
+        if self.roles == 0 || self.roles & !ALL_ROLES != 0 {
+            return Err(VireError::InvalidRole.into());
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&[u8]> for RoleArgs {
+    type Error = ProgramError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+            bytemuck::try_from_bytes::<Self>(value)
+                .copied()
+                .map_err(|_| ProgramError::InvalidInstructionData)
+    }
+}
+
+pub trait AddRoleContext<'a> {
+    fn add_role(&self, args: &RoleArgs) -> ProgramResult;
+}
+
+impl <'a> AddRoleContext <'a> for &[AccountInfo] {
+    fn add_role(&self, args: &RoleArgs) -> ProgramResult {
+        // all the required accounts for the this instruction
+        let [
+            uni_admin,
+            uni_account,
+            member,
+            ] = self
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Only the university admin (the wallet behind the uni_account PDA) grants roles
+        if !uni_admin.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if !uni_account.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let mut uni_data_ref = uni_account.try_borrow_mut_data()?;
+        let uni_account_data = bytemuck::try_from_bytes_mut::<UniAccount>(&mut uni_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if !uni_account_data.is_admin(uni_account.key(), uni_admin.key()) {
+            return Err(VireError::Unauthorized.into());
+        }
+
+        args.check()?;
+
+        uni_account_data.grant_role(member.key(), args.roles())?;
+
+        Ok(())
+    }
+}
@@ -1,9 +1,8 @@
 use bytemuck::{Pod, Zeroable};
-use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, pubkey::{self}, seeds, sysvars::{rent::Rent, Sysvar}, ProgramResult};
+use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, pubkey::{self, Pubkey}, seeds, sysvars::{rent::Rent, Sysvar}, ProgramResult};
 use pinocchio_system::instructions::CreateAccount;
-use pinocchio_token::{instructions::{InitializeMint2, MintToChecked}, state::Mint};
 
-use crate::{subject_account::SubjectAccount, uni_account::UniAccount, vire_account::VireAccount};
+use crate::{errors::VireError, subject_account::{SubjectAccount, MAX_PREREQUISITES}, token_interface::{self, MintMode, MintToChecked, NftMintAccounts, TransferChecked}, token_metadata::{self, CreateMasterEditionV3, CreateMetadataAccountV3, DecimalString, MetadataArgs, MetadataField, MetadataMode}, uni_account::{UniAccount, ROLE_REGISTRAR}, vire_account::VireAccount};
 
 
 
@@ -13,21 +12,150 @@ pub struct SubjectArgs {
     tution_fee: [u8; 8],
     max_semester: [u8; 8], 
     semester_months: [u8; 8],
+    min_grade: [u8; 8], // minimum cumulative transcript grade to unstake, 0 = none
+    requires_approval: u8, // 1 = each semester needs ApproveSemester before the next payment
+    payment_window_days: [u8; 8], // 0 = pay any time
+    late_fee: [u8; 8], // percent of the semester fee
+    installments: u8, // monthly installments per semester, 0 or 1 = paid at once
+    max_students: [u8; 8], // 0 = unlimited
+    prerequisite_count: u8,
+    prerequisites: [Pubkey; MAX_PREREQUISITES],
+    transfer_credit: u8, // percent of semesters paid in another subject credited on transfer
+    refund_percent: u8, // percent of the current semester refunded on suspension or expulsion, 0 = no refunds
+    mint_mode: u8, // 0 = collection_mint created by the frontend, 1 = created on-chain (collection_mint signs)
+    metadata_mode: u8, // 0 = no metadata, 1 = Metaplex sized collection, 2 = Token-2022 metadata extensions
+    metadata: MetadataArgs,
     bump: u8,
 }
 
 impl SubjectArgs {
-    fn tution_fee(&self) -> u64 {
+    pub fn new(
+        tution_fee: u64,
+        max_semester: u64,
+        semester_months: u64,
+        mint_mode: MintMode,
+        metadata_mode: MetadataMode,
+        metadata: MetadataArgs,
+        bump: u8,
+    ) -> Self {
+        Self {
+            tution_fee: tution_fee.to_le_bytes(),
+            max_semester: max_semester.to_le_bytes(),
+            semester_months: semester_months.to_le_bytes(),
+            min_grade: [0; 8],
+            requires_approval: 0,
+            payment_window_days: [0; 8],
+            late_fee: [0; 8],
+            installments: 0,
+            max_students: [0; 8],
+            prerequisite_count: 0,
+            prerequisites: [[0; 32]; MAX_PREREQUISITES],
+            transfer_credit: 0,
+            refund_percent: 0,
+            mint_mode: mint_mode as u8,
+            metadata_mode: metadata_mode as u8,
+            metadata,
+            bump,
+        }
+    }
+
+    // Optional subject rules, left off by `new`
+    pub fn with_min_grade(mut self, min_grade: u64) -> Self {
+        self.min_grade = min_grade.to_le_bytes();
+        self
+    }
+
+    pub fn with_semester_approval(mut self, requires_approval: bool) -> Self {
+        self.requires_approval = requires_approval as u8;
+        self
+    }
+
+    pub fn with_payment_window(mut self, payment_window_days: u64, late_fee: u64) -> Self {
+        self.payment_window_days = payment_window_days.to_le_bytes();
+        self.late_fee = late_fee.to_le_bytes();
+        self
+    }
+
+    pub fn with_installments(mut self, installments: u8) -> Self {
+        self.installments = installments;
+        self
+    }
+
+    pub fn with_capacity(mut self, max_students: u64) -> Self {
+        self.max_students = max_students.to_le_bytes();
+        self
+    }
+
+    // More than MAX_PREREQUISITES subjects are rejected by the program
+    pub fn with_prerequisites(mut self, prerequisites: &[Pubkey]) -> Self {
+        let count = prerequisites.len().min(MAX_PREREQUISITES);
+        self.prerequisites[..count].copy_from_slice(&prerequisites[..count]);
+        self.prerequisite_count = prerequisites.len().min(u8::MAX as usize) as u8;
+        self
+    }
+
+    pub fn with_transfer_credit(mut self, transfer_credit: u8) -> Self {
+        self.transfer_credit = transfer_credit;
+        self
+    }
+
+    pub fn with_refund_policy(mut self, refund_percent: u8) -> Self {
+        self.refund_percent = refund_percent;
+        self
+    }
+
+    pub fn tution_fee(&self) -> u64 {
         u64::from_le_bytes(self.tution_fee)
     }
 
-    fn max_semester(&self) -> u64 {
+    pub fn max_semester(&self) -> u64 {
         u64::from_le_bytes(self.max_semester)
     }
 
-    fn semester_months(&self) -> u64 {
+    pub fn semester_months(&self) -> u64 {
         u64::from_le_bytes(self.semester_months)
     }
+
+    pub fn min_grade(&self) -> u64 {
+        u64::from_le_bytes(self.min_grade)
+    }
+
+    pub fn mint_mode(&self) -> Result<MintMode, ProgramError> {
+        MintMode::try_from(self.mint_mode)
+    }
+
+    pub fn metadata_mode(&self) -> Result<MetadataMode, ProgramError> {
+        MetadataMode::try_from(self.metadata_mode)
+    }
+
+    pub fn check(&self) -> ProgramResult {
+        // The tuition fee is split across the semesters
+        if self.max_semester() == 0 {
+            return Err(VireError::InvalidSemesterCount.into());
+        }
+
+        // One installment per month at most
+        if self.installments as u64 > self.semester_months() {
+            return Err(VireError::InvalidInstallmentPlan.into());
+        }
+
+        if self.prerequisite_count as usize > MAX_PREREQUISITES {
+            return Err(VireError::TooManyPrerequisites.into());
+        }
+
+        if u64::from_le_bytes(self.late_fee) > 100 {
+            return Err(VireError::InvalidLateFee.into());
+        }
+
+        if self.transfer_credit > 100 {
+            return Err(VireError::InvalidTransferCredit.into());
+        }
+
+        if self.refund_percent > 100 {
+            return Err(VireError::InvalidRefundPolicy.into());
+        }
+        Ok(())
+    }
 }
 
 impl TryFrom<&[u8]> for SubjectArgs {
@@ -35,7 +163,7 @@ impl TryFrom<&[u8]> for SubjectArgs {
 
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
             bytemuck::try_from_bytes::<Self>(value)
-                .map(|reference| *reference)
+                .copied()
                 .map_err(|_| ProgramError::InvalidInstructionData)
     }
 }
@@ -57,8 +185,11 @@ impl <'a> AddSubjectContext <'a> for &[AccountInfo] {
             treasury,
             collection_mint,
             uni_collection_ata,
-            _system_program,  
-            _token_program 
+            system_program,  
+            token_program,
+            collection_token_program,
+            associated_token_program,
+            metadata_accounts @ ..
             ] = self 
         else {
             return Err(ProgramError::NotEnoughAccountKeys);
@@ -81,6 +212,9 @@ impl <'a> AddSubjectContext <'a> for &[AccountInfo] {
         //     return Err(ProgramError::IncorrectProgramId);
         // }
 
+        // mint_usdc and collection_mint can each be a Token or Token-2022 mint
+        token_interface::check_token_program(mint_usdc, token_program)?;
+
         let mut uni_data_ref_mut = uni_account.try_borrow_mut_data()?;
         let uni_account_data = bytemuck::try_from_bytes_mut::<UniAccount>(&mut uni_data_ref_mut)
             .map_err(|_| ProgramError::InvalidAccountData)?; 
@@ -89,9 +223,31 @@ impl <'a> AddSubjectContext <'a> for &[AccountInfo] {
         let vire_account_data = bytemuck::try_from_bytes::<VireAccount>(&vire_data_ref)
             .map_err(|_| ProgramError::InvalidAccountData)?;
 
+        // Subjects are added by the university's registrar (or the admin), who pays the listing fee and holds the collection NFT
+        if !uni_account.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        if !uni_admin.is_signer() || !uni_account_data.has_role(uni_account.key(), uni_admin.key(), ROLE_REGISTRAR) {
+            return Err(VireError::Unauthorized.into());
+        }
+
+        uni_account_data.check_active()?;
+
+        // The subject charges in mint_usdc, which has to be approved by the protocol
+        if !vire_account.is_owned_by(&crate::ID) || uni_account_data.vire_key != *vire_account.key() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if !vire_account_data.is_payment_mint_allowed(mint_usdc.key()) {
+            return Err(VireError::PaymentMintNotAllowed.into());
+        }
+
+        args.check()?;
+
         let subject_seeds_with_bump = &[
             uni_account.key().as_ref(), 
-            (&[u64::from_le_bytes((uni_account_data.subject_number).clone()).try_into().unwrap()]), 
+            (&[u64::from_le_bytes(uni_account_data.subject_number).try_into().unwrap()]), 
             &[args.bump]
             ];
         let subject_account_derived = pubkey::create_program_address(subject_seeds_with_bump, &crate::ID)?;
@@ -116,22 +272,38 @@ impl <'a> AddSubjectContext <'a> for &[AccountInfo] {
             owner: &crate::ID,
             lamports: Rent::get()?.minimum_balance(SubjectAccount::LEN),
         }
-        .invoke_signed(&[signer.clone()])?;
+        .invoke_signed(core::slice::from_ref(&signer))?;
 
         let mut subject_data_ref = subject_account.try_borrow_mut_data()?;
         let subject_account_data = bytemuck::try_from_bytes_mut::<SubjectAccount>(&mut subject_data_ref)
             .map_err(|_| ProgramError::InvalidAccountData)?;
-        
+
 
         // <---Filling subect_account pda--->
         // Direct field assignments for zero-copy
         subject_account_data.uni_key = *uni_account.key();
         subject_account_data.subject_code = uni_account_data.subject_number;
+        let subject_code = DecimalString::new(u64::from_le_bytes(uni_account_data.subject_number));
         subject_account_data.tution_fee = args.tution_fee;
         subject_account_data.max_semester = args.max_semester;
         subject_account_data.semester_months = args.semester_months;
+        subject_account_data.min_grade = args.min_grade;
+        subject_account_data.requires_approval = args.requires_approval;
+        subject_account_data.payment_window_days = args.payment_window_days;
+        subject_account_data.late_fee = args.late_fee;
+        subject_account_data.installments = args.installments;
+        subject_account_data.max_students = args.max_students;
+        subject_account_data.prerequisite_count = args.prerequisite_count;
+        subject_account_data.prerequisites = args.prerequisites;
+        subject_account_data.transfer_credit = args.transfer_credit;
+        subject_account_data.refund_percent = args.refund_percent;
+        subject_account_data.payment_mint = *mint_usdc.key();
+        subject_account_data.collection_mint = *collection_mint.key();
         subject_account_data.subject_bump = args.bump;
 
+        // subject_account signs the CPIs below, so it can't stay borrowed
+        drop(subject_data_ref);
+
     
         // Increasing subject number in uni_account pda by 1 (uni_account_data.subject_number += 1)
         uni_account_data.subject_number = (u64::from_le_bytes(uni_account_data.subject_number) + 1).to_le_bytes();
@@ -144,28 +316,53 @@ impl <'a> AddSubjectContext <'a> for &[AccountInfo] {
         let transaction_fee = u64::from_le_bytes(vire_account_data.transaction_fee_uni);
         let fee = ((args.tution_fee()).checked_div(100).unwrap()) * transaction_fee;
 
-        // sending mint_usdc token (uni_ata_usdc --mint_usdc--> treasury)
-        pinocchio_token::instructions::TransferChecked{
+        // sending mint_usdc token (uni_ata_usdc --mint_usdc--> treasury), treasury receives the full fee
+        TransferChecked{
             from: uni_ata_usdc,
             to: treasury,
             mint: mint_usdc,
-            amount: fee,
+            amount: token_interface::gross_amount(mint_usdc, fee)?,
             authority: uni_admin,
-            decimals: Mint::from_account_info(mint_usdc)?.decimals()
+            decimals: token_interface::load_mint(mint_usdc)?.decimals(),
+            token_program,
         }.invoke()?;
 
 
 
-        // <---Making Collection For Subject---> (How can I add metadata (In FrontEnd))
+        // <---Making Collection For Subject--->
 
 
-        // // Do the Initialine part in frontend for better CU's
-        // InitializeMint2{    //(Thinking to be optional (doing this in frontend))
-        //     mint: collection_mint,
-        //     decimals: 0,
-        //     mint_authority: subject_account.key(),
-        //     freeze_authority: Some(subject_account.key()),
-        // }.invoke()?; 
+        let collection_accounts = NftMintAccounts {
+            payer: uni_admin,
+            mint: collection_mint,
+            owner: uni_admin,
+            owner_ata: uni_collection_ata,
+            system_program,
+            token_program: collection_token_program,
+            associated_token_program,
+        };
+
+        if args.metadata_mode()? == MetadataMode::TokenMetadata {
+            // Token-2022 collection mint carrying its own metadata
+            if args.mint_mode()? != MintMode::OnChain {
+                return Err(VireError::InvalidMetadataMode.into());
+            }
+
+            let uni_id = DecimalString::new(u64::from_le_bytes(uni_account_data.uni_id));
+            token_metadata::init_nft_mint_with_metadata(
+                &collection_accounts,
+                subject_account,
+                &args.metadata,
+                &[
+                    MetadataField { key: "uni_id", value: uni_id.as_bytes() },
+                    MetadataField { key: "subject_code", value: subject_code.as_bytes() },
+                ],
+                core::slice::from_ref(&signer),
+            )?;
+        } else {
+            // collection_mint is either created here or checked to be a fresh mint owned by subject_account
+            token_interface::init_or_check_nft_mint(args.mint_mode()?, &collection_accounts, subject_account.key())?;
+        }
 
         MintToChecked{
             mint: collection_mint,
@@ -173,7 +370,44 @@ impl <'a> AddSubjectContext <'a> for &[AccountInfo] {
             mint_authority: subject_account,
             amount: 1,
             decimals: 0, 
-        }.invoke_signed(&[signer])?; 
+            token_program: collection_token_program,
+        }.invoke_signed(core::slice::from_ref(&signer))?; 
+
+
+        // <---Collection Metadata (Metaplex sized collection)--->
+
+        if args.metadata_mode()? == MetadataMode::Metaplex {
+            let [collection_metadata, collection_master_edition, token_metadata_program, ..] = metadata_accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            token_metadata::check_token_metadata_program(token_metadata_program)?;
+
+            CreateMetadataAccountV3{
+                metadata: collection_metadata,
+                mint: collection_mint,
+                mint_authority: subject_account,
+                payer: uni_admin,
+                update_authority: subject_account,
+                system_program,
+                token_metadata_program,
+                args: &args.metadata,
+                collection: None,
+                collection_size: Some(0),
+            }.invoke_signed(core::slice::from_ref(&signer))?;
+
+            // Cards are verified against the collection's master edition
+            CreateMasterEditionV3{
+                edition: collection_master_edition,
+                mint: collection_mint,
+                update_authority: subject_account,
+                mint_authority: subject_account,
+                payer: uni_admin,
+                metadata: collection_metadata,
+                token_program: collection_token_program,
+                system_program,
+                token_metadata_program,
+            }.invoke_signed(&[signer])?;
+        }
 
 
         Ok(())
@@ -0,0 +1,101 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey, ProgramResult};
+
+use crate::{errors::VireError, student_account::StudentAccount, subject_account::SubjectAccount, uni_account::{UniAccount, ROLE_REGISTRAR}};
+
+
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct ApproveSemesterArgs {
+    semester: [u8; 8],
+}
+
+impl ApproveSemesterArgs {
+    pub fn new(semester: u64) -> Self {
+        Self { semester: semester.to_le_bytes() }
+    }
+
+    pub fn semester(&self) -> u64 {
+        u64::from_le_bytes(self.semester)
+    }
+}
+
+impl TryFrom<&[u8]> for ApproveSemesterArgs {
+    type Error = ProgramError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+            bytemuck::try_from_bytes::<Self>(value)
+                .copied()
+                .map_err(|_| ProgramError::InvalidInstructionData)
+    }
+}
+
+pub trait ApproveSemesterContext<'a> {
+    fn approve_semester(&self, args: &ApproveSemesterArgs) -> ProgramResult;
+}
+
+impl <'a> ApproveSemesterContext <'a> for &[AccountInfo] {
+    fn approve_semester(&self, args: &ApproveSemesterArgs) -> ProgramResult {
+        // all the required accounts for the this instruction
+        let [
+            uni_admin,
+            uni_account,
+            subject_account,
+            student_account,
+            ] = self
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Only the university's registrar (or the admin) signs off semesters
+        if !uni_admin.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if !uni_account.is_owned_by(&crate::ID)
+            || !subject_account.is_owned_by(&crate::ID)
+            || !student_account.is_owned_by(&crate::ID)
+        {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let uni_data_ref = uni_account.try_borrow_data()?;
+        let uni_account_data = bytemuck::try_from_bytes::<UniAccount>(&uni_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if !uni_account_data.has_role(uni_account.key(), uni_admin.key(), ROLE_REGISTRAR) {
+            return Err(VireError::Unauthorized.into());
+        }
+
+        uni_account_data.check_active()?;
+
+        let subject_data_ref = subject_account.try_borrow_data()?;
+        let subject_account_data = bytemuck::try_from_bytes::<SubjectAccount>(&subject_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if subject_account_data.uni_key != *uni_account.key() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mut student_data_ref = student_account.try_borrow_mut_data()?;
+        let student_account_data = bytemuck::try_from_bytes_mut::<StudentAccount>(&mut student_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        let student_seeds_with_bump = &[
+            student_account_data.student_key.as_ref(),
+            subject_account.key().as_ref(),
+            &[student_account_data.student_bump],
+        ];
+        let student_account_derived = pubkey::create_program_address(student_seeds_with_bump, &crate::ID)?;
+        if student_account_derived != *student_account.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        if i64::from_le_bytes(student_account_data.revoked_at) != 0 {
+            return Err(VireError::CredentialRevoked.into());
+        }
+
+        student_account_data.approve_semester(args.semester())
+    }
+}
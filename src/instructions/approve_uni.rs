@@ -0,0 +1,62 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use crate::{errors::VireError, uni_account::{UniAccount, UniStatus}, vire_account::VireAccount};
+
+
+
+pub trait ApproveUniContext<'a> {
+    fn approve_uni(&self) -> ProgramResult;
+}
+
+impl <'a> ApproveUniContext <'a> for &[AccountInfo] {
+    fn approve_uni(&self) -> ProgramResult {
+        change_uni_status(self, UniStatus::Pending, UniStatus::Active)
+    }
+}
+
+
+// Shared by ApproveUni, SuspendUni and ReinstateUni: moves uni_account from `from` to `to`, signed by the Vire admin
+pub fn change_uni_status(accounts: &[AccountInfo], from: UniStatus, to: UniStatus) -> ProgramResult {
+    // all the required accounts for these instructions
+    let [
+        admin,
+        vire_account,
+        uni_account,
+        ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Only the protocol admin decides which universities operate under the Vire brand
+    if !admin.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if !vire_account.is_owned_by(&crate::ID) || !uni_account.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let vire_data_ref = vire_account.try_borrow_data()?;
+    let vire_account_data = bytemuck::try_from_bytes::<VireAccount>(&vire_data_ref)
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    if vire_account_data.admin_key != *admin.key() {
+        return Err(VireError::Unauthorized.into());
+    }
+
+    let mut uni_data_ref = uni_account.try_borrow_mut_data()?;
+    let uni_account_data = bytemuck::try_from_bytes_mut::<UniAccount>(&mut uni_data_ref)
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    if uni_account_data.vire_key != *vire_account.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if uni_account_data.status()? != from {
+        return Err(VireError::InvalidUniStatus.into());
+    }
+
+    uni_account_data.status = to as u8;
+
+    Ok(())
+}
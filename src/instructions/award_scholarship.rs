@@ -0,0 +1,159 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, pubkey, seeds, sysvars::{rent::Rent, Sysvar}, ProgramResult};
+use pinocchio_system::instructions::CreateAccount;
+
+use crate::{errors::VireError, scholarship_award::ScholarshipAward, scholarship_pool::ScholarshipPool, student_account::StudentAccount, subject_account::SubjectAccount, uni_account::{UniAccount, ROLE_FINANCE}};
+
+
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct AwardArgs {
+    amount: [u8; 8],
+    bump: u8, // scholarship_award bump
+}
+
+impl AwardArgs {
+    pub fn new(amount: u64, bump: u8) -> Self {
+        Self { amount: amount.to_le_bytes(), bump }
+    }
+
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(self.amount)
+    }
+}
+
+impl TryFrom<&[u8]> for AwardArgs {
+    type Error = ProgramError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+            bytemuck::try_from_bytes::<Self>(value)
+                .copied()
+                .map_err(|_| ProgramError::InvalidInstructionData)
+    }
+}
+
+pub trait AwardScholarshipContext<'a> {
+    fn award_scholarship(&self, args: &AwardArgs) -> ProgramResult;
+}
+
+impl <'a> AwardScholarshipContext <'a> for &[AccountInfo] {
+    fn award_scholarship(&self, args: &AwardArgs) -> ProgramResult {
+        // all the required accounts for the this instruction
+        let [
+            uni_admin,
+            uni_account,
+            scholarship_pool,
+            subject_account,
+            student_account,
+            scholarship_award,
+            _system_program,
+            ] = self
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Only the university's finance staff (or the admin) awards grants
+        if !uni_admin.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if !uni_account.is_owned_by(&crate::ID)
+            || !scholarship_pool.is_owned_by(&crate::ID)
+            || !subject_account.is_owned_by(&crate::ID)
+            || !student_account.is_owned_by(&crate::ID)
+        {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let uni_data_ref = uni_account.try_borrow_data()?;
+        let uni_account_data = bytemuck::try_from_bytes::<UniAccount>(&uni_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if !uni_account_data.has_role(uni_account.key(), uni_admin.key(), ROLE_FINANCE) {
+            return Err(VireError::Unauthorized.into());
+        }
+
+        uni_account_data.check_active()?;
+
+        let mut pool_data_ref = scholarship_pool.try_borrow_mut_data()?;
+        let pool_data = bytemuck::try_from_bytes_mut::<ScholarshipPool>(&mut pool_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        let subject_data_ref = subject_account.try_borrow_data()?;
+        let subject_account_data = bytemuck::try_from_bytes::<SubjectAccount>(&subject_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        // The pool has to belong to the university and cover the student's subject
+        if pool_data.uni_key != *uni_account.key()
+            || subject_account_data.uni_key != *uni_account.key()
+            || (pool_data.subject_key != pubkey::Pubkey::default() && pool_data.subject_key != *subject_account.key())
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mut student_data_ref = student_account.try_borrow_mut_data()?;
+        let student_account_data = bytemuck::try_from_bytes_mut::<StudentAccount>(&mut student_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        let student_seeds_with_bump = &[
+            student_account_data.student_key.as_ref(),
+            subject_account.key().as_ref(),
+            &[student_account_data.student_bump],
+        ];
+        let student_account_derived = pubkey::create_program_address(student_seeds_with_bump, &crate::ID)?;
+        if student_account_derived != *student_account.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        // Grants can't exceed what donors put in
+        let total_awarded = u64::from_le_bytes(pool_data.total_awarded)
+            .checked_add(args.amount())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        if total_awarded > u64::from_le_bytes(pool_data.total_deposited) {
+            return Err(VireError::InsufficientScholarshipFunds.into());
+        }
+        pool_data.total_awarded = total_awarded.to_le_bytes();
+        student_account_data.award_remaining = (u64::from_le_bytes(student_account_data.award_remaining) + args.amount()).to_le_bytes();
+
+
+        // <---Creating Award (first grant)--->
+
+        let award_seeds_with_bump = &[b"award".as_ref(), scholarship_pool.key().as_ref(), student_account.key().as_ref(), &[args.bump]];
+        let award_derived = pubkey::create_program_address(award_seeds_with_bump, &crate::ID)?;
+        if award_derived != *scholarship_award.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        if !scholarship_award.is_owned_by(&crate::ID) {
+            let bump_ref = &[args.bump];
+            let signer_seeds = seeds!(b"award", scholarship_pool.key().as_ref(), student_account.key().as_ref(), bump_ref);
+            let signer = Signer::from(&signer_seeds);
+
+            CreateAccount{
+                from: uni_admin,
+                to: scholarship_award,
+                space: ScholarshipAward::LEN as u64,
+                owner: &crate::ID,
+                lamports: Rent::get()?.minimum_balance(ScholarshipAward::LEN),
+            }
+            .invoke_signed(&[signer])?;
+
+            let mut award_data_ref = scholarship_award.try_borrow_mut_data()?;
+            let award_data = bytemuck::try_from_bytes_mut::<ScholarshipAward>(&mut award_data_ref)
+                .map_err(|_| ProgramError::InvalidAccountData)?;
+
+            award_data.pool = *scholarship_pool.key();
+            award_data.student_account = *student_account.key();
+            award_data.award_bump = args.bump;
+        }
+
+        let mut award_data_ref = scholarship_award.try_borrow_mut_data()?;
+        let award_data = bytemuck::try_from_bytes_mut::<ScholarshipAward>(&mut award_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        award_data.awarded = (u64::from_le_bytes(award_data.awarded) + args.amount()).to_le_bytes();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, pubkey::{self, Pubkey}, seeds, sysvars::{rent::Rent, Sysvar}, ProgramResult};
+use pinocchio_system::instructions::CreateAccount;
+
+use crate::{discount_account::{DiscountAccount, DiscountKind}, errors::VireError, subject_account::SubjectAccount, uni_account::{UniAccount, ROLE_FINANCE}};
+
+
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct DiscountArgs {
+    discount_id: [u8; 8],
+    kind: u8, // 0 = percentage, 1 = fixed amount
+    value: [u8; 8],
+    expires_at: [u8; 8], // 0 = never
+    max_uses: [u8; 8], // 0 = unlimited
+    student_key: Pubkey, // zero = any student
+    bump: u8, // discount_account bump
+}
+
+impl DiscountArgs {
+    pub fn new(discount_id: u64, kind: DiscountKind, value: u64, expires_at: i64, max_uses: u64, student_key: Pubkey, bump: u8) -> Self {
+        Self {
+            discount_id: discount_id.to_le_bytes(),
+            kind: kind as u8,
+            value: value.to_le_bytes(),
+            expires_at: expires_at.to_le_bytes(),
+            max_uses: max_uses.to_le_bytes(),
+            student_key,
+            bump,
+        }
+    }
+
+    pub fn kind(&self) -> Result<DiscountKind, ProgramError> {
+        DiscountKind::try_from(self.kind)
+    }
+
+    pub fn value(&self) -> u64 {
+        u64::from_le_bytes(self.value)
+    }
+}
+
+impl TryFrom<&[u8]> for DiscountArgs {
+    type Error = ProgramError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+            bytemuck::try_from_bytes::<Self>(value)
+                .copied()
+                .map_err(|_| ProgramError::InvalidInstructionData)
+    }
+}
+
+pub trait CreateDiscountContext<'a> {
+    fn create_discount(&self, args: &DiscountArgs) -> ProgramResult;
+}
+
+impl <'a> CreateDiscountContext <'a> for &[AccountInfo] {
+    fn create_discount(&self, args: &DiscountArgs) -> ProgramResult {
+        // all the required accounts for the this instruction
+        let [
+            uni_admin,
+            uni_account,
+            subject_account,
+            discount_account,
+            _system_program,
+            ] = self
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Only the university's finance staff (or the admin) issues discounts
+        if !uni_admin.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if !uni_account.is_owned_by(&crate::ID) || !subject_account.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let uni_data_ref = uni_account.try_borrow_data()?;
+        let uni_account_data = bytemuck::try_from_bytes::<UniAccount>(&uni_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if !uni_account_data.has_role(uni_account.key(), uni_admin.key(), ROLE_FINANCE) {
+            return Err(VireError::Unauthorized.into());
+        }
+
+        uni_account_data.check_active()?;
+
+        let subject_data_ref = subject_account.try_borrow_data()?;
+        let subject_account_data = bytemuck::try_from_bytes::<SubjectAccount>(&subject_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if subject_account_data.uni_key != *uni_account.key() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if args.kind()? == DiscountKind::Percent && args.value() > 100 {
+            return Err(VireError::InvalidDiscount.into());
+        }
+
+
+        // <---Creating Discount--->
+
+        let discount_seeds_with_bump = &[b"discount".as_ref(), subject_account.key().as_ref(), &args.discount_id, &[args.bump]];
+        let discount_account_derived = pubkey::create_program_address(discount_seeds_with_bump, &crate::ID)?;
+        if discount_account_derived != *discount_account.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        let bump_ref = &[args.bump];
+        let signer_seeds = seeds!(b"discount", subject_account.key().as_ref(), &args.discount_id, bump_ref);
+        let signer = Signer::from(&signer_seeds);
+
+        CreateAccount{
+            from: uni_admin,
+            to: discount_account,
+            space: DiscountAccount::LEN as u64,
+            owner: &crate::ID,
+            lamports: Rent::get()?.minimum_balance(DiscountAccount::LEN),
+        }
+        .invoke_signed(&[signer])?;
+
+        let mut discount_data_ref = discount_account.try_borrow_mut_data()?;
+        let discount_data = bytemuck::try_from_bytes_mut::<DiscountAccount>(&mut discount_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        discount_data.subject_key = *subject_account.key();
+        discount_data.discount_id = args.discount_id;
+        discount_data.kind = args.kind;
+        discount_data.value = args.value;
+        discount_data.expires_at = args.expires_at;
+        discount_data.max_uses = args.max_uses;
+        discount_data.student_key = args.student_key;
+        discount_data.discount_bump = args.bump;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, pubkey, seeds, sysvars::{rent::Rent, Sysvar}, ProgramResult};
+use pinocchio_system::instructions::CreateAccount;
+
+use crate::{errors::VireError, subject_account::SubjectAccount, uni_account::{UniAccount, ROLE_REGISTRAR}, waitlist_account::WaitlistAccount};
+
+
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct WaitlistArgs {
+    offer_seconds: [u8; 8], // time the next wallet has to enrol once a spot is offered
+    bump: u8, // waitlist_account bump
+}
+
+impl WaitlistArgs {
+    pub fn new(offer_seconds: i64, bump: u8) -> Self {
+        Self { offer_seconds: offer_seconds.to_le_bytes(), bump }
+    }
+
+    pub fn offer_seconds(&self) -> i64 {
+        i64::from_le_bytes(self.offer_seconds)
+    }
+
+    // An offer that expires as it is made would pass every spot down the queue
+    pub fn check(&self) -> ProgramResult {
+        if self.offer_seconds() <= 0 {
+            return Err(VireError::InvalidWaitlistOffer.into());
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&[u8]> for WaitlistArgs {
+    type Error = ProgramError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+            bytemuck::try_from_bytes::<Self>(value)
+                .copied()
+                .map_err(|_| ProgramError::InvalidInstructionData)
+    }
+}
+
+pub trait CreateWaitlistContext<'a> {
+    fn create_waitlist(&self, args: &WaitlistArgs) -> ProgramResult;
+}
+
+impl <'a> CreateWaitlistContext <'a> for &[AccountInfo] {
+    fn create_waitlist(&self, args: &WaitlistArgs) -> ProgramResult {
+        // all the required accounts for the this instruction
+        let [
+            uni_admin,
+            uni_account,
+            subject_account,
+            waitlist_account,
+            _system_program,
+            ] = self
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Only the university's registrar (or the admin) opens a waitlist
+        if !uni_admin.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if !uni_account.is_owned_by(&crate::ID) || !subject_account.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let uni_data_ref = uni_account.try_borrow_data()?;
+        let uni_account_data = bytemuck::try_from_bytes::<UniAccount>(&uni_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if !uni_account_data.has_role(uni_account.key(), uni_admin.key(), ROLE_REGISTRAR) {
+            return Err(VireError::Unauthorized.into());
+        }
+
+        uni_account_data.check_active()?;
+
+        let subject_data_ref = subject_account.try_borrow_data()?;
+        let subject_account_data = bytemuck::try_from_bytes::<SubjectAccount>(&subject_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if subject_account_data.uni_key != *uni_account.key() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        args.check()?;
+
+
+        // <---Creating Waitlist--->
+
+        let waitlist_seeds_with_bump = &[b"waitlist".as_ref(), subject_account.key().as_ref(), &[args.bump]];
+        let waitlist_account_derived = pubkey::create_program_address(waitlist_seeds_with_bump, &crate::ID)?;
+        if waitlist_account_derived != *waitlist_account.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        let bump_ref = &[args.bump];
+        let signer_seeds = seeds!(b"waitlist", subject_account.key().as_ref(), bump_ref);
+        let signer = Signer::from(&signer_seeds);
+
+        CreateAccount{
+            from: uni_admin,
+            to: waitlist_account,
+            space: WaitlistAccount::LEN as u64,
+            owner: &crate::ID,
+            lamports: Rent::get()?.minimum_balance(WaitlistAccount::LEN),
+        }
+        .invoke_signed(&[signer])?;
+
+        let mut waitlist_data_ref = waitlist_account.try_borrow_mut_data()?;
+        let waitlist_data = bytemuck::try_from_bytes_mut::<WaitlistAccount>(&mut waitlist_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        waitlist_data.subject_key = *subject_account.key();
+        waitlist_data.offer_seconds = args.offer_seconds;
+        waitlist_data.waitlist_bump = args.bump;
+
+        Ok(())
+    }
+}
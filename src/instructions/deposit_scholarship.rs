@@ -0,0 +1,98 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use crate::{scholarship_pool::ScholarshipPool, token_interface::{self, TransferChecked}, uni_account::UniAccount};
+
+
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct DepositArgs {
+    amount: [u8; 8],
+}
+
+impl DepositArgs {
+    pub fn new(amount: u64) -> Self {
+        Self { amount: amount.to_le_bytes() }
+    }
+
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(self.amount)
+    }
+}
+
+impl TryFrom<&[u8]> for DepositArgs {
+    type Error = ProgramError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+            bytemuck::try_from_bytes::<Self>(value)
+                .copied()
+                .map_err(|_| ProgramError::InvalidInstructionData)
+    }
+}
+
+pub trait DepositScholarshipContext<'a> {
+    fn deposit_scholarship(&self, args: &DepositArgs) -> ProgramResult;
+}
+
+impl <'a> DepositScholarshipContext <'a> for &[AccountInfo] {
+    fn deposit_scholarship(&self, args: &DepositArgs) -> ProgramResult {
+        // all the required accounts for the this instruction
+        let [
+            donor,
+            uni_account,
+            scholarship_pool,
+            mint,
+            donor_ata,
+            vault,
+            token_program,
+            ] = self
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Anyone can donate
+        if !donor.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if !uni_account.is_owned_by(&crate::ID) || !scholarship_pool.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        token_interface::check_token_program(mint, token_program)?;
+
+        let uni_data_ref = uni_account.try_borrow_data()?;
+        let uni_account_data = bytemuck::try_from_bytes::<UniAccount>(&uni_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        // Donations only go to pools of active universities
+        uni_account_data.check_active()?;
+
+        let mut pool_data_ref = scholarship_pool.try_borrow_mut_data()?;
+        let pool_data = bytemuck::try_from_bytes_mut::<ScholarshipPool>(&mut pool_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if pool_data.uni_key != *uni_account.key() || pool_data.mint != *mint.key() || pool_data.vault != *vault.key() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // With a Token-2022 transfer fee the donor covers it, so the pool receives the full amount
+        TransferChecked{
+            from: donor_ata,
+            mint,
+            to: vault,
+            authority: donor,
+            amount: token_interface::gross_amount(mint, args.amount())?,
+            decimals: token_interface::load_mint(mint)?.decimals(),
+            token_program,
+        }.invoke()?;
+
+        pool_data.total_deposited = u64::from_le_bytes(pool_data.total_deposited)
+            .checked_add(args.amount())
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            .to_le_bytes();
+
+        Ok(())
+    }
+}
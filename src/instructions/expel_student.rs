@@ -0,0 +1,165 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, pubkey, seeds, sysvars::{clock::Clock, Sysvar}, ProgramResult};
+
+use crate::{errors::VireError, instructions::suspend_student::refund_sponsor, student_account::StudentAccount, subject_account::SubjectAccount, token_interface::{self, BurnChecked, ThawAccount}, uni_account::{UniAccount, ROLE_FINANCE, ROLE_REGISTRAR}};
+
+
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct ExpelArgs {
+    refund: u8, // 1 = refund the sponsor under the subject's refund policy
+}
+
+impl ExpelArgs {
+    pub fn new(refund: bool) -> Self {
+        Self { refund: refund as u8 }
+    }
+
+    pub fn refund(&self) -> bool {
+        self.refund != 0
+    }
+}
+
+impl TryFrom<&[u8]> for ExpelArgs {
+    type Error = ProgramError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+            bytemuck::try_from_bytes::<Self>(value)
+                .copied()
+                .map_err(|_| ProgramError::InvalidInstructionData)
+    }
+}
+
+pub trait ExpelStudentContext<'a> {
+    fn expel_student(&self, args: &ExpelArgs) -> ProgramResult;
+}
+
+impl <'a> ExpelStudentContext <'a> for &[AccountInfo] {
+    fn expel_student(&self, args: &ExpelArgs) -> ProgramResult {
+        // all the required accounts for the this instruction
+        let [
+            uni_admin,
+            uni_account,
+            subject_account,
+            student_account,
+            card_mint,
+            student_card_ata,
+            card_token_program,
+            refund_accounts @ ..
+            ] = self
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Only the university's registrar (or the admin) can expel
+        if !uni_admin.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if !uni_account.is_owned_by(&crate::ID)
+            || !subject_account.is_owned_by(&crate::ID)
+            || !student_account.is_owned_by(&crate::ID)
+        {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // card_mint can be a Token or Token-2022 mint
+        token_interface::check_token_program(card_mint, card_token_program)?;
+
+        let uni_data_ref = uni_account.try_borrow_data()?;
+        let uni_account_data = bytemuck::try_from_bytes::<UniAccount>(&uni_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if !uni_account_data.has_role(uni_account.key(), uni_admin.key(), ROLE_REGISTRAR) {
+            return Err(VireError::Unauthorized.into());
+        }
+
+        uni_account_data.check_active()?;
+
+        let mut subject_data_ref = subject_account.try_borrow_mut_data()?;
+        let subject_account_data = bytemuck::try_from_bytes_mut::<SubjectAccount>(&mut subject_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if subject_account_data.uni_key != *uni_account.key() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mut student_data_ref = student_account.try_borrow_mut_data()?;
+        let student_account_data = bytemuck::try_from_bytes_mut::<StudentAccount>(&mut student_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        let student_key = student_account_data.student_key;
+        let student_bump = [student_account_data.student_bump];
+        let student_seeds_with_bump = &[student_key.as_ref(), subject_account.key().as_ref(), &student_bump];
+        let student_account_derived = pubkey::create_program_address(student_seeds_with_bump, &crate::ID)?;
+        if student_account_derived != *student_account.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        if i64::from_le_bytes(student_account_data.graduated_at) != 0 {
+            return Err(VireError::AlreadyGraduated.into());
+        }
+
+        if student_account_data.is_expelled() {
+            return Err(VireError::StudentExpelled.into());
+        }
+
+
+        // <---Closing The Enrolment--->
+
+        // The StudentAccount stays as the record, so the wallet can't simply enrol again
+        student_account_data.expelled_at = Clock::get()?.unix_timestamp.to_le_bytes();
+
+        if args.refund() {
+            if !uni_account_data.has_role(uni_account.key(), uni_admin.key(), ROLE_FINANCE) {
+                return Err(VireError::Unauthorized.into());
+            }
+            refund_sponsor(student_account_data, subject_account_data, uni_account, uni_account_data, uni_admin, refund_accounts)?;
+        }
+
+        subject_account_data.enrolled_count = u64::from_le_bytes(subject_account_data.enrolled_count).saturating_sub(1).to_le_bytes();
+
+        // student_account signs the CPIs below, so it can't stay borrowed
+        drop(student_data_ref);
+        drop(subject_data_ref);
+
+
+        // <---Burning The Card--->
+
+        // Token account layout: mint, owner, amount, delegate (COption) at 72, ...
+        let burnable = {
+            let data = student_card_ata.try_borrow_data()?;
+            if data.len() < 108 || data[..32] != card_mint.key()[..] || data[32..64] != student_key[..] {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            data[72..76] == [1, 0, 0, 0] && data[76..108] == student_account.key()[..]
+        };
+
+        // Cards issued before student_account was made their delegate can't be burnt, they stay frozen for good
+        if burnable {
+            let signer_seeds = seeds!(student_key.as_ref(), subject_account.key().as_ref(), &student_bump);
+            let signer = Signer::from(&signer_seeds);
+
+            ThawAccount{
+                account: student_card_ata,
+                mint: card_mint,
+                freeze_authority: student_account,
+                token_program: card_token_program,
+            }
+            .invoke_signed(core::slice::from_ref(&signer))?;
+
+            BurnChecked{
+                account: student_card_ata,
+                mint: card_mint,
+                authority: student_account,
+                amount: 1,
+                decimals: 0,
+                token_program: card_token_program,
+            }
+            .invoke_signed(&[signer])?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, pubkey, seeds, sysvars::{rent::Rent, Sysvar}, ProgramResult};
+use pinocchio_system::instructions::CreateAccount;
+
+use crate::{errors::VireError, scholarship_pool::ScholarshipPool, subject_account::SubjectAccount, token_interface::{self, CreateAssociatedTokenAccount}, uni_account::{UniAccount, ROLE_FINANCE}};
+
+
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct PoolArgs {
+    bump: u8, // scholarship_pool bump
+}
+
+impl PoolArgs {
+    pub fn new(bump: u8) -> Self {
+        Self { bump }
+    }
+}
+
+impl TryFrom<&[u8]> for PoolArgs {
+    type Error = ProgramError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+            bytemuck::try_from_bytes::<Self>(value)
+                .copied()
+                .map_err(|_| ProgramError::InvalidInstructionData)
+    }
+}
+
+pub trait InitializeScholarshipPoolContext<'a> {
+    fn initialize_scholarship_pool(&self, args: &PoolArgs) -> ProgramResult;
+}
+
+impl <'a> InitializeScholarshipPoolContext <'a> for &[AccountInfo] {
+    fn initialize_scholarship_pool(&self, args: &PoolArgs) -> ProgramResult {
+        // all the required accounts for the this instruction
+        let [
+            uni_admin,
+            uni_account,
+            scope,
+            scholarship_pool,
+            mint,
+            vault,
+            system_program,
+            token_program,
+            associated_token_program,
+            ] = self
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !uni_admin.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if !uni_account.is_owned_by(&crate::ID) || !scope.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        token_interface::check_token_program(mint, token_program)?;
+
+        {
+            let uni_data_ref = uni_account.try_borrow_data()?;
+            let uni_account_data = bytemuck::try_from_bytes::<UniAccount>(&uni_data_ref)
+                .map_err(|_| ProgramError::InvalidAccountData)?;
+
+            if !uni_account_data.has_role(uni_account.key(), uni_admin.key(), ROLE_FINANCE) {
+                return Err(VireError::Unauthorized.into());
+            }
+
+            uni_account_data.check_active()?;
+        }
+
+        // scope is the university itself or one of its subjects, which only takes its payment mint
+        let subject_key = if scope.key() == uni_account.key() {
+            pubkey::Pubkey::default()
+        } else {
+            let subject_data_ref = scope.try_borrow_data()?;
+            let subject_account_data = bytemuck::try_from_bytes::<SubjectAccount>(&subject_data_ref)
+                .map_err(|_| ProgramError::InvalidAccountData)?;
+
+            if subject_account_data.uni_key != *uni_account.key() {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            if subject_account_data.payment_mint != *mint.key() {
+                return Err(VireError::PaymentMintMismatch.into());
+            }
+            *scope.key()
+        };
+
+
+        // <---Creating Pool--->
+
+        let pool_seeds_with_bump = &[b"scholarship".as_ref(), scope.key().as_ref(), &[args.bump]];
+        let pool_derived = pubkey::create_program_address(pool_seeds_with_bump, &crate::ID)?;
+        if pool_derived != *scholarship_pool.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        let bump_ref = &[args.bump];
+        let signer_seeds = seeds!(b"scholarship", scope.key().as_ref(), bump_ref);
+        let signer = Signer::from(&signer_seeds);
+
+        CreateAccount{
+            from: uni_admin,
+            to: scholarship_pool,
+            space: ScholarshipPool::LEN as u64,
+            owner: &crate::ID,
+            lamports: Rent::get()?.minimum_balance(ScholarshipPool::LEN),
+        }
+        .invoke_signed(&[signer])?;
+
+        // The vault is the pool's ATA, so only the pool PDA can move donations out
+        CreateAssociatedTokenAccount{
+            payer: uni_admin,
+            ata: vault,
+            owner: scholarship_pool,
+            mint,
+            system_program,
+            token_program,
+            associated_token_program,
+        }
+        .invoke()?;
+
+        let mut pool_data_ref = scholarship_pool.try_borrow_mut_data()?;
+        let pool_data = bytemuck::try_from_bytes_mut::<ScholarshipPool>(&mut pool_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        pool_data.uni_key = *uni_account.key();
+        pool_data.subject_key = subject_key;
+        pool_data.mint = *mint.key();
+        pool_data.vault = *vault.key();
+        pool_data.pool_bump = args.bump;
+
+        Ok(())
+    }
+}
@@ -1,24 +1,44 @@
 use bytemuck::{Pod, Zeroable};
-use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, pubkey, seeds, sysvars::{clock::Clock, rent::Rent, Sysvar}, ProgramResult};
+use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, pubkey::{self, Pubkey}, seeds, sysvars::{clock::Clock, rent::Rent, Sysvar}, ProgramResult};
 use pinocchio_system::instructions::CreateAccount;
-use pinocchio_token::instructions::{FreezeAccount, SetAuthority};
 
-use crate::{student_account::StudentAccount, uni_account::UniAccount};
+use crate::{errors::VireError, student_account::StudentAccount, subject_account::SubjectAccount, token_interface::{self, Approve, FreezeAccount, MintMode, MintToChecked, NftMintAccounts, SetAuthority}, token_metadata::{self, CreateMetadataAccountV3, DecimalString, MetadataArgs, MetadataField, MetadataMode, VerifySizedCollectionItem}, uni_account::UniAccount};
 
 
 
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
 pub struct StudentArgs {
+    mint_mode: u8, // 0 = card_mint created by the frontend, 1 = created on-chain (card_mint signs)
+    metadata_mode: u8, // 0 = no metadata, 1 = Metaplex, verified in the subject's collection, 2 = Token-2022 metadata extensions
+    metadata: MetadataArgs,
     bump: u8,
 }
 
+impl StudentArgs {
+    pub fn new(mint_mode: MintMode, metadata_mode: MetadataMode, metadata: MetadataArgs, bump: u8) -> Self {
+        Self { mint_mode: mint_mode as u8, metadata_mode: metadata_mode as u8, metadata, bump }
+    }
+
+    pub fn mint_mode(&self) -> Result<MintMode, ProgramError> {
+        MintMode::try_from(self.mint_mode)
+    }
+
+    pub fn metadata_mode(&self) -> Result<MetadataMode, ProgramError> {
+        MetadataMode::try_from(self.metadata_mode)
+    }
+
+    pub fn bump(&self) -> u8 {
+        self.bump
+    }
+}
+
 impl TryFrom<&[u8]> for StudentArgs {
     type Error = ProgramError;
 
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
             bytemuck::try_from_bytes::<Self>(value)
-                .map(|reference| *reference)
+                .copied()
                 .map_err(|_| ProgramError::InvalidInstructionData)
         
     }
@@ -36,11 +56,13 @@ impl <'a> InitializeStudentContext <'a> for &[AccountInfo] {
             student_account,
             subject_account,
             uni_account,
-            vire_account, 
+            _vire_account, 
             card_mint,
             student_card_ata,
-            _system_program, 
-            _token_program,
+            system_program, 
+            token_program,
+            associated_token_program,
+            metadata_accounts @ ..
             ] = self 
         else {
             return Err(ProgramError::NotEnoughAccountKeys);
@@ -77,6 +99,51 @@ impl <'a> InitializeStudentContext <'a> for &[AccountInfo] {
         if student_account_derived != student_account.key().as_ref() {
             return Err(ProgramError::InvalidSeeds);
         }
+
+        // <---Intake And Capacity--->
+
+        if !subject_account.is_owned_by(&crate::ID) || !uni_account.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // Only approved universities that aren't suspended enrol students
+        {
+            let uni_data_ref = uni_account.try_borrow_data()?;
+            bytemuck::try_from_bytes::<UniAccount>(&uni_data_ref)
+                .map_err(|_| ProgramError::InvalidAccountData)?
+                .check_active()?;
+        }
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        let (cohort_start, prerequisite_count, prerequisites) = {
+            let mut subject_data_ref = subject_account.try_borrow_mut_data()?;
+            let subject_account_data = bytemuck::try_from_bytes_mut::<SubjectAccount>(&mut subject_data_ref)
+                .map_err(|_| ProgramError::InvalidAccountData)?;
+
+            if subject_account_data.uni_key != *uni_account.key() {
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            // Subjects with intake cohorts only enrol while one of their windows is open
+            let cohort_start = if subject_account_data.cohort_count == 0 {
+                None
+            } else {
+                let cohort = subject_account_data.open_cohort(current_timestamp).ok_or(VireError::EnrolmentClosed)?;
+                Some(cohort.starts_at)
+            };
+
+            // The waitlisted wallet takes the spot held for it
+            if !subject_account_data.take_spot(student.key(), current_timestamp) {
+                return Err(VireError::SubjectFull.into());
+            }
+            (cohort_start, subject_account_data.prerequisite_count as usize, subject_account_data.prerequisites)
+        };
+
+
+        // <---Prerequisites--->
+
+        let metadata_accounts = check_prerequisites(student.key(), &prerequisites[..prerequisite_count], metadata_accounts)?;
+
         let bump_ref = &[args.bump];
         
         // creating signer seeds vire pda 
@@ -90,7 +157,7 @@ impl <'a> InitializeStudentContext <'a> for &[AccountInfo] {
             owner: &crate::ID,
             lamports: Rent::get()?.minimum_balance(StudentAccount::LEN),
         }
-        .invoke_signed(&[signer.clone()])?;
+        .invoke_signed(core::slice::from_ref(&signer))?;
 
 
         let mut uni_data_ref = uni_account.try_borrow_mut_data()?;
@@ -102,12 +169,17 @@ impl <'a> InitializeStudentContext <'a> for &[AccountInfo] {
             .map_err(|_| ProgramError::InvalidAccountData)?;
         
 
-        let current_time = ((Clock::get()?.unix_timestamp)).to_le_bytes();
+        let current_time = current_timestamp.to_le_bytes();
         // let current_time: i64 = Clock::get()?.unix_timestamp;
 
         student_account_data.student_key = *student.key();
         student_account_data.student_id = uni_account_data.student_number;
+        let student_id = uni_account_data.student_number;
         student_account_data.time_start = current_time; 
+        if let Some(starts_at) = cohort_start {
+            student_account_data.time_start = starts_at;
+            student_account_data.cohort_start = 1;
+        }
         student_account_data.semesters = (1u64).to_le_bytes();
         student_account_data.student_bump = args.bump;
 
@@ -115,46 +187,233 @@ impl <'a> InitializeStudentContext <'a> for &[AccountInfo] {
 
         uni_account_data.student_number = (u64::from_le_bytes(uni_account_data.student_number) + 1).to_le_bytes();
 
+        // student_account signs the CPIs below, so it can't stay borrowed
+        drop(student_data_ref);
+
+
+        // <---Card NFT--->
+
+        issue_card(
+            &CardAccounts {
+                student,
+                student_account,
+                subject_account,
+                card_mint,
+                student_card_ata,
+                system_program,
+                token_program,
+                associated_token_program,
+                metadata_accounts,
+            },
+            args,
+            uni_account_data.uni_id,
+            student_id,
+            current_time,
+            &signer,
+        )?;
 
-        // <---Minting Card Nft---> (How can I add metadata (In FrontEnd))
+        Ok(())
+    }
+}
 
 
-        // // Do the Initialine part in frontend for better CU's
-        // pinocchio_token::instructions::InitializeMint2{   //(Thinking to be optional (doing this in frontend))
-        //     mint: card_mint,
-        //     decimals: 0,
-        //     mint_authority: student_account.key(),
-        //     freeze_authority: Some(student_account.key()),
-        // }.invoke()?; 
+// Card NFT of an enrolment, minted to the student and frozen under student_account
+pub struct CardAccounts<'a> {
+    pub student: &'a AccountInfo,
+    pub student_account: &'a AccountInfo,
+    pub subject_account: &'a AccountInfo,
+    pub card_mint: &'a AccountInfo,
+    pub student_card_ata: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub associated_token_program: &'a AccountInfo,
+    pub metadata_accounts: &'a [AccountInfo],
+}
 
-        pinocchio_token::instructions::MintToChecked{
-            mint: card_mint,
-            account: student_card_ata,
-            mint_authority: student_account,
-            amount: 1,
-            decimals: 0, 
+// Shared by InitializeStudent and TransferEnrollment, `signer` holds the student_account seeds
+pub fn issue_card(accounts: &CardAccounts, args: &StudentArgs, uni_id: [u8; 8], student_id: [u8; 8], enrolled_at: [u8; 8], signer: &Signer) -> ProgramResult {
+    let &CardAccounts {
+        student,
+        student_account,
+        subject_account,
+        card_mint,
+        student_card_ata,
+        system_program,
+        token_program,
+        associated_token_program,
+        metadata_accounts,
+    } = accounts;
+
+    // <---Minting Card Nft--->
+
+    let card_accounts = NftMintAccounts {
+        payer: student,
+        mint: card_mint,
+        owner: student,
+        owner_ata: student_card_ata,
+        system_program,
+        token_program,
+        associated_token_program,
+    };
+
+    if args.metadata_mode()? == MetadataMode::TokenMetadata {
+        // Token-2022 card mint carrying its own metadata
+        if args.mint_mode()? != MintMode::OnChain {
+            return Err(VireError::InvalidMetadataMode.into());
         }
-        .invoke_signed(&[signer.clone()])?; 
 
+        let subject_data_ref = subject_account.try_borrow_data()?;
+        let subject_account_data = bytemuck::try_from_bytes::<SubjectAccount>(&subject_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
 
-        // <---Staking(Freezing)---> 
+        let uni_id = DecimalString::new(u64::from_le_bytes(uni_id));
+        let subject_code = DecimalString::new(u64::from_le_bytes(subject_account_data.subject_code));
+        let student_id = DecimalString::new(u64::from_le_bytes(student_id));
+        let enrolment_date = DecimalString::new(i64::from_le_bytes(enrolled_at) as u64);
+        drop(subject_data_ref);
 
-        SetAuthority{
-            account: student_card_ata,
-            authority: student, 
-            authority_type: pinocchio_token::instructions::AuthorityType::FreezeAccount,
-            new_authority: Some(student_account.key()),
-        }.invoke()?;
+        token_metadata::init_nft_mint_with_metadata(
+            &card_accounts,
+            student_account,
+            &args.metadata,
+            &[
+                MetadataField { key: "uni_id", value: uni_id.as_bytes() },
+                MetadataField { key: "subject_code", value: subject_code.as_bytes() },
+                MetadataField { key: "enrolment_date", value: enrolment_date.as_bytes() },
+                MetadataField { key: "student_id", value: student_id.as_bytes() },
+            ],
+            core::slice::from_ref(signer),
+        )?;
+    } else {
+        // card_mint (Token or Token-2022) is either created here or checked to be a fresh mint owned by student_account
+        token_interface::init_or_check_nft_mint(args.mint_mode()?, &card_accounts, student_account.key())?;
+    }
 
-        FreezeAccount{
-            account: student_card_ata,
-            mint: card_mint,
-            freeze_authority: student_account,
+    MintToChecked{
+        mint: card_mint,
+        account: student_card_ata,
+        mint_authority: student_account,
+        amount: 1,
+        decimals: 0, 
+        token_program,
+    }
+    .invoke_signed(core::slice::from_ref(signer))?; 
+
+
+    // <---Card Metadata (member of the subject's Metaplex collection)--->
+
+    if args.metadata_mode()? == MetadataMode::Metaplex {
+        let [card_metadata, collection_mint, collection_metadata, collection_master_edition, token_metadata_program, ..] = metadata_accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        token_metadata::check_token_metadata_program(token_metadata_program)?;
+
+        let subject_data_ref = subject_account.try_borrow_data()?;
+        let subject_account_data = bytemuck::try_from_bytes::<SubjectAccount>(&subject_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if subject_account_data.collection_mint != *collection_mint.key() {
+            return Err(VireError::CollectionMintMismatch.into());
         }
-        .invoke_signed(&[signer])?;
 
-    
+        // subject_account is the collection's update authority
+        let subject_code = [u64::from_le_bytes(subject_account_data.subject_code) as u8];
+        let subject_bump = [subject_account_data.subject_bump];
+        let uni_key = subject_account_data.uni_key;
+        drop(subject_data_ref);
+        let subject_signer_seeds = seeds!(uni_key.as_ref(), &subject_code, &subject_bump);
+        let subject_signer = Signer::from(&subject_signer_seeds);
 
-        Ok(())
+        CreateMetadataAccountV3{
+            metadata: card_metadata,
+            mint: card_mint,
+            mint_authority: student_account,
+            payer: student,
+            update_authority: student_account,
+            system_program,
+            token_metadata_program,
+            args: &args.metadata,
+            collection: Some(collection_mint.key()),
+            collection_size: None,
+        }.invoke_signed(core::slice::from_ref(signer))?;
+
+        VerifySizedCollectionItem{
+            metadata: card_metadata,
+            collection_authority: subject_account,
+            payer: student,
+            collection_mint,
+            collection_metadata,
+            collection_master_edition,
+            token_metadata_program,
+        }.invoke_signed(&[subject_signer])?;
+    }
+
+
+    // <---Staking(Freezing)---> 
+
+    // student_account can burn the card if the university expels the student
+    Approve{
+        account: student_card_ata,
+        delegate: student_account,
+        owner: student,
+        amount: 1,
+        token_program,
+    }.invoke()?;
+
+    SetAuthority{
+        account: student_card_ata,
+        authority: student, 
+        authority_type: pinocchio_token::instructions::AuthorityType::FreezeAccount,
+        new_authority: Some(student_account.key()),
+        token_program,
+    }.invoke()?;
+
+    FreezeAccount{
+        account: student_card_ata,
+        mint: card_mint,
+        freeze_authority: student_account,
+        token_program,
     }
-}
\ No newline at end of file
+    .invoke_signed(core::slice::from_ref(signer))?;
+
+    Ok(())
+}
+
+
+// The last account per prerequisite proves it was completed, returns the accounts in front of them
+pub fn check_prerequisites<'a>(student: &Pubkey, prerequisites: &[Pubkey], accounts: &'a [AccountInfo]) -> Result<&'a [AccountInfo], ProgramError> {
+    let proofs_start = accounts
+        .len()
+        .checked_sub(prerequisites.len())
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let (accounts, proofs) = accounts.split_at(proofs_start);
+
+    for (prerequisite, proof) in prerequisites.iter().zip(proofs) {
+        check_prerequisite(student, prerequisite, proof)?;
+    }
+
+    Ok(accounts)
+}
+
+// A prerequisite is completed when the student's StudentAccount in it shows a graduation that wasn't revoked,
+// diplomas alone aren't enough since anyone can mint a token under the subject's key as mint authority
+fn check_prerequisite(student: &Pubkey, prerequisite: &Pubkey, proof: &AccountInfo) -> ProgramResult {
+    if !proof.is_owned_by(&crate::ID) {
+        return Err(VireError::PrerequisiteNotMet.into());
+    }
+
+    let student_data_ref = proof.try_borrow_data()?;
+    let student_account_data = bytemuck::try_from_bytes::<StudentAccount>(&student_data_ref)
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    if !student_account_data.completed() {
+        return Err(VireError::PrerequisiteNotMet.into());
+    }
+
+    let student_seeds_with_bump = &[student.as_ref(), prerequisite.as_ref(), &[student_account_data.student_bump]];
+    if pubkey::create_program_address(student_seeds_with_bump, &crate::ID)? != *proof.key() {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    Ok(())
+}
@@ -3,7 +3,7 @@ use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::P
 use pinocchio_system::instructions::CreateAccount;
 
 
-use crate::{uni_account::UniAccount, vire_account::VireAccount};
+use crate::{uni_account::{UniAccount, UniStatus}, vire_account::VireAccount};
 
 
 
@@ -13,12 +13,18 @@ pub struct UniArgs {
     bump: u8,
 }
 
+impl UniArgs {
+    pub fn new(bump: u8) -> Self {
+        Self { bump }
+    }
+}
+
 impl TryFrom<&[u8]> for UniArgs {
     type Error = ProgramError;
 
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
             bytemuck::try_from_bytes::<Self>(value)
-                .map(|reference| *reference)
+                .copied()
                 .map_err(|_| ProgramError::InvalidInstructionData)
         
     }
@@ -88,6 +94,9 @@ impl <'a> InitializeUniContext <'a> for &[AccountInfo] {
         uni_account_data.uni_id = vire_account_data.uni_number;
         uni_account_data.subject_number = (0u64).to_le_bytes();     
         uni_account_data.student_number = (0u64).to_le_bytes();     
+        uni_account_data.staff_count = 0;
+        // Nothing can be offered until the Vire admin approves the university
+        uni_account_data.status = UniStatus::Pending as u8;
         uni_account_data.uni_bump = args.bump;
 
         
@@ -1,9 +1,8 @@
 use bytemuck::{Pod, Zeroable};
 use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, pubkey, seeds, sysvars::{rent::Rent, Sysvar}, ProgramResult};
 use pinocchio_system::instructions::CreateAccount;
-use pinocchio_token::instructions::InitializeAccount;
 
-use crate::vire_account::VireAccount;
+use crate::{token_interface, vire_account::VireAccount};
 
 
 
@@ -17,11 +16,19 @@ pub struct VireArgs {
 
 
 impl VireArgs {
-    fn transaction_fee_uni(&self) -> u64 {
+    pub fn new(transaction_fee_uni: u64, transaction_fee_student: u64, bump: u8) -> Self {
+        Self {
+            transaction_fee_uni: transaction_fee_uni.to_le_bytes(),
+            transaction_fee_student: transaction_fee_student.to_le_bytes(),
+            bump,
+        }
+    }
+
+    pub fn transaction_fee_uni(&self) -> u64 {
         u64::from_le_bytes(self.transaction_fee_uni)
     }
 
-    fn transaction_fee_student(&self) -> u64 {
+    pub fn transaction_fee_student(&self) -> u64 {
         u64::from_le_bytes(self.transaction_fee_student)
     }
 }
@@ -31,7 +38,7 @@ impl TryFrom<&[u8]> for VireArgs {
 
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
             bytemuck::try_from_bytes::<Self>(value)
-                .map(|reference| *reference)
+                .copied()
                 .map_err(|_| ProgramError::InvalidInstructionData)
         
     }
@@ -48,7 +55,7 @@ impl <'a> InitializeVireContext <'a> for &[AccountInfo] {
             admin, 
             mint_usdc, 
             vire_account, 
-            treasury, 
+            _treasury, 
             _system_program, 
             _token_program
             ] = self 
@@ -63,6 +70,9 @@ impl <'a> InitializeVireContext <'a> for &[AccountInfo] {
             return Err(ProgramError::MissingRequiredSignature);
         }
 
+        // mint_usdc becomes the first approved payment mint
+        token_interface::load_mint(mint_usdc)?;
+
         let vire_seeds_with_bump = &[b"vire", admin.key().as_ref(), &[args.bump]];
         let vire_account_derived = pubkey::create_program_address(vire_seeds_with_bump, &crate::ID)?;
 
@@ -98,6 +108,8 @@ impl <'a> InitializeVireContext <'a> for &[AccountInfo] {
         vire_account_data.uni_number = (1u64).to_le_bytes(); //<---------- explain please (any other options)
         vire_account_data.transaction_fee_uni = args.transaction_fee_uni;
         vire_account_data.transaction_fee_student = args.transaction_fee_student;
+        vire_account_data.payment_mint_count = 1;
+        vire_account_data.payment_mints[0] = *mint_usdc.key();
         vire_account_data.vire_bump = args.bump;
 
         // Adding(setting-up(adding)) the data to state (Read-Write)
@@ -0,0 +1,56 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, sysvars::{clock::Clock, Sysvar}, ProgramResult};
+
+use crate::{subject_account::SubjectAccount, uni_account::UniAccount, waitlist_account::WaitlistAccount};
+
+
+
+pub trait JoinWaitlistContext<'a> {
+    fn join_waitlist(&self) -> ProgramResult;
+}
+
+impl <'a> JoinWaitlistContext <'a> for &[AccountInfo] {
+    fn join_waitlist(&self) -> ProgramResult {
+        // all the required accounts for the this instruction
+        let [
+            student,
+            uni_account,
+            subject_account,
+            waitlist_account,
+            ] = self
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !student.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if !uni_account.is_owned_by(&crate::ID) || !subject_account.is_owned_by(&crate::ID) || !waitlist_account.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let uni_data_ref = uni_account.try_borrow_data()?;
+        let uni_account_data = bytemuck::try_from_bytes::<UniAccount>(&uni_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        uni_account_data.check_active()?;
+
+        let mut subject_data_ref = subject_account.try_borrow_mut_data()?;
+        let subject_account_data = bytemuck::try_from_bytes_mut::<SubjectAccount>(&mut subject_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        let mut waitlist_data_ref = waitlist_account.try_borrow_mut_data()?;
+        let waitlist_data = bytemuck::try_from_bytes_mut::<WaitlistAccount>(&mut waitlist_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if subject_account_data.uni_key != *uni_account.key() || waitlist_data.subject_key != *subject_account.key() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // Only worth queueing while every spot is taken, or others are already queued for the free ones
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        subject_account_data.join_waitlist(waitlist_data, student.key(), current_timestamp)?;
+
+        Ok(())
+    }
+}
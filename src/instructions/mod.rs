@@ -5,4 +5,28 @@ pub mod add_subjects;
 pub mod initialize_student;
 pub mod pay_tution_fee;
 pub mod unstake;
+pub mod update_payment_mint;
+pub mod verify_credential;
+pub mod revoke_credential;
+pub mod record_grades;
+pub mod approve_semester;
+pub mod sponsor_pay_tuition;
+pub mod initialize_scholarship_pool;
+pub mod deposit_scholarship;
+pub mod award_scholarship;
+pub mod create_discount;
 
+pub mod create_waitlist;
+pub mod join_waitlist;
+pub mod offer_waitlist_spot;
+pub mod add_intake_cohort;
+pub mod transfer_enrollment;
+pub mod request_leave;
+pub mod resume_studies;
+pub mod suspend_student;
+pub mod expel_student;
+pub mod add_role;
+pub mod remove_role;
+pub mod approve_uni;
+pub mod suspend_uni;
+pub mod reinstate_uni;
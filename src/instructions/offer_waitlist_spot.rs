@@ -0,0 +1,52 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, sysvars::{clock::Clock, Sysvar}, ProgramResult};
+
+use crate::{subject_account::SubjectAccount, uni_account::UniAccount, waitlist_account::WaitlistAccount};
+
+
+
+pub trait OfferWaitlistSpotContext<'a> {
+    fn offer_waitlist_spot(&self) -> ProgramResult;
+}
+
+impl <'a> OfferWaitlistSpotContext <'a> for &[AccountInfo] {
+    fn offer_waitlist_spot(&self) -> ProgramResult {
+        // all the required accounts for the this instruction
+        // Anyone can crank this once a spot frees up
+        let [
+            uni_account,
+            subject_account,
+            waitlist_account,
+            ] = self
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !uni_account.is_owned_by(&crate::ID) || !subject_account.is_owned_by(&crate::ID) || !waitlist_account.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let uni_data_ref = uni_account.try_borrow_data()?;
+        let uni_account_data = bytemuck::try_from_bytes::<UniAccount>(&uni_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        uni_account_data.check_active()?;
+
+        let mut subject_data_ref = subject_account.try_borrow_mut_data()?;
+        let subject_account_data = bytemuck::try_from_bytes_mut::<SubjectAccount>(&mut subject_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        let mut waitlist_data_ref = waitlist_account.try_borrow_mut_data()?;
+        let waitlist_data = bytemuck::try_from_bytes_mut::<WaitlistAccount>(&mut waitlist_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if subject_account_data.uni_key != *uni_account.key() || waitlist_data.subject_key != *subject_account.key() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // One offer at a time, an expired one gives its spot to the next wallet
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        subject_account_data.offer_spot(waitlist_data, current_timestamp)?;
+
+        Ok(())
+    }
+}
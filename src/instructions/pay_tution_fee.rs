@@ -1,12 +1,28 @@
 use bytemuck;
-use pinocchio::{account_info::AccountInfo, program_error::ProgramError, sysvars::{clock::Clock, Sysvar}, ProgramResult};
-use pinocchio_token::state::{Mint, TokenAccount};
+use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, pubkey, seeds, sysvars::{clock::Clock, Sysvar}, ProgramResult};
 
-use crate::{student_account::StudentAccount, subject_account::SubjectAccount, uni_account::UniAccount, vire_account::VireAccount};
+use crate::{discount_account::DiscountAccount, errors::VireError, scholarship_award::ScholarshipAward, scholarship_pool::ScholarshipPool, student_account::StudentAccount, subject_account::SubjectAccount, token_interface::{self, TransferChecked}, uni_account::UniAccount, vire_account::VireAccount};
 
 
 
 
+// Accounts of a semester (installment) payment, `payer` is the token authority of `payer_ata`
+pub struct TuitionAccounts<'a> {
+    pub payer: &'a AccountInfo,
+    pub payer_ata: &'a AccountInfo,
+    pub mint_usdc: &'a AccountInfo,
+    pub student_account: &'a AccountInfo,
+    pub subject_account: &'a AccountInfo,
+    pub uni_account: &'a AccountInfo,
+    pub uni_ata_usdc: &'a AccountInfo,
+    pub vire_account: &'a AccountInfo,
+    pub treasury: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    // optional [discount_account, scholarship_pool, scholarship_award, scholarship_vault],
+    // accounts left out in front of a given one are passed as the program id
+    pub optional_accounts: &'a [AccountInfo],
+}
+
 pub trait PayTutionFeeContext<'a> {
     fn pay_tution_fee(&self) -> ProgramResult;
 }
@@ -18,7 +34,7 @@ impl <'a> PayTutionFeeContext<'a> for &[AccountInfo] {
         let [
             student, 
             mint_usdc,
-            uni_admin,
+            _uni_admin,
             student_account,
             student_ata_usdc,
             subject_account,
@@ -27,127 +43,388 @@ impl <'a> PayTutionFeeContext<'a> for &[AccountInfo] {
             vire_account, 
             treasury,
             _system_program, 
-            _token_program
+            token_program,
+            optional_accounts @ ..
             ] = self 
         else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
-        // Read-only account data access first
-        let vire_data_ref = vire_account.try_borrow_data()?;
-        let vire_account_data = bytemuck::try_from_bytes::<VireAccount>(&vire_data_ref)
-            .map_err(|_| ProgramError::InvalidAccountData)?;  
+        // The student signs even when a scholarship award covers the whole payment and nothing leaves their token account
+        if !student.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
 
-        let uni_data_ref = uni_account.try_borrow_data()?;
-        let uni_account_data = bytemuck::try_from_bytes::<UniAccount>(&uni_data_ref)
-            .map_err(|_| ProgramError::InvalidAccountData)?;
-        
-        let subject_data_ref = subject_account.try_borrow_data()?;
-        let subject_account_data = bytemuck::try_from_bytes::<SubjectAccount>(&subject_data_ref)
-            .map_err(|_| ProgramError::InvalidAccountData)?;  
-
-        // Mutable account data access
-        let mut student_data_ref = student_account.try_borrow_mut_data()?;
-        let student_account_data = bytemuck::try_from_bytes_mut::<StudentAccount>(&mut student_data_ref)
+        pay_semester(&TuitionAccounts {
+            payer: student,
+            payer_ata: student_ata_usdc,
+            mint_usdc,
+            student_account,
+            subject_account,
+            uni_account,
+            uni_ata_usdc,
+            vire_account,
+            treasury,
+            token_program,
+            optional_accounts,
+        })
+    }
+}
+
+
+// Shared by PayTutionFee and SponsorPayTuition
+pub fn pay_semester(accounts: &TuitionAccounts) -> ProgramResult {
+    let &TuitionAccounts {
+        payer,
+        payer_ata,
+        mint_usdc,
+        student_account,
+        subject_account,
+        uni_account,
+        uni_ata_usdc,
+        vire_account,
+        treasury,
+        token_program,
+        optional_accounts,
+    } = accounts;
+
+    let (discount_account, scholarship_accounts) = match optional_accounts {
+        [discount_account, rest @ ..] => (Some(discount_account).filter(|account| account.key() != &crate::ID), rest),
+        [] => (None, optional_accounts),
+    };
+
+    // mint_usdc can be a Token or Token-2022 mint
+    token_interface::check_token_program(mint_usdc, token_program)?;
+
+    // Read-only account data access first
+    let vire_data_ref = vire_account.try_borrow_data()?;
+    let vire_account_data = bytemuck::try_from_bytes::<VireAccount>(&vire_data_ref)
+        .map_err(|_| ProgramError::InvalidAccountData)?;  
+
+    let uni_data_ref = uni_account.try_borrow_data()?;
+    let uni_account_data = bytemuck::try_from_bytes::<UniAccount>(&uni_data_ref)
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    
+    let subject_data_ref = subject_account.try_borrow_data()?;
+    let subject_account_data = bytemuck::try_from_bytes::<SubjectAccount>(&subject_data_ref)
+        .map_err(|_| ProgramError::InvalidAccountData)?;  
+
+    // Mutable account data access
+    let mut student_data_ref = student_account.try_borrow_mut_data()?;
+    let student_account_data = bytemuck::try_from_bytes_mut::<StudentAccount>(&mut student_data_ref)
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+
+    if !subject_account.is_owned_by(&crate::ID) || !uni_account.is_owned_by(&crate::ID) || !student_account.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // The payer isn't necessarily the student, so student_account has to be checked to be enrolled in subject_account
+    let student_seeds_with_bump = &[
+        student_account_data.student_key.as_ref(),
+        subject_account.key().as_ref(),
+        &[student_account_data.student_bump],
+    ];
+    let student_account_derived = pubkey::create_program_address(student_seeds_with_bump, &crate::ID)?;
+    if student_account_derived != *student_account.key() {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // Tuition is only accepted by approved universities that aren't suspended
+    if subject_account_data.uni_key != *uni_account.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    uni_account_data.check_active()?;
+
+    // ...and in the subject's payment mint
+    if *mint_usdc.key() != subject_account_data.payment_mint {
+        return Err(VireError::PaymentMintMismatch.into());
+    }
+
+    // Tuition goes to the university admin's associated token account, the protocol's share to the Vire admin's (the treasury)
+    if !vire_account.is_owned_by(&crate::ID) || uni_account_data.vire_key != *vire_account.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let uni_admin = token_interface::token_account_owner(uni_ata_usdc, token_program)?;
+    if !uni_account_data.is_admin(uni_account.key(), &uni_admin)
+        || !token_interface::is_associated_token_account(uni_ata_usdc, &uni_admin, mint_usdc, token_program)
+        || !token_interface::is_associated_token_account(treasury, &vire_account_data.admin_key, mint_usdc, token_program)
+    {
+        return Err(VireError::InvalidPaymentDestination.into());
+    }
+
+    let student_semesters = u64::from_le_bytes(student_account_data.semesters);
+
+    // Nothing is left to pay once every semester has been paid
+    if student_account_data.paid_semesters() >= u64::from_le_bytes(subject_account_data.max_semester) {
+        return Err(VireError::SemesterLimitExceeded.into());
+    }
+
+    // The degree is over once the credential was claimed
+    if i64::from_le_bytes(student_account_data.graduated_at) != 0 {
+        return Err(VireError::AlreadyGraduated.into());
+    }
+
+    // Nothing is paid while the degree is paused
+    if student_account_data.on_leave() {
+        return Err(VireError::StudentOnLeave.into());
+    }
+
+    if student_account_data.is_expelled() {
+        return Err(VireError::StudentExpelled.into());
+    }
+
+    if student_account_data.is_suspended() {
+        return Err(VireError::StudentSuspended.into());
+    }
+
+    // The university has to sign off the last paid semester before the next one can be paid
+    if subject_account_data.requires_approval == 1 && !student_account_data.paid_semesters_approved() {
+        return Err(VireError::SemesterNotApproved.into());
+    }
+
+
+    let semester_paid = u64::from_le_bytes(student_account_data.semester_paid);
+
+    // Update first semester time if needed (on the first installment), cohort students start with their cohort
+    let calendar_started = student_account_data.cohort_start == 1 || student_semesters > 1 || semester_paid > 0;
+    if !calendar_started {
+        let current_time = Clock::get()?.unix_timestamp.to_le_bytes();
+        // let current_time = Clock::get()?.unix_timestamp;
+        student_account_data.time_start = current_time; 
+    }
+
+
+    // // // These checks are not COMPULSORY
+    // // Doing some checks for accounts
+    // // Check if student is a signer
+    // if !student.is_signer() {
+    //     return Err(ProgramError::MissingRequiredSignature);
+    // }
+
+    // // Verify uni_admin key matches the stored key in uni_account
+    // if uni_admin.key() != &uni_account_data.uni_key {
+    //     return Err(ProgramError::InvalidArgument);
+    // }
+
+    // // Verify program-owned accounts
+    // if !student_account.is_owned_by(&crate::ID) {//(not compsulion)
+    //     return Err(ProgramError::IncorrectProgramId);
+    // }
+
+    // if !subject_account.is_owned_by(&crate::ID) {
+    //     return Err(ProgramError::IncorrectProgramId);
+    // }
+
+    // if !uni_account.is_owned_by(&crate::ID) {
+    //     return Err(ProgramError::IncorrectProgramId);
+    // }
+
+    // if !vire_account.is_owned_by(&crate::ID) {
+    //     return Err(ProgramError::IncorrectProgramId);
+    // }
+
+    // // Use from_account_info
+    // // // Verify token account ownerships
+    // // if !TokenAccount::from_account_info(student_ata_usdc)?;.(student.key()) { //token.
+    // //     return Err(ProgramError::IllegalOwner);
+    // // }
+
+    // // if !uni_ata_usdc.is_owned_by(uni_admin.key()) {
+    // //     return Err(ProgramError::IllegalOwner);
+    // // }
+
+    // // if !treasury.is_owned_by(vire_account.key()) {
+    // //     return Err(ProgramError::IllegalOwner);
+    // // }
+
+    // // {
+    // //     let student_ata_usdc = TokenAccount::from_account_info(student_ata_usdc)?.owner();
+
+    // // }
+
+    // Fee calculations
+    let tution_fee_per_sem = subject_account_data.semester_fee();
+    let (amount, installment_index) = subject_account_data.next_installment(semester_paid);
+
+    // Discount: the payer is charged less, the semester is still credited the full amount
+    let mut charged = amount;
+    if let Some(discount_account) = discount_account {
+        if !discount_account.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let mut discount_data_ref = discount_account.try_borrow_mut_data()?;
+        let discount_data = bytemuck::try_from_bytes_mut::<DiscountAccount>(&mut discount_data_ref)
             .map_err(|_| ProgramError::InvalidAccountData)?;
 
+        if !discount_data.is_for(subject_account.key(), &student_account_data.student_key) {
+            return Err(VireError::DiscountNotForStudent.into());
+        }
 
-        let student_semesters = u64::from_le_bytes(student_account_data.semesters);
-        let max_semesters = u64::from_le_bytes(subject_account_data.max_semester);
+        let expires_at = i64::from_le_bytes(discount_data.expires_at);
+        if expires_at != 0 && Clock::get()?.unix_timestamp > expires_at {
+            return Err(VireError::DiscountExpired.into());
+        }
 
-        // Check if student has not exceeded max semesters
-        if student_semesters > max_semesters {
-            return Err(ProgramError::InvalidArgument);
+        // A use is counted once per semester, on its first installment
+        if semester_paid == 0 {
+            discount_data.take_use()?;
+            student_account_data.semester_discount = *discount_account.key();
+        } else if student_account_data.semester_discount != *discount_account.key() {
+            return Err(VireError::DiscountNotForSemester.into());
         }
 
+        charged = discount_data.apply(amount);
+    }
 
-        // Update first semester time if needed
-        if student_semesters == 1 {
-            let current_time = Clock::get()?.unix_timestamp.to_le_bytes();
-            // let current_time = Clock::get()?.unix_timestamp;
-            student_account_data.time_start = current_time; 
+    // The protocol fee is taken on the discounted amount
+    let protocol_fee = vire_account_data.student_fee(charged);
+
+    // Payment window: semester N can be paid from its start (time_start + (N - 1) semesters),
+    // after payment_window_days (plus a month per installment already paid) the late fee is added.
+    // The first payment starts the calendar unless the student's cohort already did.
+    let mut late_fee = 0;
+    let payment_window_days = i64::from_le_bytes(subject_account_data.payment_window_days);
+    if payment_window_days > 0 && calendar_started {
+        let current_time = Clock::get()?.unix_timestamp;
+        // Semesters start later by the time spent on leave
+        let time_start = i64::from_le_bytes(student_account_data.time_start) + student_account_data.paused_seconds(current_time);
+        let window_opens = subject_account_data.semester_start(time_start, student_semesters);
+
+        if current_time < window_opens {
+            return Err(VireError::PaymentTooEarly.into());
+        }
+        if current_time > subject_account_data.payment_due(window_opens, installment_index) {
+            late_fee = subject_account_data.late_fee_on(charged).ok_or(ProgramError::ArithmeticOverflow)?;
         }
+    }
 
+    // The late fee is split like the tuition, the protocol's share at the student fee rate
+    let protocol_late_fee = vire_account_data.student_fee(late_fee);
+    let uni_late_fee = late_fee - protocol_late_fee;
 
-        // // // These checks are not COMPULSORY
-        // // Doing some checks for accounts
-        // // Check if student is a signer
-        // if !student.is_signer() {
-        //     return Err(ProgramError::MissingRequiredSignature);
-        // }
+    let treasury_total = protocol_fee + protocol_late_fee;
+    let uni_total = charged + uni_late_fee;
 
-        // // Verify uni_admin key matches the stored key in uni_account
-        // if uni_admin.key() != &uni_account_data.uni_key {
-        //     return Err(ProgramError::InvalidArgument);
-        // }
+    // With a Token-2022 transfer fee the payer covers it, so the treasury and the uni receive the full amounts
+    let decimals = token_interface::load_mint(mint_usdc)?.decimals();
 
-        // // Verify program-owned accounts
-        // if !student_account.is_owned_by(&crate::ID) {//(not compsulion)
-        //     return Err(ProgramError::IncorrectProgramId);
-        // }
 
-        // if !subject_account.is_owned_by(&crate::ID) {
-        //     return Err(ProgramError::IncorrectProgramId);
-        // }
+    // <---Scholarship---> (the award covers the university first, then the protocol fee)
 
-        // if !uni_account.is_owned_by(&crate::ID) {
-        //     return Err(ProgramError::IncorrectProgramId);
-        // }
+    let mut award_uni = 0;
+    let mut award_treasury = 0;
+    if let [scholarship_pool, scholarship_award, scholarship_vault, ..] = scholarship_accounts {
+        if !scholarship_pool.is_owned_by(&crate::ID) || !scholarship_award.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
 
-        // if !vire_account.is_owned_by(&crate::ID) {
-        //     return Err(ProgramError::IncorrectProgramId);
-        // }
+        let pool_data_ref = scholarship_pool.try_borrow_data()?;
+        let pool_data = bytemuck::try_from_bytes::<ScholarshipPool>(&pool_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
 
-        // // Use from_account_info
-        // // // Verify token account ownerships
-        // // if !TokenAccount::from_account_info(student_ata_usdc)?;.(student.key()) { //token.
-        // //     return Err(ProgramError::IllegalOwner);
-        // // }
+        let mut award_data_ref = scholarship_award.try_borrow_mut_data()?;
+        let award_data = bytemuck::try_from_bytes_mut::<ScholarshipAward>(&mut award_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
 
-        // // if !uni_ata_usdc.is_owned_by(uni_admin.key()) {
-        // //     return Err(ProgramError::IllegalOwner);
-        // // }
+        if award_data.pool != *scholarship_pool.key()
+            || award_data.student_account != *student_account.key()
+            || pool_data.vault != *scholarship_vault.key()
+            || pool_data.mint != *mint_usdc.key()
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
 
-        // // if !treasury.is_owned_by(vire_account.key()) {
-        // //     return Err(ProgramError::IllegalOwner);
-        // // }
+        (award_uni, award_treasury) = award_data.draw(uni_total, treasury_total);
+        drop(award_data_ref);
+        student_account_data.award_remaining = u64::from_le_bytes(student_account_data.award_remaining)
+            .saturating_sub(award_uni + award_treasury)
+            .to_le_bytes();
+
+        // the pool PDA signs for its vault, so it can't stay borrowed
+        let scope = *pool_data.scope();
+        let pool_bump = [pool_data.pool_bump];
+        drop(pool_data_ref);
+        let pool_signer_seeds = seeds!(b"scholarship", scope.as_ref(), &pool_bump);
+        let pool_signer = Signer::from(&pool_signer_seeds);
+
+        // vault to treasury
+        if award_treasury > 0 {
+            TransferChecked{
+                from: scholarship_vault,
+                mint: mint_usdc,
+                to: treasury,
+                authority: scholarship_pool,
+                amount: token_interface::gross_amount(mint_usdc, award_treasury)?,
+                decimals,
+                token_program,
+            }.invoke_signed(core::slice::from_ref(&pool_signer))?;
+        }
 
-        // // {
-        // //     let student_ata_usdc = TokenAccount::from_account_info(student_ata_usdc)?.owner();
+        // vault to uni_ata_usdc
+        if award_uni > 0 {
+            TransferChecked{
+                from: scholarship_vault,
+                mint: mint_usdc,
+                to: uni_ata_usdc,
+                authority: scholarship_pool,
+                amount: token_interface::gross_amount(mint_usdc, award_uni)?,
+                decimals,
+                token_program,
+            }.invoke_signed(core::slice::from_ref(&pool_signer))?;
+        }
 
-        // // }
+        let mut pool_data_ref = scholarship_pool.try_borrow_mut_data()?;
+        let pool_data = bytemuck::try_from_bytes_mut::<ScholarshipPool>(&mut pool_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        pool_data.total_used = (u64::from_le_bytes(pool_data.total_used) + award_uni + award_treasury).to_le_bytes();
+    }
 
-        // Fee calculations
-        let transaction_fee = u64::from_le_bytes(vire_account_data.transaction_fee_student);
-        let tution_fee = u64::from_le_bytes(subject_account_data.tution_fee);
 
-        // Final Fee Distribution
-        let tution_fee_per_sem = tution_fee.checked_div(max_semesters).unwrap();
-        let protocol_fee = tution_fee_per_sem.checked_div(100).unwrap() * transaction_fee;
+    // <---Payer covers the rest--->
 
-        // student to treasury
-        pinocchio_token::instructions::TransferChecked{
-            from: student_ata_usdc,
+    // Refunds only return the payer's own tuition (the award is counted against the tuition first, late fees aren't refunded),
+    // so the sponsor is only recorded when the payer put some in, and a semester has one sponsor
+    let payer_tuition = charged.saturating_sub(award_uni);
+    if payer_tuition > 0 {
+        student_account_data.record_payment(payer.key(), payer_tuition)?;
+    }
+
+    // payer to treasury
+    if treasury_total > award_treasury {
+        TransferChecked{
+            from: payer_ata,
             mint: mint_usdc,
             to: treasury,
-            authority: student,
-            amount: protocol_fee,
-            decimals: Mint::from_account_info(mint_usdc)?.decimals(),
+            authority: payer,
+            amount: token_interface::gross_amount(mint_usdc, treasury_total - award_treasury)?,
+            decimals,
+            token_program,
         }.invoke()?;
+    }
 
-        // student to uni_ata_usdc
-        pinocchio_token::instructions::TransferChecked{
-            from: student_ata_usdc,
+    // payer to uni_ata_usdc
+    if uni_total > award_uni {
+        TransferChecked{
+            from: payer_ata,
             mint: mint_usdc,
             to: uni_ata_usdc,
-            authority: student,
-            amount: tution_fee_per_sem,
-            decimals: Mint::from_account_info(mint_usdc)?.decimals(),
+            authority: payer,
+            amount: token_interface::gross_amount(mint_usdc, uni_total - award_uni)?,
+            decimals,
+            token_program,
         }.invoke()?;
+    }
 
-        // Increasing semesters number in student_account pda by 1 (student_account_data.semesters += 1)
-        student_account_data.semesters = (u64::from_le_bytes(student_account_data.semesters) + 1).to_le_bytes();
-
-        Ok(())
+    // Increasing semesters number in student_account pda by 1 (student_account_data.semesters += 1) once the semester is fully paid
+    let semester_paid = semester_paid + amount;
+    if semester_paid == tution_fee_per_sem {
+        student_account_data.complete_semester();
+    } else {
+        student_account_data.semester_paid = semester_paid.to_le_bytes();
     }
-}
\ No newline at end of file
+
+    Ok(())
+}
@@ -0,0 +1,192 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, pubkey, seeds, sysvars::{clock::Clock, rent::Rent, Sysvar}, ProgramResult};
+use pinocchio_system::instructions::CreateAccount;
+
+use crate::{errors::VireError, student_account::StudentAccount, subject_account::SubjectAccount, transcript_account::{SemesterRecord, TranscriptAccount, MAX_TRANSCRIPT_RECORDS}, uni_account::{UniAccount, ROLE_GRADER}};
+
+
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct GradeArgs {
+    semester: [u8; 8],
+    grade: [u8; 8],
+    credits: [u8; 8],
+    transcript_hash: [u8; 32],
+    bump: u8, // transcript_account bump
+}
+
+impl GradeArgs {
+    pub fn new(semester: u64, grade: u64, credits: u64, transcript_hash: [u8; 32], bump: u8) -> Self {
+        Self {
+            semester: semester.to_le_bytes(),
+            grade: grade.to_le_bytes(),
+            credits: credits.to_le_bytes(),
+            transcript_hash,
+            bump,
+        }
+    }
+
+    pub fn semester(&self) -> u64 {
+        u64::from_le_bytes(self.semester)
+    }
+
+    pub fn grade(&self) -> u64 {
+        u64::from_le_bytes(self.grade)
+    }
+
+    pub fn credits(&self) -> u64 {
+        u64::from_le_bytes(self.credits)
+    }
+}
+
+impl TryFrom<&[u8]> for GradeArgs {
+    type Error = ProgramError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+            bytemuck::try_from_bytes::<Self>(value)
+                .copied()
+                .map_err(|_| ProgramError::InvalidInstructionData)
+    }
+}
+
+pub trait RecordGradesContext<'a> {
+    fn record_grades(&self, args: &GradeArgs) -> ProgramResult;
+}
+
+impl <'a> RecordGradesContext <'a> for &[AccountInfo] {
+    fn record_grades(&self, args: &GradeArgs) -> ProgramResult {
+        // all the required accounts for the this instruction
+        let [
+            grader,
+            uni_account,
+            subject_account,
+            student_account,
+            transcript_account,
+            _system_program,
+            ] = self
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !grader.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if !uni_account.is_owned_by(&crate::ID)
+            || !subject_account.is_owned_by(&crate::ID)
+            || !student_account.is_owned_by(&crate::ID)
+        {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let uni_data_ref = uni_account.try_borrow_data()?;
+        let uni_account_data = bytemuck::try_from_bytes::<UniAccount>(&uni_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        // Only staff holding the grader role (or the admin)
+        if !uni_account_data.has_role(uni_account.key(), grader.key(), ROLE_GRADER) {
+            return Err(VireError::Unauthorized.into());
+        }
+
+        uni_account_data.check_active()?;
+
+        let subject_data_ref = subject_account.try_borrow_data()?;
+        let subject_account_data = bytemuck::try_from_bytes::<SubjectAccount>(&subject_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if subject_account_data.uni_key != *uni_account.key() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let student_data_ref = student_account.try_borrow_data()?;
+        let student_account_data = bytemuck::try_from_bytes::<StudentAccount>(&student_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        let student_seeds_with_bump = &[
+            student_account_data.student_key.as_ref(),
+            subject_account.key().as_ref(),
+            &[student_account_data.student_bump],
+        ];
+        let student_account_derived = pubkey::create_program_address(student_seeds_with_bump, &crate::ID)?;
+        if student_account_derived != *student_account.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        if i64::from_le_bytes(student_account_data.revoked_at) != 0 {
+            return Err(VireError::CredentialRevoked.into());
+        }
+
+
+        // <---Creating Transcript (first record)--->
+
+        let transcript_seeds_with_bump = &[b"transcript".as_ref(), student_account.key().as_ref(), &[args.bump]];
+        let transcript_account_derived = pubkey::create_program_address(transcript_seeds_with_bump, &crate::ID)?;
+
+        // Ensure derived PDA matches the provided transcript_account
+        if transcript_account_derived != *transcript_account.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        if !transcript_account.is_owned_by(&crate::ID) {
+            let bump_ref = &[args.bump];
+            let signer_seeds = seeds!(b"transcript", student_account.key().as_ref(), bump_ref);
+            let signer = Signer::from(&signer_seeds);
+
+            CreateAccount{
+                from: grader,
+                to: transcript_account,
+                space: TranscriptAccount::LEN as u64,
+                owner: &crate::ID,
+                lamports: Rent::get()?.minimum_balance(TranscriptAccount::LEN),
+            }
+            .invoke_signed(&[signer])?;
+
+            let mut transcript_data_ref = transcript_account.try_borrow_mut_data()?;
+            let transcript_account_data = bytemuck::try_from_bytes_mut::<TranscriptAccount>(&mut transcript_data_ref)
+                .map_err(|_| ProgramError::InvalidAccountData)?;
+
+            transcript_account_data.student_account = *student_account.key();
+            transcript_account_data.transcript_bump = args.bump;
+        }
+
+
+        // <---Appending Semester Record--->
+
+        let mut transcript_data_ref = transcript_account.try_borrow_mut_data()?;
+        let transcript_account_data = bytemuck::try_from_bytes_mut::<TranscriptAccount>(&mut transcript_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        let count = transcript_account_data.record_count as usize;
+        if count == MAX_TRANSCRIPT_RECORDS {
+            return Err(VireError::TranscriptFull.into());
+        }
+
+        // Semesters are recorded in order, and only once they have been paid (`semesters` is the one being paid)
+        let paid_semesters = u64::from_le_bytes(student_account_data.semesters).saturating_sub(1);
+        if args.semester() <= transcript_account_data.last_semester() || args.semester() > paid_semesters {
+            return Err(VireError::InvalidSemesterRecord.into());
+        }
+
+        transcript_account_data.records[count] = SemesterRecord {
+            semester: args.semester,
+            grade: args.grade,
+            credits: args.credits,
+            transcript_hash: args.transcript_hash,
+            recorded_at: Clock::get()?.unix_timestamp.to_le_bytes(),
+        };
+        transcript_account_data.record_count += 1;
+
+        let total_credits = u64::from_le_bytes(transcript_account_data.total_credits)
+            .checked_add(args.credits())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        let weighted_grade_sum = args.grade()
+            .checked_mul(args.credits())
+            .and_then(|weighted| weighted.checked_add(u64::from_le_bytes(transcript_account_data.weighted_grade_sum)))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        transcript_account_data.total_credits = total_credits.to_le_bytes();
+        transcript_account_data.weighted_grade_sum = weighted_grade_sum.to_le_bytes();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,15 @@
+use pinocchio::{account_info::AccountInfo, ProgramResult};
+
+use crate::{instructions::approve_uni::change_uni_status, uni_account::UniStatus};
+
+
+
+pub trait ReinstateUniContext<'a> {
+    fn reinstate_uni(&self) -> ProgramResult;
+}
+
+impl <'a> ReinstateUniContext <'a> for &[AccountInfo] {
+    fn reinstate_uni(&self) -> ProgramResult {
+        change_uni_status(self, UniStatus::Suspended, UniStatus::Active)
+    }
+}
@@ -0,0 +1,46 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use crate::{errors::VireError, instructions::add_role::RoleArgs, uni_account::UniAccount};
+
+
+
+pub trait RemoveRoleContext<'a> {
+    fn remove_role(&self, args: &RoleArgs) -> ProgramResult;
+}
+
+impl <'a> RemoveRoleContext <'a> for &[AccountInfo] {
+    fn remove_role(&self, args: &RoleArgs) -> ProgramResult {
+        // all the required accounts for the this instruction
+        let [
+            uni_admin,
+            uni_account,
+            member,
+            ] = self
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Only the university admin takes roles away
+        if !uni_admin.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if !uni_account.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let mut uni_data_ref = uni_account.try_borrow_mut_data()?;
+        let uni_account_data = bytemuck::try_from_bytes_mut::<UniAccount>(&mut uni_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if !uni_account_data.is_admin(uni_account.key(), uni_admin.key()) {
+            return Err(VireError::Unauthorized.into());
+        }
+
+        args.check()?;
+
+        uni_account_data.revoke_role(member.key(), args.roles())?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey, sysvars::{clock::Clock, Sysvar}, ProgramResult};
+
+use crate::{errors::VireError, student_account::StudentAccount, subject_account::SubjectAccount, uni_account::{UniAccount, ROLE_REGISTRAR}};
+
+
+
+pub trait ResumeStudiesContext<'a> {
+    fn resume_studies(&self) -> ProgramResult;
+}
+
+impl <'a> ResumeStudiesContext <'a> for &[AccountInfo] {
+    fn resume_studies(&self) -> ProgramResult {
+        // all the required accounts for the this instruction
+        let [
+            student,
+            uni_admin,
+            uni_account,
+            subject_account,
+            student_account,
+            ] = self
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Returning from leave is approved by the university's registrar as well
+        if !student.is_signer() || !uni_admin.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if !uni_account.is_owned_by(&crate::ID)
+            || !subject_account.is_owned_by(&crate::ID)
+            || !student_account.is_owned_by(&crate::ID)
+        {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let uni_data_ref = uni_account.try_borrow_data()?;
+        let uni_account_data = bytemuck::try_from_bytes::<UniAccount>(&uni_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if !uni_account_data.has_role(uni_account.key(), uni_admin.key(), ROLE_REGISTRAR) {
+            return Err(VireError::Unauthorized.into());
+        }
+
+        // No check_active() here, students on leave can come back while their university is suspended
+
+        let subject_data_ref = subject_account.try_borrow_data()?;
+        let subject_account_data = bytemuck::try_from_bytes::<SubjectAccount>(&subject_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if subject_account_data.uni_key != *uni_account.key() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mut student_data_ref = student_account.try_borrow_mut_data()?;
+        let student_account_data = bytemuck::try_from_bytes_mut::<StudentAccount>(&mut student_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        let student_seeds_with_bump = &[student.key().as_ref(), subject_account.key().as_ref(), &[student_account_data.student_bump]];
+        let student_account_derived = pubkey::create_program_address(student_seeds_with_bump, &crate::ID)?;
+        if student_account_derived != *student_account.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        student_account_data.end_leave(Clock::get()?.unix_timestamp)
+    }
+}
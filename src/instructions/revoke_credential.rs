@@ -0,0 +1,150 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, pubkey::{self, Pubkey}, seeds, sysvars::{clock::Clock, Sysvar}, ProgramResult};
+
+use crate::{errors::VireError, student_account::StudentAccount, subject_account::SubjectAccount, token_interface::{FreezeAccount, TOKEN_2022_PROGRAM_ID}, uni_account::{UniAccount, ROLE_REGISTRAR}};
+
+
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct RevokeArgs {
+    reason: u8, // university-defined reason code, 0 is reserved for "not revoked"
+}
+
+impl RevokeArgs {
+    pub fn new(reason: u8) -> Self {
+        Self { reason }
+    }
+
+    pub fn reason(&self) -> u8 {
+        self.reason
+    }
+}
+
+impl TryFrom<&[u8]> for RevokeArgs {
+    type Error = ProgramError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+            bytemuck::try_from_bytes::<Self>(value)
+                .copied()
+                .map_err(|_| ProgramError::InvalidInstructionData)
+    }
+}
+
+pub trait RevokeCredentialContext<'a> {
+    fn revoke_credential(&self, args: &RevokeArgs) -> ProgramResult;
+}
+
+impl <'a> RevokeCredentialContext <'a> for &[AccountInfo] {
+    fn revoke_credential(&self, args: &RevokeArgs) -> ProgramResult {
+        // all the required accounts for the this instruction
+        let [
+            uni_admin,
+            uni_account,
+            subject_account,
+            student_account,
+            diploma_mint,
+            student_diploma_ata,
+            diploma_token_program,
+            ] = self
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Only the university's registrar (or the admin) can revoke
+        if !uni_admin.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if !uni_account.is_owned_by(&crate::ID)
+            || !subject_account.is_owned_by(&crate::ID)
+            || !student_account.is_owned_by(&crate::ID)
+        {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        if args.reason() == 0 {
+            return Err(VireError::InvalidRevocationReason.into());
+        }
+
+        let uni_data_ref = uni_account.try_borrow_data()?;
+        let uni_account_data = bytemuck::try_from_bytes::<UniAccount>(&uni_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if !uni_account_data.has_role(uni_account.key(), uni_admin.key(), ROLE_REGISTRAR) {
+            return Err(VireError::Unauthorized.into());
+        }
+
+        uni_account_data.check_active()?;
+
+        let subject_data_ref = subject_account.try_borrow_data()?;
+        let subject_account_data = bytemuck::try_from_bytes::<SubjectAccount>(&subject_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        // The subject has to belong to the admin's university
+        if subject_account_data.uni_key != *uni_account.key() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mut student_data_ref = student_account.try_borrow_mut_data()?;
+        let student_account_data = bytemuck::try_from_bytes_mut::<StudentAccount>(&mut student_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        // ...and the student to the subject
+        let student_seeds_with_bump = &[
+            student_account_data.student_key.as_ref(),
+            subject_account.key().as_ref(),
+            &[student_account_data.student_bump],
+        ];
+        let student_account_derived = pubkey::create_program_address(student_seeds_with_bump, &crate::ID)?;
+        if student_account_derived != *student_account.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        if i64::from_le_bytes(student_account_data.revoked_at) != 0 {
+            return Err(VireError::CredentialRevoked.into());
+        }
+
+        student_account_data.revoked_at = Clock::get()?.unix_timestamp.to_le_bytes();
+        student_account_data.revocation_reason = args.reason();
+
+
+        // <---Freezing Diploma---> (soulbound, so it can't be burned without the student; subject_account is its freeze authority)
+
+        if student_account_data.diploma_mint != Pubkey::default() {
+            if student_account_data.diploma_mint != *diploma_mint.key() || diploma_token_program.key() != &TOKEN_2022_PROGRAM_ID {
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            // Token account layout: mint, owner, amount at 64, ..., state at 108 (2 = frozen).
+            // A closed or emptied diploma account has nothing left to freeze, the revocation is still recorded
+            let needs_freeze = student_diploma_ata.data_len() != 0 && {
+                let ata_data = student_diploma_ata.try_borrow_data()?;
+                if !student_diploma_ata.is_owned_by(&TOKEN_2022_PROGRAM_ID)
+                    || ata_data.len() < 109
+                    || ata_data[..32] != diploma_mint.key()[..]
+                {
+                    return Err(ProgramError::InvalidAccountData);
+                }
+                ata_data[108] != 2 && ata_data[64..72] != [0; 8]
+            };
+
+            if needs_freeze {
+                let subject_code = [u64::from_le_bytes(subject_account_data.subject_code) as u8];
+                let subject_bump = [subject_account_data.subject_bump];
+                let subject_signer_seeds = seeds!(uni_account.key().as_ref(), &subject_code, &subject_bump);
+                let subject_signer = Signer::from(&subject_signer_seeds);
+
+                FreezeAccount{
+                    account: student_diploma_ata,
+                    mint: diploma_mint,
+                    freeze_authority: subject_account,
+                    token_program: diploma_token_program,
+                }
+                .invoke_signed(&[subject_signer])?;
+            }
+        }
+
+        Ok(())
+    }
+}
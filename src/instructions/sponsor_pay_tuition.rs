@@ -0,0 +1,51 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use crate::instructions::pay_tution_fee::{self, TuitionAccounts};
+
+
+
+pub trait SponsorPayTuitionContext<'a> {
+    fn sponsor_pay_tuition(&self) -> ProgramResult;
+}
+
+impl <'a> SponsorPayTuitionContext <'a> for &[AccountInfo] {
+    fn sponsor_pay_tuition(&self) -> ProgramResult {
+        // all the required accounts for the this instruction
+        let [
+            sponsor,
+            mint_usdc,
+            student_account,
+            sponsor_ata_usdc,
+            subject_account,
+            uni_account,
+            uni_ata_usdc,
+            vire_account,
+            treasury,
+            _system_program,
+            token_program,
+            optional_accounts @ ..
+            ] = self
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Anyone can pay, from their own token account
+        if !sponsor.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        pay_tution_fee::pay_semester(&TuitionAccounts {
+            payer: sponsor,
+            payer_ata: sponsor_ata_usdc,
+            mint_usdc,
+            student_account,
+            subject_account,
+            uni_account,
+            uni_ata_usdc,
+            vire_account,
+            treasury,
+            token_program,
+            optional_accounts,
+        })
+    }
+}
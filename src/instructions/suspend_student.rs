@@ -0,0 +1,198 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey, sysvars::{clock::Clock, Sysvar}, ProgramResult};
+
+use crate::{errors::VireError, student_account::StudentAccount, subject_account::SubjectAccount, token_interface::{self, TransferChecked}, uni_account::{UniAccount, ROLE_FINANCE, ROLE_REGISTRAR}};
+
+
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct SuspendArgs {
+    suspended: u8, // 1 = suspend the student, 0 = lift the suspension
+    refund: u8, // 1 = refund the sponsor under the subject's refund policy (when suspending)
+}
+
+impl SuspendArgs {
+    pub fn new(suspended: bool, refund: bool) -> Self {
+        Self { suspended: suspended as u8, refund: refund as u8 }
+    }
+
+    pub fn suspended(&self) -> bool {
+        self.suspended != 0
+    }
+
+    pub fn refund(&self) -> bool {
+        self.refund != 0
+    }
+}
+
+impl TryFrom<&[u8]> for SuspendArgs {
+    type Error = ProgramError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+            bytemuck::try_from_bytes::<Self>(value)
+                .copied()
+                .map_err(|_| ProgramError::InvalidInstructionData)
+    }
+}
+
+pub trait SuspendStudentContext<'a> {
+    fn suspend_student(&self, args: &SuspendArgs) -> ProgramResult;
+}
+
+impl <'a> SuspendStudentContext <'a> for &[AccountInfo] {
+    fn suspend_student(&self, args: &SuspendArgs) -> ProgramResult {
+        // all the required accounts for the this instruction
+        let [
+            uni_admin,
+            uni_account,
+            subject_account,
+            student_account,
+            refund_accounts @ ..
+            ] = self
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Only the university's registrar (or the admin) can suspend
+        if !uni_admin.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if !uni_account.is_owned_by(&crate::ID)
+            || !subject_account.is_owned_by(&crate::ID)
+            || !student_account.is_owned_by(&crate::ID)
+        {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let uni_data_ref = uni_account.try_borrow_data()?;
+        let uni_account_data = bytemuck::try_from_bytes::<UniAccount>(&uni_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if !uni_account_data.has_role(uni_account.key(), uni_admin.key(), ROLE_REGISTRAR) {
+            return Err(VireError::Unauthorized.into());
+        }
+
+        uni_account_data.check_active()?;
+
+        let subject_data_ref = subject_account.try_borrow_data()?;
+        let subject_account_data = bytemuck::try_from_bytes::<SubjectAccount>(&subject_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if subject_account_data.uni_key != *uni_account.key() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mut student_data_ref = student_account.try_borrow_mut_data()?;
+        let student_account_data = bytemuck::try_from_bytes_mut::<StudentAccount>(&mut student_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        let student_seeds_with_bump = &[student_account_data.student_key.as_ref(), subject_account.key().as_ref(), &[student_account_data.student_bump]];
+        let student_account_derived = pubkey::create_program_address(student_seeds_with_bump, &crate::ID)?;
+        if student_account_derived != *student_account.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        if i64::from_le_bytes(student_account_data.graduated_at) != 0 {
+            return Err(VireError::AlreadyGraduated.into());
+        }
+
+        if student_account_data.is_expelled() {
+            return Err(VireError::StudentExpelled.into());
+        }
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+
+        if args.suspended() {
+            if student_account_data.is_suspended() {
+                return Err(VireError::StudentSuspended.into());
+            }
+            student_account_data.suspended_at = current_timestamp.to_le_bytes();
+
+            if args.refund() {
+                if !uni_account_data.has_role(uni_account.key(), uni_admin.key(), ROLE_FINANCE) {
+                    return Err(VireError::Unauthorized.into());
+                }
+                refund_sponsor(student_account_data, subject_account_data, uni_account, uni_account_data, uni_admin, refund_accounts)?;
+            }
+        } else {
+            if !student_account_data.is_suspended() {
+                return Err(VireError::StudentNotSuspended.into());
+            }
+
+            // The suspension stays off the degree clock
+            let suspended_for = current_timestamp - i64::from_le_bytes(student_account_data.suspended_at);
+            student_account_data.suspended_seconds = (i64::from_le_bytes(student_account_data.suspended_seconds) + suspended_for).to_le_bytes();
+            student_account_data.suspended_at = [0; 8];
+        }
+
+        Ok(())
+    }
+}
+
+
+// Pays the sponsor back `refund_percent` of what they paid towards the semester in progress (or the last fully
+// paid semester) out of the university admin's token account and takes the semester's credit back, the caller checks the finance role
+pub fn refund_sponsor(
+    student_account_data: &mut StudentAccount,
+    subject_account_data: &SubjectAccount,
+    uni_account: &AccountInfo,
+    uni_account_data: &UniAccount,
+    uni_admin: &AccountInfo,
+    refund_accounts: &[AccountInfo],
+) -> ProgramResult {
+    let [mint_usdc, uni_ata_usdc, sponsor_ata, token_program, ..] = refund_accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if u64::from_le_bytes(student_account_data.refunded) != 0 {
+        return Err(VireError::RefundAlreadyIssued.into());
+    }
+
+    token_interface::check_token_program(mint_usdc, token_program)?;
+
+    if *mint_usdc.key() != subject_account_data.payment_mint {
+        return Err(VireError::PaymentMintMismatch.into());
+    }
+
+    // Scholarship draws and discounts were never paid by the sponsor, so only their own tuition is refunded
+    let payment = *student_account_data.refundable_payment();
+    let amount = student_account_data.refund_due(subject_account_data.refund_percent);
+    if amount == 0 {
+        return Err(VireError::NoRefundDue.into());
+    }
+
+    // Token account layout: mint, owner, ...
+    {
+        let data = sponsor_ata.try_borrow_data()?;
+        if data.len() < 64 || data[..32] != mint_usdc.key()[..] || data[32..64] != payment.sponsor[..] {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+
+    // Refunds come out of the university admin's associated token account, where the tuition went,
+    // the signer is the admin or a finance staff member the admin approved as its delegate
+    let treasury_owner = token_interface::token_account_owner(uni_ata_usdc, token_program)?;
+    if !uni_account_data.is_admin(uni_account.key(), &treasury_owner)
+        || !token_interface::is_associated_token_account(uni_ata_usdc, &treasury_owner, mint_usdc, token_program)
+    {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // The university covers a Token-2022 transfer fee, the sponsor receives the full refund
+    let decimals = token_interface::load_mint(mint_usdc)?.decimals();
+    TransferChecked{
+        from: uni_ata_usdc,
+        mint: mint_usdc,
+        to: sponsor_ata,
+        authority: uni_admin,
+        amount: token_interface::gross_amount(mint_usdc, amount)?,
+        decimals,
+        token_program,
+    }.invoke()?;
+
+    student_account_data.record_refund(amount);
+
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use pinocchio::{account_info::AccountInfo, ProgramResult};
+
+use crate::{instructions::approve_uni::change_uni_status, uni_account::UniStatus};
+
+
+
+pub trait SuspendUniContext<'a> {
+    fn suspend_uni(&self) -> ProgramResult;
+}
+
+impl <'a> SuspendUniContext <'a> for &[AccountInfo] {
+    fn suspend_uni(&self) -> ProgramResult {
+        change_uni_status(self, UniStatus::Active, UniStatus::Suspended)
+    }
+}
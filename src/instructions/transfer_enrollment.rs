@@ -0,0 +1,305 @@
+use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, pubkey, seeds, sysvars::{clock::Clock, rent::Rent, Sysvar}, ProgramResult};
+use pinocchio_system::instructions::{Allocate, Assign, CreateAccount};
+
+use crate::{errors::VireError, instructions::initialize_student::{check_prerequisites, issue_card, CardAccounts, StudentArgs}, student_account::StudentAccount, subject_account::{SubjectAccount, SECONDS_IN_A_MONTH}, token_interface::{self, BurnChecked, ThawAccount}, transcript_account::TranscriptAccount, uni_account::UniAccount};
+
+
+
+pub trait TransferEnrollmentContext<'a> {
+    fn transfer_enrollment(&self, args: &StudentArgs) -> ProgramResult;
+}
+
+impl <'a> TransferEnrollmentContext <'a> for &[AccountInfo] {
+    fn transfer_enrollment(&self, args: &StudentArgs) -> ProgramResult {
+        // all the required accounts for the this instruction
+        let [
+            student,
+            student_account,
+            subject_account,
+            new_student_account,
+            new_subject_account,
+            uni_account,
+            card_mint,
+            student_card_ata,
+            new_card_mint,
+            new_student_card_ata,
+            system_program,
+            card_token_program,
+            token_program,
+            associated_token_program,
+            transcript_account,
+            new_transcript_account,
+            metadata_accounts @ ..
+            ] = self
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // The student asks for the transfer and burns the old card
+        if !student.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if !student_account.is_owned_by(&crate::ID)
+            || !subject_account.is_owned_by(&crate::ID)
+            || !new_subject_account.is_owned_by(&crate::ID)
+            || !uni_account.is_owned_by(&crate::ID)
+        {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // old card_mint can be a Token or Token-2022 mint
+        token_interface::check_token_program(card_mint, card_token_program)?;
+
+        let uni_data_ref = uni_account.try_borrow_data()?;
+        let uni_account_data = bytemuck::try_from_bytes::<UniAccount>(&uni_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        uni_account_data.check_active()?;
+
+
+        // <---Leaving The Old Subject--->
+
+        let mut subject_data_ref = subject_account.try_borrow_mut_data()?;
+        let subject_account_data = bytemuck::try_from_bytes_mut::<SubjectAccount>(&mut subject_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if subject_account_data.uni_key != *uni_account.key() || subject_account.key() == new_subject_account.key() {
+            return Err(VireError::InvalidTransfer.into());
+        }
+
+        let student_data_ref = student_account.try_borrow_data()?;
+        let student_account_data = bytemuck::try_from_bytes::<StudentAccount>(&student_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        let old_bump = [student_account_data.student_bump];
+        let student_seeds_with_bump = &[student.key().as_ref(), subject_account.key().as_ref(), &old_bump];
+        let student_account_derived = pubkey::create_program_address(student_seeds_with_bump, &crate::ID)?;
+        if student_account_derived != *student_account.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        if i64::from_le_bytes(student_account_data.graduated_at) != 0 {
+            return Err(VireError::AlreadyGraduated.into());
+        }
+
+        if i64::from_le_bytes(student_account_data.revoked_at) != 0 {
+            return Err(VireError::CredentialRevoked.into());
+        }
+
+        if student_account_data.on_leave() {
+            return Err(VireError::StudentOnLeave.into());
+        }
+
+        if student_account_data.is_expelled() {
+            return Err(VireError::StudentExpelled.into());
+        }
+
+        if student_account_data.is_suspended() {
+            return Err(VireError::StudentSuspended.into());
+        }
+
+        // Installment payments towards an unfinished semester don't carry over
+        if u64::from_le_bytes(student_account_data.semester_paid) != 0 {
+            return Err(VireError::SemesterPartlyPaid.into());
+        }
+
+        // Awards are drawn through the StudentAccount they were granted to, so they have to be spent first
+        if u64::from_le_bytes(student_account_data.award_remaining) != 0 {
+            return Err(VireError::ScholarshipNotSpent.into());
+        }
+
+        let paid_semesters = student_account_data.paid_semesters();
+        let student_id = student_account_data.student_id;
+        let last_payment = student_account_data.last_payment;
+        drop(student_data_ref);
+
+        subject_account_data.enrolled_count = u64::from_le_bytes(subject_account_data.enrolled_count).saturating_sub(1).to_le_bytes();
+        drop(subject_data_ref);
+
+
+        // <---Joining The New Subject--->
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        let mut new_subject_data_ref = new_subject_account.try_borrow_mut_data()?;
+        let new_subject_account_data = bytemuck::try_from_bytes_mut::<SubjectAccount>(&mut new_subject_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if new_subject_account_data.uni_key != *uni_account.key() {
+            return Err(VireError::InvalidTransfer.into());
+        }
+
+        // Intake windows and capacity apply as for a new enrolment
+        let cohort_start = if new_subject_account_data.cohort_count == 0 {
+            None
+        } else {
+            let cohort = new_subject_account_data.open_cohort(current_timestamp).ok_or(VireError::EnrolmentClosed)?;
+            Some(i64::from_le_bytes(cohort.starts_at))
+        };
+
+        if !new_subject_account_data.take_spot(student.key(), current_timestamp) {
+            return Err(VireError::SubjectFull.into());
+        }
+
+        let prerequisite_count = new_subject_account_data.prerequisite_count as usize;
+        let metadata_accounts = check_prerequisites(student.key(), &new_subject_account_data.prerequisites[..prerequisite_count], metadata_accounts)?;
+
+        // The new subject credits its share of the paid semesters
+        let credited = new_subject_account_data.transfer_credit_for(paid_semesters);
+
+        // The clock is set back by the credited semesters so the next one starts now (or at the cohort start)
+        let semester_months = i64::from_le_bytes(new_subject_account_data.semester_months);
+        let time_start = cohort_start.unwrap_or(current_timestamp) - credited as i64 * semester_months * SECONDS_IN_A_MONTH;
+        drop(new_subject_data_ref);
+
+
+        // <---Burning The Old Card--->
+
+        let old_signer_seeds = seeds!(student.key().as_ref(), subject_account.key().as_ref(), &old_bump);
+        let old_signer = Signer::from(&old_signer_seeds);
+
+        ThawAccount{
+            account: student_card_ata,
+            mint: card_mint,
+            freeze_authority: student_account,
+            token_program: card_token_program,
+        }
+        .invoke_signed(&[old_signer])?;
+
+        BurnChecked{
+            account: student_card_ata,
+            mint: card_mint,
+            authority: student,
+            amount: 1,
+            decimals: 0,
+            token_program: card_token_program,
+        }
+        .invoke()?;
+
+        close_account(student_account, student)?;
+
+
+        // <---New StudentAccount--->
+
+        let new_student_seeds_with_bump = &[student.key().as_ref(), new_subject_account.key().as_ref(), &[args.bump()]];
+        let new_student_account_derived = pubkey::create_program_address(new_student_seeds_with_bump, &crate::ID)?;
+        if new_student_account_derived != *new_student_account.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        let bump_ref = &[args.bump()];
+        let signer_seeds = seeds!(student.key().as_ref(), new_subject_account.key().as_ref(), bump_ref);
+        let signer = Signer::from(&signer_seeds);
+
+        CreateAccount{
+            from: student,
+            to: new_student_account,
+            space: StudentAccount::LEN as u64,
+            owner: &crate::ID,
+            lamports: Rent::get()?.minimum_balance(StudentAccount::LEN),
+        }
+        .invoke_signed(core::slice::from_ref(&signer))?;
+
+        let mut new_student_data_ref = new_student_account.try_borrow_mut_data()?;
+        let new_student_account_data = bytemuck::try_from_bytes_mut::<StudentAccount>(&mut new_student_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        new_student_account_data.student_key = *student.key();
+        new_student_account_data.student_id = student_id;
+        new_student_account_data.time_start = time_start.to_le_bytes();
+        // The credited semesters are paid, the next one is being paid (credited stays below max_semester)
+        new_student_account_data.semesters = (credited + 1).to_le_bytes();
+        // Credited semesters count as approved
+        new_student_account_data.approved_semesters = credited.to_le_bytes();
+        new_student_account_data.last_payment = last_payment;
+        new_student_account_data.cohort_start = cohort_start.is_some() as u8;
+        new_student_account_data.student_bump = args.bump();
+
+        // new_student_account signs the CPIs below, so it can't stay borrowed
+        drop(new_student_data_ref);
+
+
+        // <---Moving The Transcript---> (its PDA is derived from the StudentAccount, the records follow the student)
+
+        let (transcript_derived, _) = pubkey::try_find_program_address(&[b"transcript", student_account.key().as_ref()], &crate::ID)
+            .ok_or(ProgramError::InvalidSeeds)?;
+        if transcript_derived != *transcript_account.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        // Students without grades yet have no transcript to move
+        if transcript_account.is_owned_by(&crate::ID) {
+            let mut transcript = *bytemuck::try_from_bytes::<TranscriptAccount>(&transcript_account.try_borrow_data()?)
+                .map_err(|_| ProgramError::InvalidAccountData)?;
+
+            if transcript.student_account != *student_account.key() {
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            let (new_transcript_derived, transcript_bump) =
+                pubkey::try_find_program_address(&[b"transcript", new_student_account.key().as_ref()], &crate::ID)
+                .ok_or(ProgramError::InvalidSeeds)?;
+            if new_transcript_derived != *new_transcript_account.key() {
+                return Err(ProgramError::InvalidSeeds);
+            }
+
+            // The old transcript's rent pays for the new one
+            close_account(transcript_account, new_transcript_account)?;
+
+            let bump_ref = &[transcript_bump];
+            let transcript_signer_seeds = seeds!(b"transcript", new_student_account.key().as_ref(), bump_ref);
+            let transcript_signer = Signer::from(&transcript_signer_seeds);
+
+            Allocate{
+                account: new_transcript_account,
+                space: TranscriptAccount::LEN as u64,
+            }
+            .invoke_signed(core::slice::from_ref(&transcript_signer))?;
+
+            Assign{
+                account: new_transcript_account,
+                owner: &crate::ID,
+            }
+            .invoke_signed(&[transcript_signer])?;
+
+            transcript.student_account = *new_student_account.key();
+            transcript.carried_over = transcript.record_count;
+            transcript.transcript_bump = transcript_bump;
+            new_transcript_account.try_borrow_mut_data()?.copy_from_slice(bytemuck::bytes_of(&transcript));
+        }
+
+
+        // <---Re-issuing The Card--->
+
+        issue_card(
+            &CardAccounts {
+                student,
+                student_account: new_student_account,
+                subject_account: new_subject_account,
+                card_mint: new_card_mint,
+                student_card_ata: new_student_card_ata,
+                system_program,
+                token_program,
+                associated_token_program,
+                metadata_accounts,
+            },
+            args,
+            uni_account_data.uni_id,
+            student_id,
+            current_timestamp.to_le_bytes(),
+            &signer,
+        )?;
+
+        Ok(())
+    }
+}
+
+// Wipes a program account and moves its lamports to `destination`
+pub fn close_account(account: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
+    account.try_borrow_mut_data()?.fill(0);
+
+    *destination.try_borrow_mut_lamports()? += account.lamports();
+    *account.try_borrow_mut_lamports()? = 0;
+
+    account.close()
+}
@@ -1,8 +1,7 @@
 use bytemuck;
 use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, pubkey, seeds, sysvars::{clock::Clock, Sysvar}, ProgramResult};
-use pinocchio_token::instructions::{SetAuthority, ThawAccount};
 
-use crate::{student_account::StudentAccount, subject_account::SubjectAccount};
+use crate::{errors::VireError, student_account::StudentAccount, subject_account::{SubjectAccount, SECONDS_IN_A_MONTH}, token_interface::{self, MintToChecked, NftMintAccounts, Revoke, SetAuthority, ThawAccount}, transcript_account::TranscriptAccount};
 
 
 
@@ -21,18 +20,46 @@ impl <'a> Unstake<'a> for &[AccountInfo] {
             subject_account,
             card_mint,
             student_card_ata,
-            _system_program, 
-            _token_program,
+            diploma_mint,
+            student_diploma_ata,
+            system_program, 
+            token_program,
+            diploma_token_program,
+            associated_token_program,
+            transcript_accounts @ ..
             ] = self 
         else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
+        // card_mint can be a Token or Token-2022 mint
+        token_interface::check_token_program(card_mint, token_program)?;
 
-        // Use read-only access for accounts we don't modify
-        let student_data_ref = student_account.try_borrow_data()?;
-        let student_account_data = bytemuck::try_from_bytes::<StudentAccount>(&student_data_ref)
+
+        // student_account records the graduation, subject_account signs the diploma
+        if !student_account.is_owned_by(&crate::ID) || !subject_account.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let mut student_data_ref = student_account.try_borrow_mut_data()?;
+        let student_account_data = bytemuck::try_from_bytes_mut::<StudentAccount>(&mut student_data_ref)
             .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if i64::from_le_bytes(student_account_data.graduated_at) != 0 {
+            return Err(VireError::AlreadyGraduated.into());
+        }
+
+        if i64::from_le_bytes(student_account_data.revoked_at) != 0 {
+            return Err(VireError::CredentialRevoked.into());
+        }
+
+        if student_account_data.is_expelled() {
+            return Err(VireError::StudentExpelled.into());
+        }
+
+        if student_account_data.is_suspended() {
+            return Err(VireError::StudentSuspended.into());
+        }
         
    
         
@@ -43,13 +70,16 @@ impl <'a> Unstake<'a> for &[AccountInfo] {
 
 
         // Check if student has completed all required semesters
-        if student_account_data.semesters != subject_account_data.max_semester {
-            return Err(ProgramError::InvalidArgument);
+        if !student_account_data.graduated(u64::from_le_bytes(subject_account_data.max_semester)) {
+            return Err(VireError::SemestersIncomplete.into());
+        }
+
+        // ...and, if the subject requires it, had every paid semester approved
+        if subject_account_data.requires_approval == 1 && !student_account_data.paid_semesters_approved() {
+            return Err(VireError::SemesterNotApproved.into());
         }
 
         // Time calculations 
-        const SECONDS_IN_A_MONTH: i64 = 30 * 24 * 60 * 60; // 30 days in seconds
-        
         let max_semester = i64::from_le_bytes(subject_account_data.max_semester);
         let semester_months = i64::from_le_bytes(subject_account_data.semester_months);
         let required_wait_time = SECONDS_IN_A_MONTH * max_semester * semester_months;
@@ -58,11 +88,38 @@ impl <'a> Unstake<'a> for &[AccountInfo] {
         let start_timestamp = i64::from_le_bytes(student_account_data.time_start);
         // let start_timestamp = student_account_data.time_start;
 
+        if student_account_data.on_leave() {
+            return Err(VireError::StudentOnLeave.into());
+        }
 
         // Main check (Verifies whether the degree duration has been completed)
-        // Check that degree duration is not yet completed
-        if current_timestamp - start_timestamp < required_wait_time {
-            return Err(ProgramError::InvalidArgument);
+        // Check that degree duration is not yet completed, leaves of absence don't count towards it
+        if current_timestamp - start_timestamp - student_account_data.paused_seconds(current_timestamp) < required_wait_time {
+            return Err(VireError::DegreeDurationIncomplete.into());
+        }
+
+        // Subjects with a minimum grade need the student's transcript
+        let min_grade = u64::from_le_bytes(subject_account_data.min_grade);
+        if min_grade > 0 {
+            let [transcript_account, ..] = transcript_accounts else {
+                return Err(VireError::GradeRequirementNotMet.into());
+            };
+
+            if !transcript_account.is_owned_by(&crate::ID) {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+
+            let transcript_data_ref = transcript_account.try_borrow_data()?;
+            let transcript_account_data = bytemuck::try_from_bytes::<TranscriptAccount>(&transcript_data_ref)
+                .map_err(|_| ProgramError::InvalidAccountData)?;
+
+            if transcript_account_data.student_account != *student_account.key() {
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            if transcript_account_data.cumulative_grade() < min_grade {
+                return Err(VireError::GradeRequirementNotMet.into());
+            }
         }
 
         
@@ -84,6 +141,46 @@ impl <'a> Unstake<'a> for &[AccountInfo] {
         // // } //(super extra)
         
 
+        // <---Recording Graduation--->
+
+        student_account_data.graduated_at = current_timestamp.to_le_bytes();
+        student_account_data.diploma_mint = *diploma_mint.key();
+        drop(student_data_ref);
+
+        let subject_code = [u64::from_le_bytes(subject_account_data.subject_code) as u8];
+        let subject_bump = [subject_account_data.subject_bump];
+        let subject_signer_seeds = seeds!(subject_account_data.uni_key.as_ref(), &subject_code, &subject_bump);
+        let subject_signer = Signer::from(&subject_signer_seeds);
+
+
+        // <---Minting Soulbound Diploma---> (Token-2022 NonTransferable, subject_account stays mint/freeze authority)
+
+        token_interface::init_non_transferable_nft_mint(
+            &NftMintAccounts {
+                payer: student,
+                mint: diploma_mint,
+                owner: student,
+                owner_ata: student_diploma_ata,
+                system_program,
+                token_program: diploma_token_program,
+                associated_token_program,
+            },
+            subject_account.key(),
+        )?;
+
+        MintToChecked{
+            mint: diploma_mint,
+            account: student_diploma_ata,
+            mint_authority: subject_account,
+            amount: 1,
+            decimals: 0,
+            token_program: diploma_token_program,
+        }
+        .invoke_signed(&[subject_signer])?;
+
+
+        // <---Releasing Card--->
+
         let student_account_seeds = &[student.key().as_ref(), subject_account.key().as_ref()];
         let (student_account_derived, student_account_bump) = 
             pubkey::try_find_program_address(student_account_seeds, &crate::ID )
@@ -103,19 +200,34 @@ impl <'a> Unstake<'a> for &[AccountInfo] {
             account: student_card_ata,
             mint: card_mint,
             freeze_authority: student_account,
+            token_program,
         }
-        .invoke_signed(&[signer.clone()])?;
+        .invoke_signed(core::slice::from_ref(&signer))?;
         
         SetAuthority{
             account: student_card_ata,
             authority: student_account,
             authority_type: pinocchio_token::instructions::AuthorityType::FreezeAccount,
             new_authority: Some(student.key()),
+            token_program,
         }
         .invoke_signed(&[signer])?;
 
+        // The graduate's card can no longer be burnt by the program
+        Revoke{
+            account: student_card_ata,
+            owner: student,
+            token_program,
+        }.invoke()?;
 
-       
+
+        // <---Freeing The Graduate's Spot--->
+
+        drop(subject_data_ref);
+        let mut subject_data_ref = subject_account.try_borrow_mut_data()?;
+        let subject_account_data = bytemuck::try_from_bytes_mut::<SubjectAccount>(&mut subject_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        subject_account_data.enrolled_count = u64::from_le_bytes(subject_account_data.enrolled_count).saturating_sub(1).to_le_bytes();
 
         Ok(())
     }
@@ -0,0 +1,95 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use crate::{errors::VireError, token_interface, vire_account::{VireAccount, MAX_PAYMENT_MINTS}};
+
+
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct PaymentMintArgs {
+    allowed: u8, // 1 = add mint to the allowlist, 0 = remove it
+}
+
+impl PaymentMintArgs {
+    pub fn new(allowed: bool) -> Self {
+        Self { allowed: allowed as u8 }
+    }
+
+    pub fn allowed(&self) -> bool {
+        self.allowed != 0
+    }
+}
+
+impl TryFrom<&[u8]> for PaymentMintArgs {
+    type Error = ProgramError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+            bytemuck::try_from_bytes::<Self>(value)
+                .copied()
+                .map_err(|_| ProgramError::InvalidInstructionData)
+    }
+}
+
+pub trait UpdatePaymentMintContext<'a> {
+    fn update_payment_mint(&self, args: &PaymentMintArgs) -> ProgramResult;
+}
+
+impl <'a> UpdatePaymentMintContext <'a> for &[AccountInfo] {
+    fn update_payment_mint(&self, args: &PaymentMintArgs) -> ProgramResult {
+        // all the required accounts for the this instruction
+        let [
+            admin,
+            vire_account,
+            payment_mint,
+            ] = self
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Only the protocol admin can change the allowlist
+        if !admin.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if !vire_account.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let mut vire_data_ref = vire_account.try_borrow_mut_data()?;
+        let vire_account_data = bytemuck::try_from_bytes_mut::<VireAccount>(&mut vire_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if vire_account_data.admin_key != *admin.key() {
+            return Err(VireError::Unauthorized.into());
+        }
+
+        let count = vire_account_data.payment_mint_count as usize;
+        let position = vire_account_data.payment_mints[..count]
+            .iter()
+            .position(|mint| mint == payment_mint.key());
+
+        match (args.allowed(), position) {
+            (true, None) => {
+                // Only real token mints can be approved
+                token_interface::load_mint(payment_mint)?;
+
+                if count == MAX_PAYMENT_MINTS {
+                    return Err(VireError::PaymentMintListFull.into());
+                }
+                vire_account_data.payment_mints[count] = *payment_mint.key();
+                vire_account_data.payment_mint_count += 1;
+            }
+            (false, Some(index)) => {
+                // swap-remove, subjects already using the mint keep charging in it
+                vire_account_data.payment_mints[index] = vire_account_data.payment_mints[count - 1];
+                vire_account_data.payment_mints[count - 1] = [0; 32];
+                vire_account_data.payment_mint_count -= 1;
+            }
+            (true, Some(_)) => {}
+            (false, None) => return Err(VireError::PaymentMintNotAllowed.into()),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{account_info::AccountInfo, cpi::set_return_data, program_error::ProgramError, pubkey::{self, Pubkey}, ProgramResult};
+
+use crate::{student_account::StudentAccount, subject_account::SubjectAccount, token_interface::TOKEN_2022_PROGRAM_ID};
+
+
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CredentialState {
+    NotEnrolled,
+    Enrolled,
+    // All semesters paid, unstaked and still holding the diploma
+    Graduated,
+    // Revoked by the university (never counts as graduated)
+    Revoked,
+    // Expelled by the university before graduating
+    Expelled,
+}
+
+impl CredentialState {
+    // State of an enrolment: graduated once every semester is paid, the graduation recorded by Unstake
+    // and the diploma still held, revocation and expulsion take precedence
+    pub fn of(student_account_data: &StudentAccount, max_semester: u64, holds_diploma: bool) -> Self {
+        if i64::from_le_bytes(student_account_data.revoked_at) != 0 {
+            Self::Revoked
+        } else if student_account_data.is_expelled() {
+            Self::Expelled
+        } else if student_account_data.graduated(max_semester) && i64::from_le_bytes(student_account_data.graduated_at) != 0 && holds_diploma {
+            Self::Graduated
+        } else {
+            Self::Enrolled
+        }
+    }
+}
+
+impl TryFrom<u8> for CredentialState {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::NotEnrolled),
+            1 => Ok(Self::Enrolled),
+            2 => Ok(Self::Graduated),
+            3 => Ok(Self::Revoked),
+            4 => Ok(Self::Expelled),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+// Returned by VerifyCredential through set_return_data
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct CredentialStatus {
+    pub state: u8,
+    pub holds_diploma: u8,
+    pub semesters: [u8; 8],
+    pub max_semester: [u8; 8],
+    pub graduated_at: [u8; 8],
+    pub diploma_mint: Pubkey,
+    pub revoked_at: [u8; 8],
+    pub revocation_reason: u8,
+}
+
+impl CredentialStatus {
+    pub const LEN: usize = core::mem::size_of::<CredentialStatus>();
+
+    pub fn state(&self) -> Result<CredentialState, ProgramError> {
+        CredentialState::try_from(self.state)
+    }
+}
+
+pub trait VerifyCredentialContext<'a> {
+    fn verify_credential(&self) -> ProgramResult;
+}
+
+impl <'a> VerifyCredentialContext <'a> for &[AccountInfo] {
+    fn verify_credential(&self) -> ProgramResult {
+        // all the required accounts for the this instruction (none of them is modified)
+        let [
+            student,
+            student_account,
+            subject_account,
+            student_diploma_ata,
+            ] = self
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !subject_account.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let subject_data_ref = subject_account.try_borrow_data()?;
+        let subject_account_data = bytemuck::try_from_bytes::<SubjectAccount>(&subject_data_ref)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        let student_account_seeds = &[student.key().as_ref(), subject_account.key().as_ref()];
+        let (student_account_derived, _) = pubkey::try_find_program_address(student_account_seeds, &crate::ID)
+            .ok_or(ProgramError::InvalidSeeds)?;
+
+        // Ensure derived PDA matches the provided student_account
+        if student_account_derived != *student_account.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        let mut status = CredentialStatus::zeroed();
+        status.max_semester = subject_account_data.max_semester;
+
+        // A missing student_account means the wallet never enrolled
+        if student_account.is_owned_by(&crate::ID) {
+            let student_data_ref = student_account.try_borrow_data()?;
+            let student_account_data = bytemuck::try_from_bytes::<StudentAccount>(&student_data_ref)
+                .map_err(|_| ProgramError::InvalidAccountData)?;
+
+            status.semesters = student_account_data.semesters;
+            status.graduated_at = student_account_data.graduated_at;
+            status.diploma_mint = student_account_data.diploma_mint;
+            status.revoked_at = student_account_data.revoked_at;
+            status.revocation_reason = student_account_data.revocation_reason;
+            status.holds_diploma = holds_diploma(student_diploma_ata, student.key(), &student_account_data.diploma_mint)? as u8;
+
+            status.state = CredentialState::of(student_account_data, u64::from_le_bytes(subject_account_data.max_semester), status.holds_diploma == 1) as u8;
+        }
+
+        set_return_data(bytemuck::bytes_of(&status));
+
+        Ok(())
+    }
+}
+
+// Whether `ata` is a Token-2022 account of `owner` holding the diploma
+fn holds_diploma(ata: &AccountInfo, owner: &Pubkey, diploma_mint: &Pubkey) -> Result<bool, ProgramError> {
+    if !ata.is_owned_by(&TOKEN_2022_PROGRAM_ID) || *diploma_mint == Pubkey::default() {
+        return Ok(false);
+    }
+
+    // Token account layout: mint, owner, amount
+    let data = ata.try_borrow_data()?;
+    if data.len() < 72 {
+        return Ok(false);
+    }
+    let amount = u64::from_le_bytes(data[64..72].try_into().unwrap());
+
+    Ok(data[..32] == diploma_mint[..] && data[32..64] == owner[..] && amount > 0)
+}
@@ -2,13 +2,39 @@ use pinocchio::program_error::ProgramError;
 
 
 #[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum VireInstruction {
     InitializeVire,
     InitializeUni,
     AddSubjects,
     InitializeStudent,
     PayTutionFee,
-    UnStake
+    UnStake,
+    UpdatePaymentMint,
+    VerifyCredential,
+    RevokeCredential,
+    // 9 was SetGrader, graders are added with AddRole
+    RecordGrades = 10,
+    ApproveSemester,
+    SponsorPayTuition,
+    InitializeScholarshipPool,
+    DepositScholarship,
+    AwardScholarship,
+    CreateDiscount,
+    CreateWaitlist,
+    JoinWaitlist,
+    OfferWaitlistSpot,
+    AddIntakeCohort,
+    TransferEnrollment,
+    RequestLeave,
+    ResumeStudies,
+    SuspendStudent,
+    ExpelStudent,
+    AddRole,
+    RemoveRole,
+    ApproveUni,
+    SuspendUni,
+    ReinstateUni,
 }
 
 
@@ -23,6 +49,30 @@ impl TryFrom<&u8> for VireInstruction{
             3 => Ok(Self::InitializeStudent),
             4 => Ok(Self::PayTutionFee),
             5 => Ok(Self::UnStake),
+            6 => Ok(Self::UpdatePaymentMint),
+            7 => Ok(Self::VerifyCredential),
+            8 => Ok(Self::RevokeCredential),
+            10 => Ok(Self::RecordGrades),
+            11 => Ok(Self::ApproveSemester),
+            12 => Ok(Self::SponsorPayTuition),
+            13 => Ok(Self::InitializeScholarshipPool),
+            14 => Ok(Self::DepositScholarship),
+            15 => Ok(Self::AwardScholarship),
+            16 => Ok(Self::CreateDiscount),
+            17 => Ok(Self::CreateWaitlist),
+            18 => Ok(Self::JoinWaitlist),
+            19 => Ok(Self::OfferWaitlistSpot),
+            20 => Ok(Self::AddIntakeCohort),
+            21 => Ok(Self::TransferEnrollment),
+            22 => Ok(Self::RequestLeave),
+            23 => Ok(Self::ResumeStudies),
+            24 => Ok(Self::SuspendStudent),
+            25 => Ok(Self::ExpelStudent),
+            26 => Ok(Self::AddRole),
+            27 => Ok(Self::RemoveRole),
+            28 => Ok(Self::ApproveUni),
+            29 => Ok(Self::SuspendUni),
+            30 => Ok(Self::ReinstateUni),
             _ => Err(ProgramError::InvalidInstructionData),
         }
     }
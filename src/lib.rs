@@ -1,13 +1,24 @@
 #![allow(unexpected_cfgs)]
-use instructions::{add_subjects::AddSubjectContext, initialize_student::InitializeStudentContext, initialize_uni::InitializeUniContext, initialize_vire::InitializeVireContext, pay_tution_fee::PayTutionFeeContext, unstake::Unstake, vire_instructions::VireInstruction};
-use pinocchio::{account_info::AccountInfo, entrypoint, program_error::ProgramError, pubkey::Pubkey, ProgramResult};
+use instructions::{add_intake_cohort::AddIntakeCohortContext, add_role::AddRoleContext, add_subjects::AddSubjectContext, approve_semester::ApproveSemesterContext, approve_uni::ApproveUniContext, award_scholarship::AwardScholarshipContext, create_discount::CreateDiscountContext, create_waitlist::CreateWaitlistContext, deposit_scholarship::DepositScholarshipContext, expel_student::ExpelStudentContext, initialize_scholarship_pool::InitializeScholarshipPoolContext, initialize_student::InitializeStudentContext, initialize_uni::InitializeUniContext, initialize_vire::InitializeVireContext, join_waitlist::JoinWaitlistContext, offer_waitlist_spot::OfferWaitlistSpotContext, pay_tution_fee::PayTutionFeeContext, record_grades::RecordGradesContext, reinstate_uni::ReinstateUniContext, remove_role::RemoveRoleContext, request_leave::RequestLeaveContext, resume_studies::ResumeStudiesContext, revoke_credential::RevokeCredentialContext, sponsor_pay_tuition::SponsorPayTuitionContext, suspend_student::SuspendStudentContext, suspend_uni::SuspendUniContext, transfer_enrollment::TransferEnrollmentContext, unstake::Unstake, update_payment_mint::UpdatePaymentMintContext, verify_credential::VerifyCredentialContext, vire_instructions::VireInstruction};
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult};
 
-mod instructions;
+pub mod errors;
+pub mod idl;
+pub mod instructions;
 mod states;
+pub mod token_interface;
+pub mod token_metadata;
 pub use states::*;
 
 
-entrypoint!(process_instruction);
+#[cfg(not(feature = "no-entrypoint"))]
+mod entrypoint {
+    use pinocchio::entrypoint;
+
+    use crate::process_instruction;
+
+    entrypoint!(process_instruction);
+}
 
 pinocchio_pubkey::declare_id!("Hh6AGqBdAeXJF64MmkLrV5yD3citghoEh4MDyh4rHy9j");
 
@@ -27,6 +38,30 @@ pub fn process_instruction(
         VireInstruction::InitializeStudent => accounts.initialize_student(&data.try_into()?),
         VireInstruction::PayTutionFee => accounts.pay_tution_fee(),
         VireInstruction::UnStake => accounts.unstake(),
+        VireInstruction::UpdatePaymentMint => accounts.update_payment_mint(&data.try_into()?),
+        VireInstruction::VerifyCredential => accounts.verify_credential(),
+        VireInstruction::RevokeCredential => accounts.revoke_credential(&data.try_into()?),
+        VireInstruction::RecordGrades => accounts.record_grades(&data.try_into()?),
+        VireInstruction::ApproveSemester => accounts.approve_semester(&data.try_into()?),
+        VireInstruction::SponsorPayTuition => accounts.sponsor_pay_tuition(),
+        VireInstruction::InitializeScholarshipPool => accounts.initialize_scholarship_pool(&data.try_into()?),
+        VireInstruction::DepositScholarship => accounts.deposit_scholarship(&data.try_into()?),
+        VireInstruction::AwardScholarship => accounts.award_scholarship(&data.try_into()?),
+        VireInstruction::CreateDiscount => accounts.create_discount(&data.try_into()?),
+        VireInstruction::CreateWaitlist => accounts.create_waitlist(&data.try_into()?),
+        VireInstruction::JoinWaitlist => accounts.join_waitlist(),
+        VireInstruction::OfferWaitlistSpot => accounts.offer_waitlist_spot(),
+        VireInstruction::AddIntakeCohort => accounts.add_intake_cohort(&data.try_into()?),
+        VireInstruction::TransferEnrollment => accounts.transfer_enrollment(&data.try_into()?),
+        VireInstruction::RequestLeave => accounts.request_leave(),
+        VireInstruction::ResumeStudies => accounts.resume_studies(),
+        VireInstruction::SuspendStudent => accounts.suspend_student(&data.try_into()?),
+        VireInstruction::ExpelStudent => accounts.expel_student(&data.try_into()?),
+        VireInstruction::AddRole => accounts.add_role(&data.try_into()?),
+        VireInstruction::RemoveRole => accounts.remove_role(&data.try_into()?),
+        VireInstruction::ApproveUni => accounts.approve_uni(),
+        VireInstruction::SuspendUni => accounts.suspend_uni(),
+        VireInstruction::ReinstateUni => accounts.reinstate_uni(),
     }?;
 
     Ok(())
@@ -0,0 +1,72 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey, ProgramResult};
+
+use crate::errors::VireError;
+
+
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DiscountKind {
+    // value is a percentage of each payment (100 = fee waiver)
+    Percent,
+    // value is taken off each payment
+    Fixed,
+}
+
+impl TryFrom<u8> for DiscountKind {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Percent),
+            1 => Ok(Self::Fixed),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct DiscountAccount {
+    pub subject_key: Pubkey,
+    pub discount_id: [u8; 8],
+    pub kind: u8,
+    pub value: [u8; 8],
+    pub expires_at: [u8; 8], // i64 unix timestamp, 0 = never
+    pub max_uses: [u8; 8], // 0 = unlimited
+    pub uses: [u8; 8],
+    pub student_key: Pubkey, // zero = any student of the subject
+    pub discount_bump: u8,
+}
+
+impl DiscountAccount {
+    pub const LEN: usize = core::mem::size_of::<DiscountAccount>();
+
+    // Whether `student_key` can use the discount in `subject_key`
+    pub fn is_for(&self, subject_key: &Pubkey, student_key: &Pubkey) -> bool {
+        self.subject_key == *subject_key && (self.student_key == Pubkey::default() || self.student_key == *student_key)
+    }
+
+    // Counts a use, a semester is one use however many installments it is paid in
+    pub fn take_use(&mut self) -> ProgramResult {
+        let uses = u64::from_le_bytes(self.uses);
+        let max_uses = u64::from_le_bytes(self.max_uses);
+        if max_uses != 0 && uses >= max_uses {
+            return Err(VireError::DiscountUsedUp.into());
+        }
+        self.uses = (uses + 1).to_le_bytes();
+        Ok(())
+    }
+
+    // Amount left to pay after the discount
+    pub fn apply(&self, amount: u64) -> u64 {
+        let value = u64::from_le_bytes(self.value);
+        match DiscountKind::try_from(self.kind) {
+            Ok(DiscountKind::Percent) => amount - amount * value.min(100) / 100,
+            Ok(DiscountKind::Fixed) => amount.saturating_sub(value),
+            Err(_) => amount,
+        }
+    }
+}
+
+// seeds = [b"discount", subject_account.key().as_ref(), &discount_id]
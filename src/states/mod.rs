@@ -2,3 +2,8 @@ pub mod vire_account;
 pub mod uni_account;
 pub mod subject_account;
 pub mod student_account;
+pub mod transcript_account;
+pub mod scholarship_pool;
+pub mod scholarship_award;
+pub mod discount_account;
+pub mod waitlist_account;
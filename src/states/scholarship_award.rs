@@ -0,0 +1,32 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::pubkey::Pubkey;
+
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct ScholarshipAward {
+    pub pool: Pubkey,
+    pub student_account: Pubkey,
+    pub awarded: [u8; 8],
+    pub used: [u8; 8], // drawn by tuition payments
+    pub award_bump: u8,
+}
+
+impl ScholarshipAward {
+    pub const LEN: usize = core::mem::size_of::<ScholarshipAward>();
+
+    pub fn remaining(&self) -> u64 {
+        u64::from_le_bytes(self.awarded) - u64::from_le_bytes(self.used)
+    }
+
+    // Draws the award for a payment of `uni_total` to the university and `treasury_total` to the protocol,
+    // covering the university first; returns the amounts drawn for each
+    pub fn draw(&mut self, uni_total: u64, treasury_total: u64) -> (u64, u64) {
+        let award_uni = self.remaining().min(uni_total);
+        let award_treasury = (self.remaining() - award_uni).min(treasury_total);
+        self.used = (u64::from_le_bytes(self.used) + award_uni + award_treasury).to_le_bytes();
+        (award_uni, award_treasury)
+    }
+}
+
+// seeds = [b"award", scholarship_pool.key().as_ref(), student_account.key().as_ref()]
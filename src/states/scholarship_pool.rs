@@ -0,0 +1,27 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::pubkey::Pubkey;
+
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct ScholarshipPool {
+    pub uni_key: Pubkey,
+    pub subject_key: Pubkey, // zero = the pool funds every subject of the university
+    pub mint: Pubkey,
+    pub vault: Pubkey, // ATA of the pool for mint
+    pub total_deposited: [u8; 8],
+    pub total_awarded: [u8; 8],
+    pub total_used: [u8; 8],
+    pub pool_bump: u8,
+}
+
+impl ScholarshipPool {
+    pub const LEN: usize = core::mem::size_of::<ScholarshipPool>();
+
+    // uni_account or subject_account, whichever the pool was created for
+    pub fn scope(&self) -> &Pubkey {
+        if self.subject_key == Pubkey::default() { &self.uni_key } else { &self.subject_key }
+    }
+}
+
+// seeds = [b"scholarship", scope.key().as_ref()] (scope = uni_account or subject_account)
@@ -1,6 +1,26 @@
 use bytemuck::{Pod, Zeroable};
-use pinocchio::pubkey::Pubkey;
+use pinocchio::{pubkey::Pubkey, ProgramResult};
 
+use crate::errors::VireError;
+
+
+pub const MAX_LEAVES: usize = 4;
+
+// Leave of absence approved by the university, the degree clock is paused in between
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct LeaveInterval {
+    pub started_at: [u8; 8],
+    pub ended_at: [u8; 8], // 0 while the student is on leave
+}
+
+// Tuition a payer put into a semester out of their own token account (discounts and scholarship draws left out)
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct SemesterPayment {
+    pub sponsor: Pubkey, // the student or a sponsor, refunds go back to it
+    pub paid: [u8; 8],
+}
 
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)] //(checkout pod u16...)
@@ -10,6 +30,23 @@ pub struct StudentAccount{
     pub time_start:  [u8; 8],
     // pub time_start:  i64, //<----- for time
     pub semesters: [u8; 8],
+    pub semester_paid: [u8; 8], // tuition paid so far for the current semester (installment plans)
+    pub approved_semesters: [u8; 8], // semesters signed off by the university (subjects with requires_approval)
+    pub payment: SemesterPayment, // paid towards the semester in progress
+    pub last_payment: SemesterPayment, // paid towards the last fully paid semester
+    pub graduated_at: [u8; 8], // i64 unix timestamp, 0 until unstake
+    pub diploma_mint: Pubkey,
+    pub revoked_at: [u8; 8], // i64 unix timestamp, 0 unless the university revoked the credential
+    pub revocation_reason: u8,
+    pub cohort_start: u8, // 1 = time_start is the intake cohort's start instead of the first payment
+    pub leave_count: u8,
+    pub leaves: [LeaveInterval; MAX_LEAVES],
+    pub suspended_at: [u8; 8], // i64 unix timestamp, 0 unless the university suspended the student
+    pub suspended_seconds: [u8; 8], // time spent in lifted suspensions
+    pub expelled_at: [u8; 8], // i64 unix timestamp, 0 unless the university expelled the student
+    pub refunded: [u8; 8], // tuition refunded to the sponsor, a student is refunded once
+    pub semester_discount: Pubkey, // discount the semester in progress was started with, later installments only take that one
+    pub award_remaining: [u8; 8], // scholarship funds awarded to the enrolment and not drawn yet
     pub student_bump: u8,
     // pub _padding: [u8; 7]
 }
@@ -18,6 +55,144 @@ pub struct StudentAccount{
 
 impl StudentAccount {
     pub const LEN: usize = core::mem::size_of::<StudentAccount>();
+
+    pub fn on_leave(&self) -> bool {
+        self.leave_count > 0 && i64::from_le_bytes(self.leaves[self.leave_count as usize - 1].ended_at) == 0
+    }
+
+    pub fn is_suspended(&self) -> bool {
+        i64::from_le_bytes(self.suspended_at) != 0
+    }
+
+    pub fn is_expelled(&self) -> bool {
+        i64::from_le_bytes(self.expelled_at) != 0
+    }
+
+    // `semesters` starts at 1 and is the semester being paid, so one less has been paid
+    pub fn paid_semesters(&self) -> u64 {
+        u64::from_le_bytes(self.semesters).saturating_sub(1)
+    }
+
+    // Every one of the subject's `max_semester` semesters has been paid
+    pub fn graduated(&self, max_semester: u64) -> bool {
+        self.paid_semesters() == max_semester
+    }
+
+    // Every paid semester has been signed off with ApproveSemester (subjects with requires_approval)
+    pub fn paid_semesters_approved(&self) -> bool {
+        u64::from_le_bytes(self.approved_semesters) >= self.paid_semesters()
+    }
+
+    // Signs off `semester`, semesters are approved in order once they have been paid
+    pub fn approve_semester(&mut self, semester: u64) -> ProgramResult {
+        if semester != u64::from_le_bytes(self.approved_semesters) + 1 || semester > self.paid_semesters() {
+            return Err(VireError::InvalidSemesterApproval.into());
+        }
+        self.approved_semesters = semester.to_le_bytes();
+        Ok(())
+    }
+
+    // Graduation recorded by Unstake and not revoked since
+    pub fn completed(&self) -> bool {
+        i64::from_le_bytes(self.graduated_at) != 0 && i64::from_le_bytes(self.revoked_at) == 0
+    }
+
+    // The payment a refund is for: the semester in progress if it was partly paid, otherwise the last paid one
+    pub fn refundable_payment(&self) -> &SemesterPayment {
+        match u64::from_le_bytes(self.semester_paid) {
+            0 => &self.last_payment,
+            _ => &self.payment,
+        }
+    }
+
+    // Refund due to the sponsor of the refundable payment under a `refund_percent` policy
+    pub fn refund_due(&self, refund_percent: u8) -> u64 {
+        u64::from_le_bytes(self.refundable_payment().paid) * refund_percent as u64 / 100
+    }
+
+    // Records `amount` refunded to the sponsor, a student is refunded once
+    pub fn record_refund(&mut self, amount: u64) {
+        self.refunded = amount.to_le_bytes();
+        self.take_back_refunded_semester();
+    }
+
+    // The refunded semester isn't credited anymore, a reinstated student pays it again
+    pub fn take_back_refunded_semester(&mut self) {
+        if u64::from_le_bytes(self.semester_paid) > 0 {
+            self.semester_paid = [0; 8];
+            self.semester_discount = Pubkey::default();
+            self.payment = SemesterPayment::zeroed();
+        } else {
+            let semesters = u64::from_le_bytes(self.semesters).saturating_sub(1).max(1);
+            let approved_semesters = u64::from_le_bytes(self.approved_semesters).min(semesters - 1);
+            self.semesters = semesters.to_le_bytes();
+            self.approved_semesters = approved_semesters.to_le_bytes();
+            self.last_payment = SemesterPayment::zeroed();
+        }
+    }
+
+    // Records `amount` of tuition `payer` put into the semester in progress out of its own token account,
+    // a semester has one sponsor
+    pub fn record_payment(&mut self, payer: &Pubkey, amount: u64) -> ProgramResult {
+        if self.payment.sponsor != Pubkey::default() && self.payment.sponsor != *payer {
+            return Err(VireError::SponsorMismatch.into());
+        }
+        self.payment.sponsor = *payer;
+        self.payment.paid = (u64::from_le_bytes(self.payment.paid) + amount).to_le_bytes();
+        Ok(())
+    }
+
+    // Moves on to the next semester once the one in progress is fully paid
+    pub fn complete_semester(&mut self) {
+        self.semesters = (u64::from_le_bytes(self.semesters) + 1).to_le_bytes();
+        self.semester_paid = [0; 8];
+        self.semester_discount = Pubkey::default();
+        self.last_payment = self.payment;
+        self.payment = SemesterPayment::zeroed();
+    }
+
+    // Starts a leave of absence at `now`, the degree clock is paused until end_leave
+    pub fn start_leave(&mut self, now: i64) -> ProgramResult {
+        if self.on_leave() {
+            return Err(VireError::StudentOnLeave.into());
+        }
+
+        let leave_count = self.leave_count as usize;
+        if leave_count == MAX_LEAVES {
+            return Err(VireError::TooManyLeaves.into());
+        }
+
+        self.leaves[leave_count] = LeaveInterval { started_at: now.to_le_bytes(), ended_at: [0; 8] };
+        self.leave_count += 1;
+        Ok(())
+    }
+
+    pub fn end_leave(&mut self, now: i64) -> ProgramResult {
+        if !self.on_leave() {
+            return Err(VireError::NotOnLeave.into());
+        }
+
+        self.leaves[self.leave_count as usize - 1].ended_at = now.to_le_bytes();
+        Ok(())
+    }
+
+    // Seconds spent on leave or suspended up to `now`, left out of the degree clock
+    pub fn paused_seconds(&self, now: i64) -> i64 {
+        let leaves: i64 = self.leaves[..self.leave_count as usize]
+            .iter()
+            .map(|leave| match i64::from_le_bytes(leave.ended_at) {
+                0 => now - i64::from_le_bytes(leave.started_at),
+                ended_at => ended_at - i64::from_le_bytes(leave.started_at),
+            })
+            .sum();
+
+        let suspension = match i64::from_le_bytes(self.suspended_at) {
+            0 => 0,
+            suspended_at => now - suspended_at,
+        };
+
+        leaves + i64::from_le_bytes(self.suspended_seconds) + suspension
+    }
 }
 
 
@@ -1,6 +1,28 @@
 use bytemuck::{Pod, Zeroable};
-use pinocchio::pubkey::Pubkey;
+use pinocchio::{pubkey::Pubkey, ProgramResult};
 
+use crate::{errors::VireError, waitlist_account::WaitlistAccount};
+
+
+pub const SECONDS_IN_A_DAY: i64 = 24 * 60 * 60;
+pub const SECONDS_IN_A_MONTH: i64 = 30 * SECONDS_IN_A_DAY; // 30 days in seconds
+pub const MAX_INTAKE_COHORTS: usize = 4;
+pub const MAX_PREREQUISITES: usize = 4;
+
+// Enrolment window of one intake, every student enrolled in it starts at starts_at
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct IntakeCohort {
+    pub opens_at: [u8; 8],
+    pub closes_at: [u8; 8],
+    pub starts_at: [u8; 8],
+}
+
+impl IntakeCohort {
+    pub fn is_open(&self, now: i64) -> bool {
+        i64::from_le_bytes(self.opens_at) <= now && now <= i64::from_le_bytes(self.closes_at)
+    }
+}
 
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
@@ -10,11 +32,168 @@ pub struct SubjectAccount{
     pub tution_fee: [u8; 8],
     pub max_semester: [u8; 8],
     pub semester_months: [u8; 8],
+    pub payment_mint: Pubkey,
+    pub collection_mint: Pubkey,
+    pub min_grade: [u8; 8], // minimum cumulative transcript grade to unstake, 0 = none
+    pub requires_approval: u8, // 1 = each semester needs ApproveSemester before the next payment
+    pub payment_window_days: [u8; 8], // days after a semester starts to pay without late fee, 0 = pay any time
+    pub late_fee: [u8; 8], // percent of the semester fee added after the payment window
+    pub installments: u8, // monthly installments per semester, 0 or 1 = paid at once
+    pub max_students: [u8; 8], // 0 = unlimited
+    pub enrolled_count: [u8; 8], // students enrolled and not yet graduated
+    pub offered_student: Pubkey, // waitlisted wallet holding a reserved spot, zero = none
+    pub offer_expires_at: [u8; 8], // i64 unix timestamp the reserved spot is released at
+    pub cohort_count: u8, // 0 = enrol any time, the clock starts at the first payment
+    pub cohorts: [IntakeCohort; MAX_INTAKE_COHORTS],
+    pub prerequisite_count: u8,
+    pub prerequisites: [Pubkey; MAX_PREREQUISITES], // subjects a student has to graduate from before enrolling
+    pub transfer_credit: u8, // percent of the semesters paid in another subject of the university credited on transfer
+    pub refund_percent: u8, // percent of the current semester's tuition refunded to the sponsor on suspension or expulsion
+    pub waitlist_len: u8, // wallets queued on the subject's waitlist, freed spots go to them first
     pub subject_bump: u8,
 }
 
 impl SubjectAccount {
     pub const LEN: usize = core::mem::size_of::<SubjectAccount>();
+
+    // Unix time semester `semester` (1-based) starts at, counted from the student's first payment
+    pub fn semester_start(&self, time_start: i64, semester: u64) -> i64 {
+        let semester_months = i64::from_le_bytes(self.semester_months);
+        time_start + (semester as i64 - 1) * semester_months * SECONDS_IN_A_MONTH
+    }
+
+    // Tuition of one semester
+    pub fn semester_fee(&self) -> u64 {
+        u64::from_le_bytes(self.tution_fee) / u64::from_le_bytes(self.max_semester)
+    }
+
+    // Installment plan: the semester fee is paid in `installments` monthly parts, the last one takes the remainder.
+    // Returns the amount due next after `semester_paid` and its (0-based) index
+    pub fn next_installment(&self, semester_paid: u64) -> (u64, i64) {
+        let semester_fee = self.semester_fee();
+        let installments = (self.installments as u64).max(1);
+        let installment = semester_fee / installments;
+        let remaining = semester_fee - semester_paid;
+        let amount = if remaining <= installment + semester_fee % installments { remaining } else { installment };
+        (amount, semester_paid.checked_div(installment).unwrap_or(0) as i64)
+    }
+
+    // End of the window to pay installment `installment_index` (0-based) of a semester that opens at `window_opens`
+    // without the late fee, a month later for every installment
+    pub fn payment_due(&self, window_opens: i64, installment_index: i64) -> i64 {
+        window_opens + installment_index * SECONDS_IN_A_MONTH + i64::from_le_bytes(self.payment_window_days) * SECONDS_IN_A_DAY
+    }
+
+    // Late fee added to a payment of `amount` made after its window (late_fee is a percentage up to 100)
+    pub fn late_fee_on(&self, amount: u64) -> Option<u64> {
+        amount.checked_mul(u64::from_le_bytes(self.late_fee)).map(|fee| fee / 100)
+    }
+
+    // Semesters credited on transfer for `paid_semesters` paid in another subject of the university,
+    // all but the last one at most, so the student still has to pay it to graduate
+    pub fn transfer_credit_for(&self, paid_semesters: u64) -> u64 {
+        let max_semester = u64::from_le_bytes(self.max_semester);
+        (paid_semesters * self.transfer_credit as u64 / 100).min(max_semester.saturating_sub(1))
+    }
+
+    // Cohort whose enrolment window is open at `now`
+    pub fn open_cohort(&self, now: i64) -> Option<&IntakeCohort> {
+        self.cohorts[..self.cohort_count as usize].iter().find(|cohort| cohort.is_open(now))
+    }
+
+    // Schedules `cohort`, appended while there is room, afterwards a cohort whose window has closed by `now` is replaced
+    pub fn add_cohort(&mut self, cohort: IntakeCohort, now: i64) -> ProgramResult {
+        if i64::from_le_bytes(cohort.opens_at) >= i64::from_le_bytes(cohort.closes_at) {
+            return Err(VireError::InvalidIntakeCohort.into());
+        }
+
+        // Students' semesters (and payment windows) count from the start, so it can't be unset or in the past
+        let starts_at = i64::from_le_bytes(cohort.starts_at);
+        if starts_at == 0 || starts_at < now {
+            return Err(VireError::InvalidIntakeCohort.into());
+        }
+
+        let cohort_count = self.cohort_count as usize;
+        let slot = if cohort_count < MAX_INTAKE_COHORTS {
+            self.cohort_count += 1;
+            cohort_count
+        } else {
+            self.cohorts
+                .iter()
+                .position(|c| i64::from_le_bytes(c.closes_at) < now)
+                .ok_or(VireError::IntakeCohortsFull)?
+        };
+
+        self.cohorts[slot] = cohort;
+        Ok(())
+    }
+
+    // Spot held for the waitlisted wallet until its offer expires
+    pub fn has_active_offer(&self, now: i64) -> bool {
+        self.offered_student != [0; 32] && now <= i64::from_le_bytes(self.offer_expires_at)
+    }
+
+    // Takes a spot for `student`, the one held for it by a waitlist offer or an open one (false when full,
+    // or while waitlisted wallets are still to be offered the open spots)
+    pub fn take_spot(&mut self, student: &Pubkey, now: i64) -> bool {
+        if self.has_active_offer(now) && self.offered_student == *student {
+            self.offered_student = [0; 32];
+            self.offer_expires_at = [0; 8];
+        } else if self.open_spots(now) == 0 || self.waitlist_len > 0 {
+            return false;
+        }
+
+        self.enrolled_count = (u64::from_le_bytes(self.enrolled_count) + 1).to_le_bytes();
+        true
+    }
+
+    // Spots left for enrolments that don't hold the waitlist offer
+    pub fn open_spots(&self, now: i64) -> u64 {
+        let max_students = u64::from_le_bytes(self.max_students);
+        if max_students == 0 {
+            return u64::MAX;
+        }
+        max_students
+            .saturating_sub(u64::from_le_bytes(self.enrolled_count))
+            .saturating_sub(self.has_active_offer(now) as u64)
+    }
+
+    // Queues `student` on the subject's waitlist, only worth it while every spot is taken
+    // or others are already queued for the free ones
+    pub fn join_waitlist(&mut self, waitlist: &mut WaitlistAccount, student: &Pubkey, now: i64) -> ProgramResult {
+        if self.open_spots(now) > 0 && self.waitlist_len == 0 {
+            return Err(VireError::SubjectNotFull.into());
+        }
+
+        if waitlist.contains(student) || (self.has_active_offer(now) && self.offered_student == *student) {
+            return Err(VireError::AlreadyWaitlisted.into());
+        }
+
+        if !waitlist.push(*student) {
+            return Err(VireError::WaitlistFull.into());
+        }
+        self.waitlist_len = waitlist.len;
+        Ok(())
+    }
+
+    // Holds a free spot for the next wallet on `waitlist` until its offer expires,
+    // one offer at a time (an expired one gives its spot to the next wallet)
+    pub fn offer_spot(&mut self, waitlist: &mut WaitlistAccount, now: i64) -> ProgramResult {
+        if self.has_active_offer(now) {
+            return Err(VireError::WaitlistOfferActive.into());
+        }
+
+        if self.open_spots(now) == 0 {
+            return Err(VireError::SubjectFull.into());
+        }
+
+        let next = waitlist.pop().ok_or(VireError::WaitlistEmpty)?;
+        self.waitlist_len = waitlist.len;
+
+        self.offered_student = next;
+        self.offer_expires_at = (now + i64::from_le_bytes(waitlist.offer_seconds)).to_le_bytes();
+        Ok(())
+    }
 }
 
 // seeds = [uni_account.key().as_ref(), &[uni_account.subject_number]]
\ No newline at end of file
@@ -0,0 +1,53 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::pubkey::Pubkey;
+
+
+pub const MAX_TRANSCRIPT_RECORDS: usize = 16;
+
+// Results of one semester, recorded by the university's grader
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct SemesterRecord {
+    pub semester: [u8; 8],
+    pub grade: [u8; 8], // GPA x100 (e.g. 350 = 3.50) or credit points, as defined by the university
+    pub credits: [u8; 8],
+    pub transcript_hash: [u8; 32], // hash of the off-chain transcript document
+    pub recorded_at: [u8; 8],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct TranscriptAccount {
+    pub student_account: Pubkey,
+    pub record_count: u8,
+    pub total_credits: [u8; 8],
+    pub weighted_grade_sum: [u8; 8], // sum of grade * credits
+    pub records: [SemesterRecord; MAX_TRANSCRIPT_RECORDS],
+    pub carried_over: u8, // records moved over from the subject the student transferred out of
+    pub transcript_bump: u8,
+}
+
+impl TranscriptAccount {
+    pub const LEN: usize = core::mem::size_of::<TranscriptAccount>();
+
+    // Credit-weighted average of all recorded semesters
+    pub fn cumulative_grade(&self) -> u64 {
+        let total_credits = u64::from_le_bytes(self.total_credits);
+        if total_credits == 0 {
+            return 0;
+        }
+        u64::from_le_bytes(self.weighted_grade_sum) / total_credits
+    }
+
+    // Semester of the last record made in this subject, 0 before the first one
+    // (numbering starts over after the records carried over from a transfer)
+    pub fn last_semester(&self) -> u64 {
+        let count = self.record_count as usize;
+        match count - self.carried_over as usize {
+            0 => 0,
+            _ => u64::from_le_bytes(self.records[count - 1].semester),
+        }
+    }
+}
+
+// seeds = [b"transcript", student_account.key().as_ref()]
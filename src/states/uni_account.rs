@@ -1,6 +1,46 @@
 use bytemuck::{Pod, Zeroable};
-use pinocchio::pubkey::Pubkey;
+use pinocchio::{program_error::ProgramError, pubkey::{self, Pubkey}, ProgramResult};
 
+use crate::errors::VireError;
+
+
+pub const MAX_STAFF: usize = 8;
+
+// Role bits held by a staff member
+pub const ROLE_FINANCE: u8 = 1 << 0; // discounts, scholarships and refunds
+pub const ROLE_REGISTRAR: u8 = 1 << 1; // subjects and student records
+pub const ROLE_GRADER: u8 = 1 << 2; // transcripts
+pub const ALL_ROLES: u8 = ROLE_FINANCE | ROLE_REGISTRAR | ROLE_GRADER;
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UniStatus {
+    // Registered, waiting for ApproveUni
+    Pending,
+    Active,
+    // Suspended by the Vire admin until ReinstateUni
+    Suspended,
+}
+
+impl TryFrom<u8> for UniStatus {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Pending),
+            1 => Ok(Self::Active),
+            2 => Ok(Self::Suspended),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct StaffMember {
+    pub member: Pubkey,
+    pub roles: u8,
+}
 
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
@@ -10,11 +50,83 @@ pub struct UniAccount{
     pub uni_id: [u8; 8],
     pub subject_number: [u8; 8],
     pub student_number: [u8; 8],
+    pub staff_count: u8,
+    pub staff: [StaffMember; MAX_STAFF], // wallets acting for the university, managed by the admin with AddRole/RemoveRole
+    pub status: u8, // UniStatus, set by the Vire admin
     pub uni_bump: u8,
 }
 
 impl UniAccount {
     pub const LEN: usize = core::mem::size_of::<UniAccount>();
+
+    // uni_account is the PDA of [uni_admin, vire_account], so the admin is checked by re-deriving it
+    pub fn is_admin(&self, uni_account: &Pubkey, admin: &Pubkey) -> bool {
+        let seeds: &[&[u8]] = &[admin.as_ref(), self.vire_key.as_ref(), &[self.uni_bump]];
+        pubkey::create_program_address(seeds, &crate::ID).is_ok_and(|derived| derived == *uni_account)
+    }
+
+    pub fn status(&self) -> Result<UniStatus, ProgramError> {
+        UniStatus::try_from(self.status)
+    }
+
+    // Universities only operate once approved and while not suspended
+    pub fn check_active(&self) -> ProgramResult {
+        match self.status()? {
+            UniStatus::Active => Ok(()),
+            UniStatus::Pending => Err(VireError::UniPending.into()),
+            UniStatus::Suspended => Err(VireError::UniSuspended.into()),
+        }
+    }
+
+    pub fn staff_position(&self, member: &Pubkey) -> Option<usize> {
+        self.staff[..self.staff_count as usize].iter().position(|staff| staff.member == *member)
+    }
+
+    // Whether `signer` is on the staff list with every bit of `role`
+    pub fn staff_holds(&self, signer: &Pubkey, role: u8) -> bool {
+        self.staff_position(signer).is_some_and(|index| self.staff[index].roles & role == role)
+    }
+
+    // The admin holds every role, staff members the ones granted to them
+    // (the staff list is checked first, it doesn't need to re-derive the PDA)
+    pub fn has_role(&self, uni_account: &Pubkey, signer: &Pubkey, role: u8) -> bool {
+        self.staff_holds(signer, role) || self.is_admin(uni_account, signer)
+    }
+
+    // Adds `roles` to `member`, putting them on the staff list if they aren't on it yet
+    pub fn grant_role(&mut self, member: &Pubkey, roles: u8) -> ProgramResult {
+        match self.staff_position(member) {
+            Some(index) => self.staff[index].roles |= roles,
+            None => {
+                let staff_count = self.staff_count as usize;
+                if staff_count == MAX_STAFF {
+                    return Err(VireError::StaffListFull.into());
+                }
+
+                self.staff[staff_count] = StaffMember { member: *member, roles };
+                self.staff_count += 1;
+            }
+        }
+        Ok(())
+    }
+
+    // Takes `roles` away from `member`, a member left without roles is dropped and the last entry takes its slot
+    pub fn revoke_role(&mut self, member: &Pubkey, roles: u8) -> ProgramResult {
+        let index = self.staff_position(member).ok_or(VireError::RoleNotHeld)?;
+        if self.staff[index].roles & roles == 0 {
+            return Err(VireError::RoleNotHeld.into());
+        }
+
+        self.staff[index].roles &= !roles;
+
+        if self.staff[index].roles == 0 {
+            let last = self.staff_count as usize - 1;
+            self.staff[index] = self.staff[last];
+            self.staff[last] = StaffMember::zeroed();
+            self.staff_count -= 1;
+        }
+        Ok(())
+    }
 }
 
 // seeds = [uni_admin.key().as_ref(), vire_account.key().as_ref()]
\ No newline at end of file
@@ -2,6 +2,8 @@ use bytemuck::{Pod, Zeroable};
 use pinocchio::pubkey::Pubkey;
 
 
+pub const MAX_PAYMENT_MINTS: usize = 8;
+
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
 pub struct VireAccount{
@@ -9,11 +11,22 @@ pub struct VireAccount{
     pub uni_number: [u8; 8],
     pub transaction_fee_uni: [u8; 8],
     pub transaction_fee_student: [u8; 8],
+    pub payment_mint_count: u8,
+    pub payment_mints: [Pubkey; MAX_PAYMENT_MINTS], // protocol-approved tuition mints (first payment_mint_count are used)
     pub vire_bump: u8,
 }
 
 impl VireAccount {
     pub const LEN: usize = core::mem::size_of::<VireAccount>();
+
+    // Protocol's share of `amount` paid by a student
+    pub fn student_fee(&self, amount: u64) -> u64 {
+        amount / 100 * u64::from_le_bytes(self.transaction_fee_student)
+    }
+
+    pub fn is_payment_mint_allowed(&self, mint: &Pubkey) -> bool {
+        self.payment_mints[..self.payment_mint_count as usize].contains(mint)
+    }
 }
 
-// seeds = [b"vire", admin.key().as_ref()]
\ No newline at end of file
+// seeds = [b"vire", admin.key().as_ref()]
@@ -0,0 +1,47 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::pubkey::Pubkey;
+
+
+pub const MAX_WAITLIST: usize = 32;
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct WaitlistAccount {
+    pub subject_key: Pubkey,
+    pub offer_seconds: [u8; 8], // how long the next wallet has to enrol once a spot is offered
+    pub head: u8,
+    pub len: u8,
+    pub entries: [Pubkey; MAX_WAITLIST], // ring buffer, entries[head] is next in line
+    pub waitlist_bump: u8,
+}
+
+impl WaitlistAccount {
+    pub const LEN: usize = core::mem::size_of::<WaitlistAccount>();
+
+    pub fn contains(&self, wallet: &Pubkey) -> bool {
+        (0..self.len as usize).any(|i| self.entries[(self.head as usize + i) % MAX_WAITLIST] == *wallet)
+    }
+
+    // false if the queue is full
+    pub fn push(&mut self, wallet: Pubkey) -> bool {
+        if self.len as usize == MAX_WAITLIST {
+            return false;
+        }
+        self.entries[(self.head as usize + self.len as usize) % MAX_WAITLIST] = wallet;
+        self.len += 1;
+        true
+    }
+
+    pub fn pop(&mut self) -> Option<Pubkey> {
+        if self.len == 0 {
+            return None;
+        }
+        let wallet = self.entries[self.head as usize];
+        self.entries[self.head as usize] = [0; 32];
+        self.head = ((self.head as usize + 1) % MAX_WAITLIST) as u8;
+        self.len -= 1;
+        Some(wallet)
+    }
+}
+
+// seeds = [b"waitlist", subject_account.key().as_ref()]
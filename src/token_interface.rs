@@ -0,0 +1,682 @@
+use pinocchio::{
+    account_info::{AccountInfo, Ref},
+    cpi::invoke_signed,
+    instruction::{AccountMeta, Instruction, Signer},
+    program_error::ProgramError,
+    pubkey::{self, Pubkey},
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use pinocchio_token::{instructions::AuthorityType, state::Mint};
+
+use crate::errors::VireError;
+
+
+// Token and Token-2022 share the instruction layouts used here, so every CPI goes to
+// the program passed in `token_program` (which has to own the mint).
+
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pinocchio_pubkey::pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = pinocchio_pubkey::pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+// Token-2022 mints are padded to the token account size, followed by the account type and the TLV extensions
+const ACCOUNT_TYPE_OFFSET: usize = 165;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const EXTENSION_TRANSFER_FEE_CONFIG: u16 = 1;
+// Mint with the (empty) NonTransferable extension: base account, account type and TLV header
+const NON_TRANSFERABLE_MINT_LEN: usize = ACCOUNT_TYPE_OFFSET + 1 + 4;
+const ONE_IN_BASIS_POINTS: u128 = 10_000;
+
+
+pub fn is_token_program(program_id: &Pubkey) -> bool {
+    *program_id == pinocchio_token::ID || *program_id == TOKEN_2022_PROGRAM_ID
+}
+
+// Checks that `token_program` is Token or Token-2022 and owns `mint`
+pub fn check_token_program(mint: &AccountInfo, token_program: &AccountInfo) -> ProgramResult {
+    if !is_token_program(token_program.key()) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if !mint.is_owned_by(token_program.key()) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    Ok(())
+}
+
+// Token account layout: mint, owner, ...; returns the owner of a `token_program` token account
+pub fn token_account_owner(account: &AccountInfo, token_program: &AccountInfo) -> Result<Pubkey, ProgramError> {
+    if !account.is_owned_by(token_program.key()) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let data = account.try_borrow_data()?;
+    if data.len() < 64 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let mut owner = Pubkey::default();
+    owner.copy_from_slice(&data[32..64]);
+    Ok(owner)
+}
+
+// Checks that `account` is `owner`'s associated token account for `mint`
+pub fn is_associated_token_account(account: &AccountInfo, owner: &Pubkey, mint: &AccountInfo, token_program: &AccountInfo) -> bool {
+    let seeds: &[&[u8]] = &[owner.as_ref(), token_program.key().as_ref(), mint.key().as_ref()];
+    pubkey::try_find_program_address(seeds, &ASSOCIATED_TOKEN_PROGRAM_ID).is_some_and(|(address, _)| address == *account.key())
+}
+
+// Like Mint::from_account_info, but also accepts Token-2022 mints (with or without extensions)
+pub fn load_mint(mint: &AccountInfo) -> Result<Ref<'_, Mint>, ProgramError> {
+    if mint.is_owned_by(&pinocchio_token::ID) {
+        return Mint::from_account_info(mint);
+    }
+
+    if !mint.is_owned_by(&TOKEN_2022_PROGRAM_ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let data = mint.try_borrow_data()?;
+    let valid_len = data.len() == Mint::LEN
+        || (data.len() > ACCOUNT_TYPE_OFFSET && data[ACCOUNT_TYPE_OFFSET] == ACCOUNT_TYPE_MINT);
+    if !valid_len {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(Ref::map(data, |data| unsafe { Mint::from_bytes(&data[..Mint::LEN]) }))
+}
+
+// Returns the value of a Token-2022 mint extension
+pub fn mint_extension(data: &[u8], extension_type: u16) -> Option<&[u8]> {
+    let mut offset = ACCOUNT_TYPE_OFFSET + 1;
+
+    while offset + 4 <= data.len() {
+        let ty = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let len = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let value = data.get(offset + 4..offset + 4 + len)?;
+
+        if ty == extension_type {
+            return Some(value);
+        }
+        // Uninitialized marks the end of the extensions
+        if ty == 0 {
+            return None;
+        }
+        offset += 4 + len;
+    }
+
+    None
+}
+
+
+// <---Credential NFT Mints (collection_mint / card_mint)--->
+
+// How the 0-decimal NFT mint of an instruction is provided
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MintMode {
+    // Created by the frontend (InitializeMint2 with the PDA as authority), only checked here
+    Frontend,
+    // Created and initialised by the program, the mint has to sign as a new account
+    OnChain,
+}
+
+impl TryFrom<u8> for MintMode {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Frontend),
+            1 => Ok(Self::OnChain),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+// A frontend-created NFT mint must be unused, 0-decimal and fully controlled by `authority`
+pub fn check_nft_mint(mint: &AccountInfo, authority: &Pubkey) -> ProgramResult {
+    let mint_data = load_mint(mint)?;
+
+    if !mint_data.is_initialized() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    if mint_data.mint_authority() != Some(authority) || mint_data.freeze_authority() != Some(authority) {
+        return Err(VireError::InvalidMintAuthority.into());
+    }
+
+    if mint_data.decimals() != 0 {
+        return Err(VireError::InvalidMintDecimals.into());
+    }
+
+    if mint_data.supply() != 0 {
+        return Err(VireError::MintSupplyNotZero.into());
+    }
+
+    Ok(())
+}
+
+pub struct NftMintAccounts<'a> {
+    pub payer: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub owner: &'a AccountInfo,
+    pub owner_ata: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub associated_token_program: &'a AccountInfo,
+}
+
+// Creates (MintMode::OnChain) or checks (MintMode::Frontend) the NFT mint with `authority` as mint and freeze authority
+pub fn init_or_check_nft_mint(mode: MintMode, accounts: &NftMintAccounts, authority: &Pubkey) -> ProgramResult {
+    if mode == MintMode::Frontend {
+        check_token_program(accounts.mint, accounts.token_program)?;
+        return check_nft_mint(accounts.mint, authority);
+    }
+
+    if !is_token_program(accounts.token_program.key()) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if accounts.associated_token_program.key() != &ASSOCIATED_TOKEN_PROGRAM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    CreateAccount{
+        from: accounts.payer,
+        to: accounts.mint,
+        space: Mint::LEN as u64,
+        owner: accounts.token_program.key(),
+        lamports: Rent::get()?.minimum_balance(Mint::LEN),
+    }
+    .invoke()?;
+
+    InitializeMint2{
+        mint: accounts.mint,
+        decimals: 0,
+        mint_authority: authority,
+        freeze_authority: Some(authority),
+        token_program: accounts.token_program,
+    }
+    .invoke()?;
+
+    CreateAssociatedTokenAccount{
+        payer: accounts.payer,
+        ata: accounts.owner_ata,
+        owner: accounts.owner,
+        mint: accounts.mint,
+        system_program: accounts.system_program,
+        token_program: accounts.token_program,
+        associated_token_program: accounts.associated_token_program,
+    }
+    .invoke()
+}
+
+
+// Creates a soulbound Token-2022 NFT mint (NonTransferable extension) with `authority` as mint and freeze authority,
+// then the owner's ATA (which Token-2022 makes non-transferable as well)
+pub fn init_non_transferable_nft_mint(accounts: &NftMintAccounts, authority: &Pubkey) -> ProgramResult {
+    if accounts.token_program.key() != &TOKEN_2022_PROGRAM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if accounts.associated_token_program.key() != &ASSOCIATED_TOKEN_PROGRAM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    CreateAccount{
+        from: accounts.payer,
+        to: accounts.mint,
+        space: NON_TRANSFERABLE_MINT_LEN as u64,
+        owner: accounts.token_program.key(),
+        lamports: Rent::get()?.minimum_balance(NON_TRANSFERABLE_MINT_LEN),
+    }
+    .invoke()?;
+
+    InitializeNonTransferableMint{
+        mint: accounts.mint,
+        token_program: accounts.token_program,
+    }
+    .invoke()?;
+
+    InitializeMint2{
+        mint: accounts.mint,
+        decimals: 0,
+        mint_authority: authority,
+        freeze_authority: Some(authority),
+        token_program: accounts.token_program,
+    }
+    .invoke()?;
+
+    CreateAssociatedTokenAccount{
+        payer: accounts.payer,
+        ata: accounts.owner_ata,
+        owner: accounts.owner,
+        mint: accounts.mint,
+        system_program: accounts.system_program,
+        token_program: accounts.token_program,
+        associated_token_program: accounts.associated_token_program,
+    }
+    .invoke()
+}
+
+
+// <---Transfer Fees (Token-2022 `TransferFeeConfig` extension)--->
+
+#[derive(Clone, Copy)]
+pub struct TransferFee {
+    pub epoch: u64,
+    pub maximum_fee: u64,
+    pub basis_points: u16,
+}
+
+impl TransferFee {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            epoch: u64::from_le_bytes(bytes[0..8].try_into().unwrap()),
+            maximum_fee: u64::from_le_bytes(bytes[8..16].try_into().unwrap()),
+            basis_points: u16::from_le_bytes(bytes[16..18].try_into().unwrap()),
+        }
+    }
+
+    // Fee withheld from a transfer of `amount` (same rounding as Token-2022)
+    pub fn calculate_fee(&self, amount: u64) -> Option<u64> {
+        if self.basis_points == 0 || amount == 0 {
+            return Some(0);
+        }
+        let numerator = (amount as u128).checked_mul(self.basis_points as u128)?;
+        let raw_fee = numerator.div_ceil(ONE_IN_BASIS_POINTS);
+        u64::try_from(raw_fee.min(self.maximum_fee as u128)).ok()
+    }
+
+    // Amount that has to be sent so the recipient ends up with `post_fee_amount`
+    pub fn calculate_pre_fee_amount(&self, post_fee_amount: u64) -> Option<u64> {
+        let basis_points = self.basis_points as u128;
+        match (basis_points, post_fee_amount) {
+            (0, _) => Some(post_fee_amount),
+            (_, 0) => Some(0),
+            (ONE_IN_BASIS_POINTS, _) => post_fee_amount.checked_add(self.maximum_fee),
+            _ => {
+                let numerator = (post_fee_amount as u128).checked_mul(ONE_IN_BASIS_POINTS)?;
+                let denominator = ONE_IN_BASIS_POINTS.checked_sub(basis_points)?;
+                let raw_pre_fee_amount = numerator.div_ceil(denominator);
+
+                if raw_pre_fee_amount - post_fee_amount as u128 >= self.maximum_fee as u128 {
+                    post_fee_amount.checked_add(self.maximum_fee)
+                } else {
+                    u64::try_from(raw_pre_fee_amount).ok()
+                }
+            }
+        }
+    }
+}
+
+// Transfer fee of the mint for the current epoch (None for legacy mints and mints without fees)
+pub fn current_transfer_fee(mint: &AccountInfo) -> Result<Option<TransferFee>, ProgramError> {
+    if !mint.is_owned_by(&TOKEN_2022_PROGRAM_ID) {
+        return Ok(None);
+    }
+
+    let data = mint.try_borrow_data()?;
+    // transfer_fee_config_authority, withdraw_withheld_authority, withheld_amount, older and newer fee
+    let Some(config) = mint_extension(&data, EXTENSION_TRANSFER_FEE_CONFIG) else {
+        return Ok(None);
+    };
+    if config.len() < 108 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let older = TransferFee::from_bytes(&config[72..90]);
+    let newer = TransferFee::from_bytes(&config[90..108]);
+    let epoch = Clock::get()?.epoch;
+
+    Ok(Some(if epoch >= newer.epoch { newer } else { older }))
+}
+
+// Amount to transfer so the recipient receives exactly `net_amount` after transfer fees
+pub fn gross_amount(mint: &AccountInfo, net_amount: u64) -> Result<u64, ProgramError> {
+    match current_transfer_fee(mint)? {
+        Some(fee) => fee.calculate_pre_fee_amount(net_amount).ok_or(ProgramError::ArithmeticOverflow),
+        None => Ok(net_amount),
+    }
+}
+
+
+// <---CPIs--->
+
+pub struct TransferChecked<'a> {
+    pub from: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub to: &'a AccountInfo,
+    pub authority: &'a AccountInfo,
+    pub amount: u64,
+    pub decimals: u8,
+    pub token_program: &'a AccountInfo,
+}
+
+impl TransferChecked<'_> {
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let mut data = [0u8; 10];
+        data[0] = 12;
+        data[1..9].copy_from_slice(&self.amount.to_le_bytes());
+        data[9] = self.decimals;
+
+        invoke_signed(
+            &Instruction {
+                program_id: self.token_program.key(),
+                accounts: &[
+                    AccountMeta::writable(self.from.key()),
+                    AccountMeta::readonly(self.mint.key()),
+                    AccountMeta::writable(self.to.key()),
+                    AccountMeta::readonly_signer(self.authority.key()),
+                ],
+                data: &data,
+            },
+            &[self.from, self.mint, self.to, self.authority],
+            signers,
+        )
+    }
+}
+
+pub struct MintToChecked<'a> {
+    pub mint: &'a AccountInfo,
+    pub account: &'a AccountInfo,
+    pub mint_authority: &'a AccountInfo,
+    pub amount: u64,
+    pub decimals: u8,
+    pub token_program: &'a AccountInfo,
+}
+
+impl MintToChecked<'_> {
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let mut data = [0u8; 10];
+        data[0] = 14;
+        data[1..9].copy_from_slice(&self.amount.to_le_bytes());
+        data[9] = self.decimals;
+
+        invoke_signed(
+            &Instruction {
+                program_id: self.token_program.key(),
+                accounts: &[
+                    AccountMeta::writable(self.mint.key()),
+                    AccountMeta::writable(self.account.key()),
+                    AccountMeta::readonly_signer(self.mint_authority.key()),
+                ],
+                data: &data,
+            },
+            &[self.mint, self.account, self.mint_authority],
+            signers,
+        )
+    }
+}
+
+pub struct BurnChecked<'a> {
+    pub account: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub authority: &'a AccountInfo,
+    pub amount: u64,
+    pub decimals: u8,
+    pub token_program: &'a AccountInfo,
+}
+
+impl BurnChecked<'_> {
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let mut data = [0u8; 10];
+        data[0] = 15;
+        data[1..9].copy_from_slice(&self.amount.to_le_bytes());
+        data[9] = self.decimals;
+
+        invoke_signed(
+            &Instruction {
+                program_id: self.token_program.key(),
+                accounts: &[
+                    AccountMeta::writable(self.account.key()),
+                    AccountMeta::writable(self.mint.key()),
+                    AccountMeta::readonly_signer(self.authority.key()),
+                ],
+                data: &data,
+            },
+            &[self.account, self.mint, self.authority],
+            signers,
+        )
+    }
+}
+
+pub struct Approve<'a> {
+    pub account: &'a AccountInfo,
+    pub delegate: &'a AccountInfo,
+    pub owner: &'a AccountInfo,
+    pub amount: u64,
+    pub token_program: &'a AccountInfo,
+}
+
+impl Approve<'_> {
+    pub fn invoke(&self) -> ProgramResult {
+        let mut data = [0u8; 9];
+        data[0] = 4;
+        data[1..9].copy_from_slice(&self.amount.to_le_bytes());
+
+        invoke_signed(
+            &Instruction {
+                program_id: self.token_program.key(),
+                accounts: &[
+                    AccountMeta::writable(self.account.key()),
+                    AccountMeta::readonly(self.delegate.key()),
+                    AccountMeta::readonly_signer(self.owner.key()),
+                ],
+                data: &data,
+            },
+            &[self.account, self.delegate, self.owner],
+            &[],
+        )
+    }
+}
+
+pub struct Revoke<'a> {
+    pub account: &'a AccountInfo,
+    pub owner: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl Revoke<'_> {
+    pub fn invoke(&self) -> ProgramResult {
+        invoke_signed(
+            &Instruction {
+                program_id: self.token_program.key(),
+                accounts: &[
+                    AccountMeta::writable(self.account.key()),
+                    AccountMeta::readonly_signer(self.owner.key()),
+                ],
+                data: &[5],
+            },
+            &[self.account, self.owner],
+            &[],
+        )
+    }
+}
+
+pub struct FreezeAccount<'a> {
+    pub account: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub freeze_authority: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl FreezeAccount<'_> {
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        invoke_signed(
+            &Instruction {
+                program_id: self.token_program.key(),
+                accounts: &[
+                    AccountMeta::writable(self.account.key()),
+                    AccountMeta::readonly(self.mint.key()),
+                    AccountMeta::readonly_signer(self.freeze_authority.key()),
+                ],
+                data: &[10],
+            },
+            &[self.account, self.mint, self.freeze_authority],
+            signers,
+        )
+    }
+}
+
+pub struct ThawAccount<'a> {
+    pub account: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub freeze_authority: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl ThawAccount<'_> {
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        invoke_signed(
+            &Instruction {
+                program_id: self.token_program.key(),
+                accounts: &[
+                    AccountMeta::writable(self.account.key()),
+                    AccountMeta::readonly(self.mint.key()),
+                    AccountMeta::readonly_signer(self.freeze_authority.key()),
+                ],
+                data: &[11],
+            },
+            &[self.account, self.mint, self.freeze_authority],
+            signers,
+        )
+    }
+}
+
+pub struct SetAuthority<'a> {
+    pub account: &'a AccountInfo,
+    pub authority: &'a AccountInfo,
+    pub authority_type: AuthorityType,
+    pub new_authority: Option<&'a Pubkey>,
+    pub token_program: &'a AccountInfo,
+}
+
+impl SetAuthority<'_> {
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let mut data = [0u8; 35];
+        data[0] = 6;
+        data[1] = self.authority_type as u8;
+        let len = match self.new_authority {
+            Some(new_authority) => {
+                data[2] = 1;
+                data[3..].copy_from_slice(new_authority);
+                35
+            }
+            None => 3,
+        };
+
+        invoke_signed(
+            &Instruction {
+                program_id: self.token_program.key(),
+                accounts: &[
+                    AccountMeta::writable(self.account.key()),
+                    AccountMeta::readonly_signer(self.authority.key()),
+                ],
+                data: &data[..len],
+            },
+            &[self.account, self.authority],
+            signers,
+        )
+    }
+}
+
+pub struct InitializeMint2<'a> {
+    pub mint: &'a AccountInfo,
+    pub decimals: u8,
+    pub mint_authority: &'a Pubkey,
+    pub freeze_authority: Option<&'a Pubkey>,
+    pub token_program: &'a AccountInfo,
+}
+
+impl InitializeMint2<'_> {
+    pub fn invoke(&self) -> ProgramResult {
+        let mut data = [0u8; 67];
+        data[0] = 20;
+        data[1] = self.decimals;
+        data[2..34].copy_from_slice(self.mint_authority);
+        let len = match self.freeze_authority {
+            Some(freeze_authority) => {
+                data[34] = 1;
+                data[35..].copy_from_slice(freeze_authority);
+                67
+            }
+            None => 35,
+        };
+
+        invoke_signed(
+            &Instruction {
+                program_id: self.token_program.key(),
+                accounts: &[AccountMeta::writable(self.mint.key())],
+                data: &data[..len],
+            },
+            &[self.mint],
+            &[],
+        )
+    }
+}
+
+// Token-2022 only, has to run before InitializeMint2
+pub struct InitializeNonTransferableMint<'a> {
+    pub mint: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl InitializeNonTransferableMint<'_> {
+    pub fn invoke(&self) -> ProgramResult {
+        invoke_signed(
+            &Instruction {
+                program_id: self.token_program.key(),
+                accounts: &[AccountMeta::writable(self.mint.key())],
+                data: &[32],
+            },
+            &[self.mint],
+            &[],
+        )
+    }
+}
+
+// Associated token account program `CreateIdempotent`
+pub struct CreateAssociatedTokenAccount<'a> {
+    pub payer: &'a AccountInfo,
+    pub ata: &'a AccountInfo,
+    pub owner: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub associated_token_program: &'a AccountInfo,
+}
+
+impl CreateAssociatedTokenAccount<'_> {
+    pub fn invoke(&self) -> ProgramResult {
+        invoke_signed(
+            &Instruction {
+                program_id: self.associated_token_program.key(),
+                accounts: &[
+                    AccountMeta::writable_signer(self.payer.key()),
+                    AccountMeta::writable(self.ata.key()),
+                    AccountMeta::readonly(self.owner.key()),
+                    AccountMeta::readonly(self.mint.key()),
+                    AccountMeta::readonly(self.system_program.key()),
+                    AccountMeta::readonly(self.token_program.key()),
+                ],
+                data: &[1],
+            },
+            &[self.payer, self.ata, self.owner, self.mint, self.system_program, self.token_program],
+            &[],
+        )
+    }
+}
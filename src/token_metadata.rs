@@ -0,0 +1,509 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::invoke_signed,
+    instruction::{AccountMeta, Instruction, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+
+use crate::{
+    errors::VireError,
+    token_interface::{CreateAssociatedTokenAccount, InitializeMint2, NftMintAccounts, ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID},
+};
+
+
+// Metaplex Token Metadata CPIs (borsh-encoded by hand, the program is not a dependency).
+// A local validator can load the program fixture at TOKEN_METADATA_PROGRAM_ID.
+// MetadataMode::TokenMetadata uses the Token-2022 metadata extensions instead (no external program).
+
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey = pinocchio_pubkey::pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+pub const MAX_NAME_LENGTH: usize = 32;
+pub const MAX_SYMBOL_LENGTH: usize = 10;
+pub const MAX_URI_LENGTH: usize = 200;
+
+// Largest CreateMetadataAccountV3 payload written here (full name/symbol/uri, collection and collection details)
+const MAX_METADATA_DATA_LEN: usize = 1 + (4 + MAX_NAME_LENGTH) + (4 + MAX_SYMBOL_LENGTH) + (4 + MAX_URI_LENGTH) + 2 + 1 + 34 + 1 + 1 + 10;
+
+
+// How an instruction attaches metadata to its NFT mint
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MetadataMode {
+    // No metadata (the frontend can add it later)
+    None,
+    // Metaplex metadata account, plus a master edition for collections
+    Metaplex,
+    // Token-2022 metadata-pointer and token-metadata extensions on the mint itself (mint created on-chain)
+    TokenMetadata,
+}
+
+impl TryFrom<u8> for MetadataMode {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Metaplex),
+            2 => Ok(Self::TokenMetadata),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+// Fixed-size name/symbol/uri, zero padded (Metaplex maximum lengths)
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct MetadataArgs {
+    name: [u8; MAX_NAME_LENGTH],
+    symbol: [u8; MAX_SYMBOL_LENGTH],
+    uri: [u8; MAX_URI_LENGTH],
+}
+
+impl MetadataArgs {
+    // Longer values are cut to the Metaplex maximum lengths
+    pub fn new(name: &str, symbol: &str, uri: &str) -> Self {
+        let mut args = Self::zeroed();
+        copy_padded(&mut args.name, name.as_bytes());
+        copy_padded(&mut args.symbol, symbol.as_bytes());
+        copy_padded(&mut args.uri, uri.as_bytes());
+        args
+    }
+
+    pub fn name(&self) -> &[u8] {
+        trim_padding(&self.name)
+    }
+
+    pub fn symbol(&self) -> &[u8] {
+        trim_padding(&self.symbol)
+    }
+
+    pub fn uri(&self) -> &[u8] {
+        trim_padding(&self.uri)
+    }
+}
+
+fn copy_padded(dst: &mut [u8], src: &[u8]) {
+    let len = src.len().min(dst.len());
+    dst[..len].copy_from_slice(&src[..len]);
+}
+
+fn trim_padding(value: &[u8]) -> &[u8] {
+    let len = value.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+    &value[..len]
+}
+
+pub fn check_token_metadata_program(program: &AccountInfo) -> ProgramResult {
+    if program.key() != &TOKEN_METADATA_PROGRAM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+
+// Minimal borsh writer over a stack buffer
+struct BorshWriter<'a> {
+    data: &'a mut [u8],
+    len: usize,
+}
+
+impl BorshWriter<'_> {
+    fn bytes(&mut self, value: &[u8]) {
+        self.data[self.len..self.len + value.len()].copy_from_slice(value);
+        self.len += value.len();
+    }
+
+    fn string(&mut self, value: &[u8]) {
+        self.bytes(&(value.len() as u32).to_le_bytes());
+        self.bytes(value);
+    }
+}
+
+
+// <---Token Metadata Instructions--->
+
+pub struct CreateMetadataAccountV3<'a> {
+    pub metadata: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub mint_authority: &'a AccountInfo,
+    pub payer: &'a AccountInfo,
+    pub update_authority: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub token_metadata_program: &'a AccountInfo,
+    pub args: &'a MetadataArgs,
+    // Unverified collection the NFT claims to belong to
+    pub collection: Option<&'a Pubkey>,
+    // Some(0) marks the NFT as a sized collection
+    pub collection_size: Option<u64>,
+}
+
+impl CreateMetadataAccountV3<'_> {
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let mut data = [0u8; MAX_METADATA_DATA_LEN];
+        let mut writer = BorshWriter { data: &mut data, len: 0 };
+
+        writer.bytes(&[33]);
+        // DataV2
+        writer.string(self.args.name());
+        writer.string(self.args.symbol());
+        writer.string(self.args.uri());
+        writer.bytes(&0u16.to_le_bytes()); // seller_fee_basis_points
+        writer.bytes(&[0]); // creators: None
+        match self.collection {
+            Some(collection) => {
+                writer.bytes(&[1, 0]); // Some(Collection { verified: false, .. })
+                writer.bytes(collection);
+            }
+            None => writer.bytes(&[0]),
+        }
+        writer.bytes(&[0]); // uses: None
+        writer.bytes(&[1]); // is_mutable
+        match self.collection_size {
+            Some(size) => {
+                writer.bytes(&[1, 0]); // Some(CollectionDetails::V1 { .. })
+                writer.bytes(&size.to_le_bytes());
+            }
+            None => writer.bytes(&[0]),
+        }
+        let len = writer.len;
+
+        invoke_signed(
+            &Instruction {
+                program_id: self.token_metadata_program.key(),
+                accounts: &[
+                    AccountMeta::writable(self.metadata.key()),
+                    AccountMeta::readonly(self.mint.key()),
+                    AccountMeta::readonly_signer(self.mint_authority.key()),
+                    AccountMeta::writable_signer(self.payer.key()),
+                    AccountMeta::readonly_signer(self.update_authority.key()),
+                    AccountMeta::readonly(self.system_program.key()),
+                ],
+                data: &data[..len],
+            },
+            &[self.metadata, self.mint, self.mint_authority, self.payer, self.update_authority, self.system_program],
+            signers,
+        )
+    }
+}
+
+// Turns a minted collection NFT into a master edition (supply stays 1), taking over its mint and freeze authority
+pub struct CreateMasterEditionV3<'a> {
+    pub edition: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub update_authority: &'a AccountInfo,
+    pub mint_authority: &'a AccountInfo,
+    pub payer: &'a AccountInfo,
+    pub metadata: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub token_metadata_program: &'a AccountInfo,
+}
+
+impl CreateMasterEditionV3<'_> {
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        // max_supply: Some(0)
+        let mut data = [0u8; 10];
+        data[0] = 17;
+        data[1] = 1;
+
+        invoke_signed(
+            &Instruction {
+                program_id: self.token_metadata_program.key(),
+                accounts: &[
+                    AccountMeta::writable(self.edition.key()),
+                    AccountMeta::writable(self.mint.key()),
+                    AccountMeta::readonly_signer(self.update_authority.key()),
+                    AccountMeta::readonly_signer(self.mint_authority.key()),
+                    AccountMeta::writable_signer(self.payer.key()),
+                    AccountMeta::writable(self.metadata.key()),
+                    AccountMeta::readonly(self.token_program.key()),
+                    AccountMeta::readonly(self.system_program.key()),
+                ],
+                data: &data,
+            },
+            &[
+                self.edition,
+                self.mint,
+                self.update_authority,
+                self.mint_authority,
+                self.payer,
+                self.metadata,
+                self.token_program,
+                self.system_program,
+            ],
+            signers,
+        )
+    }
+}
+
+// Verifies an NFT as a member of a sized collection and bumps the collection size
+pub struct VerifySizedCollectionItem<'a> {
+    pub metadata: &'a AccountInfo,
+    pub collection_authority: &'a AccountInfo,
+    pub payer: &'a AccountInfo,
+    pub collection_mint: &'a AccountInfo,
+    pub collection_metadata: &'a AccountInfo,
+    pub collection_master_edition: &'a AccountInfo,
+    pub token_metadata_program: &'a AccountInfo,
+}
+
+impl VerifySizedCollectionItem<'_> {
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        invoke_signed(
+            &Instruction {
+                program_id: self.token_metadata_program.key(),
+                accounts: &[
+                    AccountMeta::writable(self.metadata.key()),
+                    AccountMeta::readonly_signer(self.collection_authority.key()),
+                    AccountMeta::writable_signer(self.payer.key()),
+                    AccountMeta::readonly(self.collection_mint.key()),
+                    AccountMeta::writable(self.collection_metadata.key()),
+                    AccountMeta::readonly(self.collection_master_edition.key()),
+                ],
+                data: &[30],
+            },
+            &[
+                self.metadata,
+                self.collection_authority,
+                self.payer,
+                self.collection_mint,
+                self.collection_metadata,
+                self.collection_master_edition,
+            ],
+            signers,
+        )
+    }
+}
+
+
+// <---Token-2022 Metadata Extensions--->
+
+// Mint with the MetadataPointer extension (base account, account type, TLV header + 2 pubkeys)
+const METADATA_POINTER_MINT_LEN: usize = 165 + 1 + 4 + 64;
+const TLV_HEADER_LEN: usize = 4;
+const METADATA_POINTER_EXTENSION: u8 = 39;
+// sha256("spl_token_metadata_interface:<name>")[..8]
+const INITIALIZE_TOKEN_METADATA: [u8; 8] = [210, 225, 30, 162, 88, 184, 77, 141];
+const UPDATE_TOKEN_METADATA_FIELD: [u8; 8] = [221, 233, 49, 45, 181, 202, 220, 200];
+// Field::Key(String)
+const FIELD_KEY: u8 = 3;
+const MAX_FIELD_LEN: usize = 32;
+
+// Additional key/value pair stored in the token-metadata extension
+pub struct MetadataField<'a> {
+    pub key: &'a str,
+    pub value: &'a [u8],
+}
+
+// Decimal string of a u64 (field values are stored as text)
+pub struct DecimalString {
+    buf: [u8; 20],
+    start: usize,
+}
+
+impl DecimalString {
+    pub fn new(mut value: u64) -> Self {
+        let mut buf = [0u8; 20];
+        let mut start = buf.len();
+        loop {
+            start -= 1;
+            buf[start] = b'0' + (value % 10) as u8;
+            value /= 10;
+            if value == 0 {
+                break;
+            }
+        }
+        Self { buf, start }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[self.start..]
+    }
+}
+
+// Size of the serialized TokenMetadata extension value
+fn token_metadata_len(args: &MetadataArgs, fields: &[MetadataField]) -> usize {
+    let base = 32 + 32 + (4 + args.name().len()) + (4 + args.symbol().len()) + (4 + args.uri().len()) + 4;
+    fields.iter().fold(base, |len, field| len + 4 + field.key.len() + 4 + field.value.len())
+}
+
+// Creates a Token-2022 NFT mint whose metadata lives on the mint: MetadataPointer to itself,
+// InitializeMint2 and the token-metadata extension with `fields`, then the owner's ATA.
+// `authority` (a PDA signing through `signers`) is mint, freeze, pointer and update authority.
+pub fn init_nft_mint_with_metadata(
+    accounts: &NftMintAccounts,
+    authority: &AccountInfo,
+    args: &MetadataArgs,
+    fields: &[MetadataField],
+    signers: &[Signer],
+) -> ProgramResult {
+    if accounts.token_program.key() != &TOKEN_2022_PROGRAM_ID {
+        return Err(VireError::InvalidMetadataMode.into());
+    }
+
+    if accounts.associated_token_program.key() != &ASSOCIATED_TOKEN_PROGRAM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if fields.iter().any(|field| field.key.len() > MAX_FIELD_LEN || field.value.len() > MAX_FIELD_LEN) {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Token-2022 reallocates the mint for the metadata, the rent for it is paid upfront
+    let full_len = METADATA_POINTER_MINT_LEN + TLV_HEADER_LEN + token_metadata_len(args, fields);
+    CreateAccount{
+        from: accounts.payer,
+        to: accounts.mint,
+        space: METADATA_POINTER_MINT_LEN as u64,
+        owner: accounts.token_program.key(),
+        lamports: Rent::get()?.minimum_balance(full_len),
+    }
+    .invoke()?;
+
+    InitializeMetadataPointer{
+        mint: accounts.mint,
+        authority: authority.key(),
+        token_program: accounts.token_program,
+    }
+    .invoke()?;
+
+    InitializeMint2{
+        mint: accounts.mint,
+        decimals: 0,
+        mint_authority: authority.key(),
+        freeze_authority: Some(authority.key()),
+        token_program: accounts.token_program,
+    }
+    .invoke()?;
+
+    InitializeTokenMetadata{
+        mint: accounts.mint,
+        authority,
+        args,
+        token_program: accounts.token_program,
+    }
+    .invoke_signed(signers)?;
+
+    for field in fields {
+        UpdateTokenMetadataField{
+            mint: accounts.mint,
+            update_authority: authority,
+            field,
+            token_program: accounts.token_program,
+        }
+        .invoke_signed(signers)?;
+    }
+
+    CreateAssociatedTokenAccount{
+        payer: accounts.payer,
+        ata: accounts.owner_ata,
+        owner: accounts.owner,
+        mint: accounts.mint,
+        system_program: accounts.system_program,
+        token_program: accounts.token_program,
+        associated_token_program: accounts.associated_token_program,
+    }
+    .invoke()
+}
+
+// MetadataPointerInstruction::Initialize, pointing at the mint itself
+pub struct InitializeMetadataPointer<'a> {
+    pub mint: &'a AccountInfo,
+    pub authority: &'a Pubkey,
+    pub token_program: &'a AccountInfo,
+}
+
+impl InitializeMetadataPointer<'_> {
+    pub fn invoke(&self) -> ProgramResult {
+        let mut data = [0u8; 66];
+        data[0] = METADATA_POINTER_EXTENSION;
+        data[1] = 0;
+        data[2..34].copy_from_slice(self.authority);
+        data[34..66].copy_from_slice(self.mint.key());
+
+        invoke_signed(
+            &Instruction {
+                program_id: self.token_program.key(),
+                accounts: &[AccountMeta::writable(self.mint.key())],
+                data: &data,
+            },
+            &[self.mint],
+            &[],
+        )
+    }
+}
+
+// spl-token-metadata-interface `Initialize` on a mint that is its own metadata account
+pub struct InitializeTokenMetadata<'a> {
+    pub mint: &'a AccountInfo,
+    // update authority and mint authority
+    pub authority: &'a AccountInfo,
+    pub args: &'a MetadataArgs,
+    pub token_program: &'a AccountInfo,
+}
+
+impl InitializeTokenMetadata<'_> {
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let mut data = [0u8; 8 + (4 + MAX_NAME_LENGTH) + (4 + MAX_SYMBOL_LENGTH) + (4 + MAX_URI_LENGTH)];
+        let mut writer = BorshWriter { data: &mut data, len: 0 };
+
+        writer.bytes(&INITIALIZE_TOKEN_METADATA);
+        writer.string(self.args.name());
+        writer.string(self.args.symbol());
+        writer.string(self.args.uri());
+        let len = writer.len;
+
+        invoke_signed(
+            &Instruction {
+                program_id: self.token_program.key(),
+                accounts: &[
+                    AccountMeta::writable(self.mint.key()),
+                    AccountMeta::readonly(self.authority.key()),
+                    AccountMeta::readonly(self.mint.key()),
+                    AccountMeta::readonly_signer(self.authority.key()),
+                ],
+                data: &data[..len],
+            },
+            &[self.mint, self.authority, self.mint, self.authority],
+            signers,
+        )
+    }
+}
+
+// spl-token-metadata-interface `UpdateField` with a custom key
+pub struct UpdateTokenMetadataField<'a> {
+    pub mint: &'a AccountInfo,
+    pub update_authority: &'a AccountInfo,
+    pub field: &'a MetadataField<'a>,
+    pub token_program: &'a AccountInfo,
+}
+
+impl UpdateTokenMetadataField<'_> {
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let mut data = [0u8; 8 + 1 + (4 + MAX_FIELD_LEN) + (4 + MAX_FIELD_LEN)];
+        let mut writer = BorshWriter { data: &mut data, len: 0 };
+
+        writer.bytes(&UPDATE_TOKEN_METADATA_FIELD);
+        writer.bytes(&[FIELD_KEY]);
+        writer.string(self.field.key.as_bytes());
+        writer.string(self.field.value);
+        let len = writer.len;
+
+        invoke_signed(
+            &Instruction {
+                program_id: self.token_program.key(),
+                accounts: &[
+                    AccountMeta::writable(self.mint.key()),
+                    AccountMeta::readonly_signer(self.update_authority.key()),
+                ],
+                data: &data[..len],
+            },
+            &[self.mint, self.update_authority],
+            signers,
+        )
+    }
+}
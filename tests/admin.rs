@@ -0,0 +1,193 @@
+mod common;
+
+use bytemuck::Zeroable;
+use common::{key, process, TestAccount};
+use escrow_bytemuck::{
+    errors::VireError,
+    instructions::{update_payment_mint::PaymentMintArgs, vire_instructions::VireInstruction},
+    token_interface,
+    uni_account::{UniAccount, UniStatus},
+    vire_account::{VireAccount, MAX_PAYMENT_MINTS},
+};
+use pinocchio::program_error::ProgramError;
+use pinocchio_token::state::Mint;
+
+const ADMIN: u8 = 1;
+const VIRE: u8 = 2;
+
+fn vire_account() -> VireAccount {
+    let mut vire = VireAccount::zeroed();
+    vire.admin_key = key(ADMIN);
+    vire
+}
+
+fn mint_account(seed: u8) -> TestAccount {
+    let mut data = vec![0; Mint::LEN];
+    data[45] = 1; // is_initialized
+    TestAccount::with_data(key(seed), pinocchio_token::ID, data)
+}
+
+
+// <---UpdatePaymentMint--->
+
+fn update_payment_mint(vire: &VireAccount, signer: u8, mint: TestAccount, allowed: bool) -> (Result<(), ProgramError>, VireAccount) {
+    let mut accounts = [TestAccount::signer(key(signer)), TestAccount::program_account(key(VIRE), vire), mint];
+    let result = process(&mut accounts, VireInstruction::UpdatePaymentMint, bytemuck::bytes_of(&PaymentMintArgs::new(allowed)));
+    (result, *accounts[1].state::<VireAccount>())
+}
+
+#[test]
+fn admin_adds_and_removes_payment_mints() {
+    let (result, vire) = update_payment_mint(&vire_account(), ADMIN, mint_account(10), true);
+    assert_eq!(result, Ok(()));
+    let (result, vire) = update_payment_mint(&vire, ADMIN, mint_account(11), true);
+    assert_eq!(result, Ok(()));
+    assert!(vire.is_payment_mint_allowed(&key(10)) && vire.is_payment_mint_allowed(&key(11)));
+
+    // adding twice is a no-op
+    let (result, vire) = update_payment_mint(&vire, ADMIN, mint_account(10), true);
+    assert_eq!(result, Ok(()));
+    assert_eq!(vire.payment_mint_count, 2);
+
+    let (result, vire) = update_payment_mint(&vire, ADMIN, mint_account(10), false);
+    assert_eq!(result, Ok(()));
+    assert_eq!(vire.payment_mint_count, 1);
+    assert!(!vire.is_payment_mint_allowed(&key(10)) && vire.is_payment_mint_allowed(&key(11)));
+}
+
+#[test]
+fn only_the_vire_admin_updates_payment_mints() {
+    let (result, vire) = update_payment_mint(&vire_account(), 3, mint_account(10), true);
+    assert_eq!(result, Err(VireError::Unauthorized.into()));
+    assert_eq!(vire.payment_mint_count, 0);
+
+    let mut accounts = [TestAccount::wallet(key(ADMIN)), TestAccount::program_account(key(VIRE), &vire_account()), mint_account(10)];
+    let result = process(&mut accounts, VireInstruction::UpdatePaymentMint, bytemuck::bytes_of(&PaymentMintArgs::new(true)));
+    assert_eq!(result, Err(ProgramError::MissingRequiredSignature));
+}
+
+#[test]
+fn payment_mints_have_to_be_token_mints() {
+    let not_a_mint = TestAccount::with_data(key(10), key(9), vec![0; Mint::LEN]);
+    let (result, vire) = update_payment_mint(&vire_account(), ADMIN, not_a_mint, true);
+    assert_eq!(result, Err(ProgramError::InvalidAccountOwner));
+    assert_eq!(vire.payment_mint_count, 0);
+}
+
+#[test]
+fn token_2022_mints_can_be_payment_mints() {
+    // Token-2022 mint with a (transfer fee config) extension after the account type
+    let mut data = vec![0; 166];
+    data[45] = 1;
+    data[165] = 1;
+    data.extend_from_slice(&[1, 0, 108, 0]);
+    data.extend_from_slice(&[0; 108]);
+    let mint = TestAccount::with_data(key(10), token_interface::TOKEN_2022_PROGRAM_ID, data);
+
+    let (result, vire) = update_payment_mint(&vire_account(), ADMIN, mint, true);
+    assert_eq!(result, Ok(()));
+    assert!(vire.is_payment_mint_allowed(&key(10)));
+
+    // ...as long as the account really is a mint
+    let mut token_account = vec![0; 166];
+    token_account[165] = 2;
+    let token_account = TestAccount::with_data(key(11), token_interface::TOKEN_2022_PROGRAM_ID, token_account);
+    let (result, _) = update_payment_mint(&vire, ADMIN, token_account, true);
+    assert_eq!(result, Err(ProgramError::InvalidAccountData));
+}
+
+#[test]
+fn payment_mint_allowlist_is_bounded() {
+    let mut vire = vire_account();
+    for seed in 0..MAX_PAYMENT_MINTS as u8 {
+        let (result, updated) = update_payment_mint(&vire, ADMIN, mint_account(10 + seed), true);
+        assert_eq!(result, Ok(()));
+        vire = updated;
+    }
+
+    let (result, _) = update_payment_mint(&vire, ADMIN, mint_account(100), true);
+    assert_eq!(result, Err(VireError::PaymentMintListFull.into()));
+}
+
+#[test]
+fn removing_an_unlisted_mint_fails() {
+    let (result, _) = update_payment_mint(&vire_account(), ADMIN, mint_account(10), false);
+    assert_eq!(result, Err(VireError::PaymentMintNotAllowed.into()));
+}
+
+
+// <---ApproveUni/SuspendUni/ReinstateUni--->
+
+const UNI: u8 = 3;
+
+fn uni_account(status: UniStatus) -> UniAccount {
+    let mut uni = UniAccount::zeroed();
+    uni.vire_key = key(VIRE);
+    uni.status = status as u8;
+    uni
+}
+
+fn change_uni_status(uni: &UniAccount, signer: u8, instruction: VireInstruction) -> (Result<(), ProgramError>, UniStatus) {
+    let mut accounts = [
+        TestAccount::signer(key(signer)),
+        TestAccount::program_account(key(VIRE), &vire_account()),
+        TestAccount::program_account(key(UNI), uni),
+    ];
+    let result = process(&mut accounts, instruction, &[]);
+    (result, accounts[2].state::<UniAccount>().status().unwrap())
+}
+
+#[test]
+fn universities_are_approved_suspended_and_reinstated() {
+    let (result, status) = change_uni_status(&uni_account(UniStatus::Pending), ADMIN, VireInstruction::ApproveUni);
+    assert_eq!(result, Ok(()));
+    assert_eq!(status, UniStatus::Active);
+
+    let (result, status) = change_uni_status(&uni_account(status), ADMIN, VireInstruction::SuspendUni);
+    assert_eq!(result, Ok(()));
+    assert_eq!(status, UniStatus::Suspended);
+
+    let (result, status) = change_uni_status(&uni_account(status), ADMIN, VireInstruction::ReinstateUni);
+    assert_eq!(result, Ok(()));
+    assert_eq!(status, UniStatus::Active);
+}
+
+#[test]
+fn uni_status_only_moves_from_the_expected_one() {
+    for (status, instruction) in [
+        (UniStatus::Active, VireInstruction::ApproveUni),
+        (UniStatus::Suspended, VireInstruction::ApproveUni),
+        (UniStatus::Pending, VireInstruction::SuspendUni),
+        (UniStatus::Suspended, VireInstruction::SuspendUni),
+        (UniStatus::Pending, VireInstruction::ReinstateUni),
+        (UniStatus::Active, VireInstruction::ReinstateUni),
+    ] {
+        let (result, unchanged) = change_uni_status(&uni_account(status), ADMIN, instruction);
+        assert_eq!(result, Err(VireError::InvalidUniStatus.into()));
+        assert_eq!(unchanged, status);
+    }
+}
+
+#[test]
+fn only_the_vire_admin_changes_uni_status() {
+    let (result, status) = change_uni_status(&uni_account(UniStatus::Pending), 4, VireInstruction::ApproveUni);
+    assert_eq!(result, Err(VireError::Unauthorized.into()));
+    assert_eq!(status, UniStatus::Pending);
+
+    // ...of universities registered under this Vire account
+    let mut other_vire = uni_account(UniStatus::Pending);
+    other_vire.vire_key = key(9);
+    let (result, _) = change_uni_status(&other_vire, ADMIN, VireInstruction::ApproveUni);
+    assert_eq!(result, Err(ProgramError::InvalidAccountData));
+}
+
+#[test]
+fn only_active_universities_operate() {
+    assert_eq!(uni_account(UniStatus::Active).check_active(), Ok(()));
+    assert_eq!(uni_account(UniStatus::Pending).check_active(), Err(VireError::UniPending.into()));
+    assert_eq!(uni_account(UniStatus::Suspended).check_active(), Err(VireError::UniSuspended.into()));
+
+    let mut unknown = uni_account(UniStatus::Active);
+    unknown.status = 3;
+    assert_eq!(unknown.check_active(), Err(ProgramError::InvalidAccountData));
+}
@@ -0,0 +1,108 @@
+#![allow(dead_code)]
+
+use std::mem::MaybeUninit;
+
+use bytemuck::Pod;
+use escrow_bytemuck::{instructions::vire_instructions::VireInstruction, process_instruction};
+use pinocchio::{account_info::AccountInfo, entrypoint, pubkey::Pubkey, ProgramResult};
+
+// Host harness for the program: accounts are serialized the way the runtime passes them to the entrypoint
+// and process_instruction runs on them.
+// Syscalls don't exist off-chain (PDA derivation panics, sysvars return an error and CPIs do nothing),
+// so only paths that finish before reaching one of them can be run here.
+
+const NON_DUP_MARKER: u8 = u8::MAX;
+const MAX_PERMITTED_DATA_INCREASE: usize = 10 * 1024;
+const MAX_ACCOUNTS: usize = 32;
+
+pub const SYSTEM_PROGRAM_ID: Pubkey = [0; 32];
+
+pub struct TestAccount {
+    pub key: Pubkey,
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl TestAccount {
+    // Wallet signing the transaction
+    pub fn signer(key: Pubkey) -> Self {
+        Self { key, owner: SYSTEM_PROGRAM_ID, lamports: 1_000_000_000, data: Vec::new(), is_signer: true, is_writable: true }
+    }
+
+    // Wallet that doesn't sign
+    pub fn wallet(key: Pubkey) -> Self {
+        Self { is_signer: false, ..Self::signer(key) }
+    }
+
+    // Account owned by `owner` holding `data`
+    pub fn with_data(key: Pubkey, owner: Pubkey, data: Vec<u8>) -> Self {
+        Self { key, owner, lamports: 1_000_000, data, is_signer: false, is_writable: true }
+    }
+
+    // Program account holding `state`
+    pub fn program_account<T: Pod>(key: Pubkey, state: &T) -> Self {
+        Self::with_data(key, escrow_bytemuck::ID, bytemuck::bytes_of(state).to_vec())
+    }
+
+    pub fn state<T: Pod>(&self) -> &T {
+        bytemuck::from_bytes(&self.data)
+    }
+}
+
+pub fn key(seed: u8) -> Pubkey {
+    [seed; 32]
+}
+
+fn serialize(accounts: &[TestAccount], instruction_data: &[u8]) -> Vec<u64> {
+    let mut input = Vec::new();
+    input.extend_from_slice(&(accounts.len() as u64).to_le_bytes());
+    for account in accounts {
+        input.extend_from_slice(&[NON_DUP_MARKER, account.is_signer as u8, account.is_writable as u8, 0, 0, 0, 0, 0]);
+        input.extend_from_slice(&account.key);
+        input.extend_from_slice(&account.owner);
+        input.extend_from_slice(&account.lamports.to_le_bytes());
+        input.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+        input.extend_from_slice(&account.data);
+        input.resize(input.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+        input.resize(input.len().next_multiple_of(8), 0);
+        input.extend_from_slice(&u64::MAX.to_le_bytes()); // rent epoch
+    }
+    input.extend_from_slice(&(instruction_data.len() as u64).to_le_bytes());
+    input.extend_from_slice(instruction_data);
+    input.extend_from_slice(&escrow_bytemuck::ID);
+
+    // the entrypoint expects an 8-byte aligned buffer
+    input.resize(input.len().next_multiple_of(8), 0);
+    input.chunks_exact(8).map(|word| u64::from_ne_bytes(word.try_into().unwrap())).collect()
+}
+
+// Runs `f` on the AccountInfos the entrypoint would get for `accounts` and writes the resulting lamports,
+// owners and data back into them
+pub fn with_account_infos<R>(accounts: &mut [TestAccount], instruction_data: &[u8], f: impl FnOnce(&Pubkey, &[AccountInfo], &[u8]) -> R) -> R {
+    let mut input = serialize(accounts, instruction_data);
+    let mut account_infos = [const { MaybeUninit::<AccountInfo>::uninit() }; MAX_ACCOUNTS];
+    let (program_id, count, instruction_data) =
+        unsafe { entrypoint::deserialize::<MAX_ACCOUNTS>(input.as_mut_ptr() as *mut u8, &mut account_infos) };
+    let account_infos = unsafe { core::slice::from_raw_parts(account_infos.as_ptr() as *const AccountInfo, count) };
+
+    let result = f(program_id, account_infos, instruction_data);
+
+    for (account, info) in accounts.iter_mut().zip(account_infos) {
+        account.lamports = info.lamports();
+        account.owner = unsafe { *info.owner() };
+        account.data = info.try_borrow_data().unwrap().to_vec();
+    }
+
+    result
+}
+
+// Runs `instruction` through process_instruction
+pub fn process(accounts: &mut [TestAccount], instruction: VireInstruction, args: &[u8]) -> ProgramResult {
+    let mut instruction_data = vec![instruction as u8];
+    instruction_data.extend_from_slice(args);
+
+    with_account_infos(accounts, &instruction_data, process_instruction)
+}
@@ -0,0 +1,185 @@
+mod common;
+
+use bytemuck::Zeroable;
+use common::{key, process, TestAccount};
+use escrow_bytemuck::{
+    errors::VireError,
+    instructions::{revoke_credential::RevokeArgs, verify_credential::CredentialState, vire_instructions::VireInstruction},
+    student_account::StudentAccount,
+    subject_account::SubjectAccount,
+    token_interface,
+    transcript_account::{SemesterRecord, TranscriptAccount},
+    uni_account::UniAccount,
+};
+use pinocchio::program_error::ProgramError;
+use pinocchio_token::state::Mint;
+
+const NOW: i64 = 1_700_000_000;
+
+
+// <---Credential mints--->
+
+const AUTHORITY: u8 = 2;
+
+// A legacy Token mint with `authority` as mint and freeze authority
+fn nft_mint(authority: u8, decimals: u8, supply: u64) -> TestAccount {
+    let mut data = vec![0; Mint::LEN];
+    data[0] = 1; // mint_authority is set
+    data[4..36].copy_from_slice(&key(authority));
+    data[36..44].copy_from_slice(&supply.to_le_bytes());
+    data[44] = decimals;
+    data[45] = 1; // is_initialized
+    data[46] = 1; // freeze_authority is set
+    data[50..82].copy_from_slice(&key(authority));
+    TestAccount::with_data(key(1), pinocchio_token::ID, data)
+}
+
+fn check_nft_mint(mint: TestAccount) -> Result<(), ProgramError> {
+    common::with_account_infos(&mut [mint], &[], |_, accounts, _| {
+        token_interface::check_nft_mint(&accounts[0], &key(AUTHORITY))
+    })
+}
+
+#[test]
+fn frontend_mints_are_fresh_nft_mints() {
+    assert_eq!(check_nft_mint(nft_mint(AUTHORITY, 0, 0)), Ok(()));
+}
+
+#[test]
+fn frontend_mints_belong_to_the_program() {
+    assert_eq!(check_nft_mint(nft_mint(3, 0, 0)), Err(VireError::InvalidMintAuthority.into()));
+
+    // the freeze authority as well
+    let mut mint = nft_mint(AUTHORITY, 0, 0);
+    mint.data[50..82].copy_from_slice(&key(3));
+    assert_eq!(check_nft_mint(mint), Err(VireError::InvalidMintAuthority.into()));
+
+    let mut mint = nft_mint(AUTHORITY, 0, 0);
+    mint.data[46] = 0;
+    assert_eq!(check_nft_mint(mint), Err(VireError::InvalidMintAuthority.into()));
+}
+
+#[test]
+fn frontend_mints_are_unminted_and_indivisible() {
+    assert_eq!(check_nft_mint(nft_mint(AUTHORITY, 6, 0)), Err(VireError::InvalidMintDecimals.into()));
+    assert_eq!(check_nft_mint(nft_mint(AUTHORITY, 0, 1)), Err(VireError::MintSupplyNotZero.into()));
+}
+
+
+// <---VerifyCredential--->
+
+fn enrolment(paid_semesters: u64, graduated_at: i64) -> StudentAccount {
+    let mut student = StudentAccount::zeroed();
+    student.semesters = (paid_semesters + 1).to_le_bytes();
+    student.graduated_at = graduated_at.to_le_bytes();
+    student
+}
+
+#[test]
+fn students_graduate_once_every_semester_is_paid() {
+    // `semesters` is the one being paid, the last one still has to be paid at semesters == max_semester
+    assert!(!enrolment(3, 0).graduated(4));
+    assert!(enrolment(4, 0).graduated(4));
+    assert_eq!(enrolment(4, 0).paid_semesters(), 4);
+    assert_eq!(StudentAccount::zeroed().paid_semesters(), 0);
+}
+
+#[test]
+fn graduates_verify_while_they_hold_the_diploma() {
+    assert_eq!(CredentialState::of(&enrolment(4, NOW), 4, true), CredentialState::Graduated);
+    assert_eq!(CredentialState::of(&enrolment(4, NOW), 4, false), CredentialState::Enrolled);
+
+    // unstaked and paid up are both needed
+    assert_eq!(CredentialState::of(&enrolment(4, 0), 4, true), CredentialState::Enrolled);
+    assert_eq!(CredentialState::of(&enrolment(3, NOW), 4, true), CredentialState::Enrolled);
+}
+
+#[test]
+fn revocation_and_expulsion_come_first() {
+    let mut revoked = enrolment(4, NOW);
+    revoked.revoked_at = NOW.to_le_bytes();
+    assert_eq!(CredentialState::of(&revoked, 4, true), CredentialState::Revoked);
+
+    let mut expelled = enrolment(2, 0);
+    expelled.expelled_at = NOW.to_le_bytes();
+    assert_eq!(CredentialState::of(&expelled, 4, false), CredentialState::Expelled);
+    assert_eq!(CredentialState::of(&enrolment(0, 0), 4, false), CredentialState::Enrolled);
+}
+
+
+// <---RevokeCredential--->
+
+fn revoke_accounts(signer: TestAccount) -> [TestAccount; 7] {
+    [
+        signer,
+        TestAccount::program_account(key(2), &UniAccount::zeroed()),
+        TestAccount::program_account(key(3), &SubjectAccount::zeroed()),
+        TestAccount::program_account(key(4), &StudentAccount::zeroed()),
+        TestAccount::with_data(key(5), token_interface::TOKEN_2022_PROGRAM_ID, Vec::new()),
+        TestAccount::with_data(key(6), token_interface::TOKEN_2022_PROGRAM_ID, Vec::new()),
+        TestAccount::wallet(token_interface::TOKEN_2022_PROGRAM_ID),
+    ]
+}
+
+#[test]
+fn revocations_need_a_reason() {
+    let mut accounts = revoke_accounts(TestAccount::signer(key(1)));
+    let result = process(&mut accounts, VireInstruction::RevokeCredential, bytemuck::bytes_of(&RevokeArgs::new(0)));
+    assert_eq!(result, Err(VireError::InvalidRevocationReason.into()));
+}
+
+#[test]
+fn revocations_are_signed() {
+    let mut accounts = revoke_accounts(TestAccount::wallet(key(1)));
+    let result = process(&mut accounts, VireInstruction::RevokeCredential, bytemuck::bytes_of(&RevokeArgs::new(1)));
+    assert_eq!(result, Err(ProgramError::MissingRequiredSignature));
+}
+
+
+// <---Transcripts--->
+
+fn record(semester: u64, grade: u64, credits: u64) -> SemesterRecord {
+    SemesterRecord {
+        semester: semester.to_le_bytes(),
+        grade: grade.to_le_bytes(),
+        credits: credits.to_le_bytes(),
+        ..SemesterRecord::zeroed()
+    }
+}
+
+fn transcript(records: &[SemesterRecord]) -> TranscriptAccount {
+    let mut transcript = TranscriptAccount::zeroed();
+    let (mut total_credits, mut weighted_grade_sum) = (0, 0);
+    for (index, record) in records.iter().enumerate() {
+        transcript.records[index] = *record;
+        total_credits += u64::from_le_bytes(record.credits);
+        weighted_grade_sum += u64::from_le_bytes(record.grade) * u64::from_le_bytes(record.credits);
+    }
+    transcript.record_count = records.len() as u8;
+    transcript.total_credits = u64::to_le_bytes(total_credits);
+    transcript.weighted_grade_sum = u64::to_le_bytes(weighted_grade_sum);
+    transcript
+}
+
+#[test]
+fn cumulative_grade_is_weighted_by_credits() {
+    assert_eq!(transcript(&[]).cumulative_grade(), 0);
+    assert_eq!(transcript(&[record(1, 300, 10), record(2, 400, 30)]).cumulative_grade(), 375);
+}
+
+#[test]
+fn semesters_are_recorded_in_order() {
+    assert_eq!(transcript(&[]).last_semester(), 0);
+    assert_eq!(transcript(&[record(1, 300, 10), record(3, 400, 30)]).last_semester(), 3);
+}
+
+#[test]
+fn carried_over_records_dont_count_towards_the_order() {
+    let mut transferred = transcript(&[record(1, 300, 10), record(2, 400, 30)]);
+    transferred.carried_over = 2;
+    assert_eq!(transferred.last_semester(), 0);
+
+    transferred.records[2] = record(1, 350, 10);
+    transferred.record_count = 3;
+    assert_eq!(transferred.last_semester(), 1);
+}
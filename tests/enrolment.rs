@@ -0,0 +1,574 @@
+mod common;
+
+use bytemuck::Zeroable;
+use common::{key, process, TestAccount, SYSTEM_PROGRAM_ID};
+use escrow_bytemuck::{
+    errors::VireError,
+    instructions::{create_waitlist::WaitlistArgs, initialize_student::{check_prerequisites, StudentArgs}, suspend_student::refund_sponsor, vire_instructions::VireInstruction},
+    student_account::{StudentAccount, MAX_LEAVES},
+    subject_account::{IntakeCohort, SubjectAccount, MAX_INTAKE_COHORTS},
+    token_interface::TOKEN_2022_PROGRAM_ID,
+    uni_account::{UniAccount, UniStatus, ROLE_REGISTRAR},
+    waitlist_account::{WaitlistAccount, MAX_WAITLIST},
+};
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+use pinocchio_token::state::Mint;
+
+const NOW: i64 = 1_700_000_000;
+const SUBJECT: u8 = 3;
+
+fn subject(max_students: u64, enrolled_count: u64) -> SubjectAccount {
+    let mut subject = SubjectAccount::zeroed();
+    subject.max_students = max_students.to_le_bytes();
+    subject.enrolled_count = enrolled_count.to_le_bytes();
+    subject
+}
+
+fn uni(status: UniStatus) -> UniAccount {
+    let mut uni = UniAccount::zeroed();
+    uni.status = status as u8;
+    uni
+}
+
+// A subject of the university at key(UNI)
+fn uni_subject(max_students: u64, enrolled_count: u64) -> SubjectAccount {
+    let mut subject = subject(max_students, enrolled_count);
+    subject.uni_key = key(UNI);
+    subject
+}
+
+
+// <---Capacity and waitlist--->
+
+fn waitlist(offer_seconds: i64) -> WaitlistAccount {
+    let mut waitlist = WaitlistAccount::zeroed();
+    waitlist.subject_key = key(SUBJECT);
+    waitlist.offer_seconds = offer_seconds.to_le_bytes();
+    waitlist
+}
+
+#[test]
+fn spots_are_taken_until_the_subject_is_full() {
+    let mut subject = subject(2, 0);
+    assert!(subject.take_spot(&key(1), NOW));
+    assert!(subject.take_spot(&key(2), NOW));
+    assert_eq!(subject.open_spots(NOW), 0);
+    assert!(!subject.take_spot(&key(3), NOW));
+
+    let mut unlimited = self::subject(0, 1_000);
+    assert!(unlimited.take_spot(&key(1), NOW));
+}
+
+#[test]
+fn waitlist_is_joined_once_the_subject_is_full() {
+    let mut waitlist = waitlist(3_600);
+    let mut open = subject(2, 1);
+    assert_eq!(open.join_waitlist(&mut waitlist, &key(1), NOW), Err(VireError::SubjectNotFull.into()));
+
+    let mut full = subject(2, 2);
+    assert_eq!(full.join_waitlist(&mut waitlist, &key(1), NOW), Ok(()));
+    assert_eq!(full.join_waitlist(&mut waitlist, &key(1), NOW), Err(VireError::AlreadyWaitlisted.into()));
+    assert_eq!(full.waitlist_len, 1);
+}
+
+#[test]
+fn waitlist_is_bounded() {
+    let mut waitlist = waitlist(3_600);
+    let mut full = subject(2, 2);
+    for seed in 0..MAX_WAITLIST as u8 {
+        assert_eq!(full.join_waitlist(&mut waitlist, &key(100 + seed), NOW), Ok(()));
+    }
+    assert_eq!(full.join_waitlist(&mut waitlist, &key(1), NOW), Err(VireError::WaitlistFull.into()));
+}
+
+#[test]
+fn freed_spots_go_to_the_waitlist_first() {
+    let mut waitlist = waitlist(3_600);
+    let mut subject = subject(1, 1);
+    subject.join_waitlist(&mut waitlist, &key(1), NOW).unwrap();
+    subject.join_waitlist(&mut waitlist, &key(2), NOW).unwrap();
+
+    // a student leaves, the free spot can't be taken around the queue
+    subject.enrolled_count = 0u64.to_le_bytes();
+    assert!(!subject.take_spot(&key(9), NOW));
+    // ...and can still be queued for
+    assert_eq!(subject.join_waitlist(&mut waitlist, &key(9), NOW), Ok(()));
+
+    assert_eq!(subject.offer_spot(&mut waitlist, NOW), Ok(()));
+    assert_eq!(subject.offered_student, key(1));
+    assert_eq!(subject.waitlist_len, 2);
+    assert_eq!(subject.open_spots(NOW), 0);
+    assert_eq!(subject.offer_spot(&mut waitlist, NOW), Err(VireError::WaitlistOfferActive.into()));
+
+    // only the offered wallet takes the held spot
+    assert!(!subject.take_spot(&key(2), NOW));
+    assert!(subject.take_spot(&key(1), NOW + 3_600));
+    assert_eq!(subject.offered_student, [0; 32]);
+    assert_eq!(u64::from_le_bytes(subject.enrolled_count), 1);
+}
+
+#[test]
+fn expired_offers_go_to_the_next_wallet() {
+    let mut waitlist = waitlist(3_600);
+    let mut subject = subject(1, 1);
+    subject.join_waitlist(&mut waitlist, &key(1), NOW).unwrap();
+    subject.join_waitlist(&mut waitlist, &key(2), NOW).unwrap();
+    subject.enrolled_count = 0u64.to_le_bytes();
+
+    subject.offer_spot(&mut waitlist, NOW).unwrap();
+    let expired = NOW + 3_601;
+    assert!(!subject.take_spot(&key(1), expired));
+
+    assert_eq!(subject.offer_spot(&mut waitlist, expired), Ok(()));
+    assert_eq!(subject.offered_student, key(2));
+    assert_eq!(subject.waitlist_len, 0);
+    assert!(subject.take_spot(&key(2), expired));
+}
+
+#[test]
+fn offers_need_a_free_spot_and_a_waiting_wallet() {
+    let mut waitlist = waitlist(3_600);
+    let mut full = subject(1, 1);
+    assert_eq!(full.offer_spot(&mut waitlist, NOW), Err(VireError::SubjectFull.into()));
+
+    let mut open = subject(1, 0);
+    assert_eq!(open.offer_spot(&mut waitlist, NOW), Err(VireError::WaitlistEmpty.into()));
+}
+
+#[test]
+fn waitlist_offers_last_a_while() {
+    assert_eq!(WaitlistArgs::new(3_600, 255).check(), Ok(()));
+    assert_eq!(WaitlistArgs::new(0, 255).check(), Err(VireError::InvalidWaitlistOffer.into()));
+    assert_eq!(WaitlistArgs::new(-1, 255).check(), Err(VireError::InvalidWaitlistOffer.into()));
+}
+
+#[test]
+fn waitlists_belong_to_their_subject() {
+    let mut other = waitlist(3_600);
+    other.subject_key = key(4);
+    let mut accounts = [
+        TestAccount::signer(key(1)),
+        TestAccount::program_account(key(UNI), &uni(UniStatus::Active)),
+        TestAccount::program_account(key(SUBJECT), &uni_subject(1, 1)),
+        TestAccount::program_account(key(5), &other),
+    ];
+
+    let result = process(&mut accounts, VireInstruction::JoinWaitlist, &[]);
+    assert_eq!(result, Err(ProgramError::InvalidAccountData));
+}
+
+#[test]
+fn waitlists_only_move_at_active_universities() {
+    let mut accounts = [
+        TestAccount::signer(key(1)),
+        TestAccount::program_account(key(UNI), &uni(UniStatus::Suspended)),
+        TestAccount::program_account(key(SUBJECT), &uni_subject(1, 1)),
+        TestAccount::program_account(key(5), &waitlist(3_600)),
+    ];
+    let result = process(&mut accounts, VireInstruction::JoinWaitlist, &[]);
+    assert_eq!(result, Err(VireError::UniSuspended.into()));
+
+    let result = process(&mut accounts[1..], VireInstruction::OfferWaitlistSpot, &[]);
+    assert_eq!(result, Err(VireError::UniSuspended.into()));
+}
+
+
+// <---Intake cohorts--->
+
+const DAY: i64 = 24 * 60 * 60;
+
+fn cohort(opens_at: i64, closes_at: i64, starts_at: i64) -> IntakeCohort {
+    IntakeCohort {
+        opens_at: opens_at.to_le_bytes(),
+        closes_at: closes_at.to_le_bytes(),
+        starts_at: starts_at.to_le_bytes(),
+    }
+}
+
+#[test]
+fn students_enrol_in_the_open_cohort() {
+    let mut subject = subject(0, 0);
+    subject.add_cohort(cohort(NOW, NOW + 30 * DAY, NOW + 40 * DAY), NOW).unwrap();
+    subject.add_cohort(cohort(NOW + 100 * DAY, NOW + 130 * DAY, NOW + 140 * DAY), NOW).unwrap();
+
+    let starts_at = |now| subject.open_cohort(now).map(|cohort| i64::from_le_bytes(cohort.starts_at));
+    assert_eq!(starts_at(NOW - 1), None);
+    assert_eq!(starts_at(NOW + 30 * DAY), Some(NOW + 40 * DAY));
+    assert_eq!(starts_at(NOW + 60 * DAY), None);
+    assert_eq!(starts_at(NOW + 120 * DAY), Some(NOW + 140 * DAY));
+}
+
+#[test]
+fn cohort_windows_have_to_close_after_opening() {
+    let mut subject = subject(0, 0);
+    assert_eq!(subject.add_cohort(cohort(NOW, NOW, NOW), NOW), Err(VireError::InvalidIntakeCohort.into()));
+    assert_eq!(subject.cohort_count, 0);
+}
+
+#[test]
+fn cohorts_start_in_the_future() {
+    let mut subject = subject(0, 0);
+    assert_eq!(subject.add_cohort(cohort(NOW, NOW + 30 * DAY, 0), NOW), Err(VireError::InvalidIntakeCohort.into()));
+    assert_eq!(subject.add_cohort(cohort(NOW, NOW + 30 * DAY, NOW - DAY), NOW), Err(VireError::InvalidIntakeCohort.into()));
+    assert_eq!(subject.cohort_count, 0);
+
+    assert_eq!(subject.add_cohort(cohort(NOW, NOW + 30 * DAY, NOW), NOW), Ok(()));
+}
+
+#[test]
+fn closed_cohorts_make_room_for_new_ones() {
+    let mut subject = subject(0, 0);
+    for i in 0..MAX_INTAKE_COHORTS as i64 {
+        let opens_at = NOW + i * 100 * DAY;
+        subject.add_cohort(cohort(opens_at, opens_at + 30 * DAY, opens_at + 40 * DAY), NOW).unwrap();
+    }
+
+    let next = cohort(NOW + 500 * DAY, NOW + 530 * DAY, NOW + 540 * DAY);
+    assert_eq!(subject.add_cohort(next, NOW), Err(VireError::IntakeCohortsFull.into()));
+
+    // once the first window has closed its slot is reused
+    assert_eq!(subject.add_cohort(next, NOW + 31 * DAY), Ok(()));
+    assert_eq!(subject.cohort_count as usize, MAX_INTAKE_COHORTS);
+    assert_eq!(i64::from_le_bytes(subject.cohorts[0].opens_at), NOW + 500 * DAY);
+}
+
+
+
+// <---Prerequisites--->
+
+const PREREQUISITE: u8 = 20;
+
+fn prove(proof: TestAccount) -> Result<usize, ProgramError> {
+    let mut accounts = [TestAccount::signer(key(1)), TestAccount::wallet(key(2)), proof];
+    common::with_account_infos(&mut accounts, &[], |_, accounts, _| {
+        check_prerequisites(&key(1), &[key(PREREQUISITE)], accounts).map(|rest| rest.len())
+    })
+}
+
+#[test]
+fn subjects_without_prerequisites_take_no_proofs() {
+    let mut accounts = [TestAccount::signer(key(1)), TestAccount::wallet(key(2))];
+    let rest = common::with_account_infos(&mut accounts, &[], |_, accounts, _| {
+        check_prerequisites(&key(1), &[], accounts).map(|rest| rest.len())
+    });
+    assert_eq!(rest, Ok(2));
+}
+
+#[test]
+fn graduates_complete_their_prerequisites() {
+    let mut student = StudentAccount::zeroed();
+    assert!(!student.completed());
+
+    student.graduated_at = NOW.to_le_bytes();
+    assert!(student.completed());
+
+    student.revoked_at = (NOW + DAY).to_le_bytes();
+    assert!(!student.completed());
+
+    // an enrolment that hasn't graduated is turned down before its address is checked
+    let proof = TestAccount::program_account(key(21), &StudentAccount::zeroed());
+    assert_eq!(prove(proof), Err(VireError::PrerequisiteNotMet.into()));
+}
+
+#[test]
+fn forged_diplomas_dont_prove_a_prerequisite() {
+    // a token account holding a mint anyone created with the prerequisite subject as mint authority
+    let mut diploma = vec![0; 166];
+    diploma[..32].copy_from_slice(&key(22));
+    diploma[32..64].copy_from_slice(&key(1));
+    diploma[64..72].copy_from_slice(&1u64.to_le_bytes());
+    diploma[108] = 1;
+    diploma[165] = 2;
+    let proof = TestAccount::with_data(key(23), TOKEN_2022_PROGRAM_ID, diploma);
+    assert_eq!(prove(proof), Err(VireError::PrerequisiteNotMet.into()));
+
+    let mut accounts = [TestAccount::signer(key(1))];
+    let result = common::with_account_infos(&mut accounts, &[], |_, accounts, _| {
+        check_prerequisites(&key(1), &[key(PREREQUISITE), key(PREREQUISITE + 1)], accounts).map(|rest| rest.len())
+    });
+    assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+}
+
+// <---Transfers--->
+
+const UNI: u8 = 6;
+
+fn transfer_accounts(subject: &SubjectAccount, new_subject: &SubjectAccount) -> Vec<TestAccount> {
+    let mut uni = UniAccount::zeroed();
+    uni.status = UniStatus::Active as u8;
+    vec![
+        TestAccount::signer(key(1)),
+        TestAccount::program_account(key(2), &StudentAccount::zeroed()),
+        TestAccount::program_account(key(SUBJECT), subject),
+        TestAccount::wallet(key(4)),
+        TestAccount::program_account(key(5), new_subject),
+        TestAccount::program_account(key(UNI), &uni),
+        TestAccount::with_data(key(7), pinocchio_token::ID, Vec::new()), // card_mint
+        TestAccount::wallet(key(8)),
+        TestAccount::wallet(key(9)),
+        TestAccount::wallet(key(10)),
+        TestAccount::wallet(common::SYSTEM_PROGRAM_ID),
+        TestAccount::wallet(pinocchio_token::ID), // card_token_program
+        TestAccount::wallet(pinocchio_token::ID),
+        TestAccount::wallet(key(14)),
+        TestAccount::wallet(key(15)),
+        TestAccount::wallet(key(16)),
+    ]
+}
+
+#[test]
+fn transfers_stay_within_the_university() {
+    let mut subject = subject(0, 1);
+    subject.uni_key = key(UNI);
+    let mut other_uni = self::subject(0, 0);
+    other_uni.uni_key = key(9);
+
+    let mut accounts = transfer_accounts(&other_uni, &subject);
+    let result = process(&mut accounts, VireInstruction::TransferEnrollment, bytemuck::bytes_of(&StudentArgs::zeroed()));
+    assert_eq!(result, Err(VireError::InvalidTransfer.into()));
+
+    // ...and go to another subject
+    let mut accounts = transfer_accounts(&subject, &subject);
+    accounts[4].key = key(SUBJECT);
+    let result = process(&mut accounts, VireInstruction::TransferEnrollment, bytemuck::bytes_of(&StudentArgs::zeroed()));
+    assert_eq!(result, Err(VireError::InvalidTransfer.into()));
+}
+
+#[test]
+fn transfer_credits_a_share_of_the_paid_semesters() {
+    let mut new_subject = subject(0, 0);
+    new_subject.max_semester = 8u64.to_le_bytes();
+    new_subject.semester_months = 6u64.to_le_bytes();
+    assert_eq!(new_subject.transfer_credit_for(4), 0);
+
+    new_subject.transfer_credit = 50;
+    assert_eq!(new_subject.transfer_credit_for(4), 2);
+    assert_eq!(new_subject.transfer_credit_for(3), 1);
+
+    // the credited semesters are behind the student, the next one starts now
+    let credited = new_subject.transfer_credit_for(4);
+    let time_start = NOW - credited as i64 * 6 * 30 * DAY;
+    assert_eq!(new_subject.semester_start(time_start, credited + 1), NOW);
+}
+
+#[test]
+fn transfer_credit_leaves_the_last_semester_to_pay() {
+    let mut new_subject = subject(0, 0);
+    new_subject.max_semester = 4u64.to_le_bytes();
+    new_subject.transfer_credit = 100;
+    let credited = new_subject.transfer_credit_for(6);
+    assert_eq!(credited, 3);
+
+    // the new enrolment is paying the semester after the credited ones
+    let mut student = StudentAccount::zeroed();
+    student.semesters = (credited + 1).to_le_bytes();
+    assert!(!student.graduated(4));
+
+    student.complete_semester();
+    assert!(student.graduated(4));
+}
+
+
+// <---Leaves of absence--->
+
+#[test]
+fn leave_pauses_the_degree_clock() {
+    let mut student = StudentAccount::zeroed();
+    assert_eq!(student.start_leave(NOW), Ok(()));
+    assert!(student.on_leave());
+    assert_eq!(student.paused_seconds(NOW + 10 * DAY), 10 * DAY);
+    assert_eq!(student.start_leave(NOW + DAY), Err(VireError::StudentOnLeave.into()));
+
+    assert_eq!(student.end_leave(NOW + 30 * DAY), Ok(()));
+    assert!(!student.on_leave());
+    assert_eq!(student.paused_seconds(NOW + 100 * DAY), 30 * DAY);
+    assert_eq!(student.end_leave(NOW + 31 * DAY), Err(VireError::NotOnLeave.into()));
+}
+
+#[test]
+fn leaves_and_suspensions_add_up() {
+    let mut student = StudentAccount::zeroed();
+    student.start_leave(NOW).unwrap();
+    student.end_leave(NOW + 10 * DAY).unwrap();
+    student.start_leave(NOW + 50 * DAY).unwrap();
+    student.end_leave(NOW + 55 * DAY).unwrap();
+    student.suspended_seconds = (3 * DAY).to_le_bytes();
+    student.suspended_at = (NOW + 80 * DAY).to_le_bytes();
+
+    assert_eq!(student.paused_seconds(NOW + 82 * DAY), 10 * DAY + 5 * DAY + 3 * DAY + 2 * DAY);
+}
+
+#[test]
+fn leaves_are_limited() {
+    let mut student = StudentAccount::zeroed();
+    for i in 0..MAX_LEAVES as i64 {
+        student.start_leave(NOW + i * 10 * DAY).unwrap();
+        student.end_leave(NOW + i * 10 * DAY + DAY).unwrap();
+    }
+    assert_eq!(student.start_leave(NOW + 100 * DAY), Err(VireError::TooManyLeaves.into()));
+}
+
+#[test]
+fn semesters_start_later_by_the_time_on_leave() {
+    let mut subject = subject(0, 0);
+    subject.semester_months = 6u64.to_le_bytes();
+    let mut student = StudentAccount::zeroed();
+    student.start_leave(NOW + DAY).unwrap();
+    student.end_leave(NOW + 31 * DAY).unwrap();
+
+    let now = NOW + 300 * DAY;
+    let time_start = NOW + student.paused_seconds(now);
+    assert_eq!(subject.semester_start(time_start, 2), NOW + 210 * DAY);
+}
+
+#[test]
+fn leaves_are_approved_by_the_university() {
+    let mut accounts = [
+        TestAccount::signer(key(1)),
+        TestAccount::wallet(key(2)),
+        TestAccount::program_account(key(UNI), &UniAccount::zeroed()),
+        TestAccount::program_account(key(SUBJECT), &subject(0, 0)),
+        TestAccount::program_account(key(4), &StudentAccount::zeroed()),
+    ];
+    let result = process(&mut accounts, VireInstruction::RequestLeave, &[]);
+    assert_eq!(result, Err(ProgramError::MissingRequiredSignature));
+}
+
+#[test]
+fn students_come_back_from_leave_at_suspended_universities() {
+    let mut suspended = uni(UniStatus::Suspended);
+    suspended.grant_role(&key(2), ROLE_REGISTRAR).unwrap();
+    let mut accounts = [
+        TestAccount::signer(key(1)),
+        TestAccount::signer(key(2)),
+        TestAccount::program_account(key(UNI), &suspended),
+        TestAccount::program_account(key(SUBJECT), &subject(0, 0)),
+        TestAccount::program_account(key(4), &StudentAccount::zeroed()),
+    ];
+    let result = process(&mut accounts, VireInstruction::RequestLeave, &[]);
+    assert_eq!(result, Err(VireError::UniSuspended.into()));
+
+    // resuming gets past the status to the subject, which isn't the university's here
+    let result = process(&mut accounts, VireInstruction::ResumeStudies, &[]);
+    assert_eq!(result, Err(ProgramError::InvalidAccountData));
+}
+
+
+// <---Suspensions and refunds--->
+
+const SPONSOR: u8 = 7;
+const MINT: u8 = 10;
+
+fn paid_student(semesters: u64, semester_paid: u64) -> StudentAccount {
+    let mut student = StudentAccount::zeroed();
+    student.semesters = semesters.to_le_bytes();
+    student.approved_semesters = semesters.to_le_bytes();
+    student.record_payment(&key(SPONSOR), 3_000).unwrap();
+    student.semester_paid = 3_000u64.to_le_bytes();
+    if semester_paid == 0 {
+        student.complete_semester();
+    } else {
+        student.semester_paid = semester_paid.to_le_bytes();
+    }
+    student
+}
+
+fn refund_with(student: &mut StudentAccount, subject: &SubjectAccount, sponsor: u8, uni_ata_owner: Pubkey) -> Result<(), ProgramError> {
+    let mut mint = vec![0; Mint::LEN];
+    mint[45] = 1;
+    let mut sponsor_ata = vec![0; 165];
+    sponsor_ata[..32].copy_from_slice(&key(MINT));
+    sponsor_ata[32..64].copy_from_slice(&key(sponsor));
+    let uni = UniAccount::zeroed();
+    let mut accounts = [
+        TestAccount::signer(key(1)),
+        TestAccount::program_account(key(UNI), &uni),
+        TestAccount::with_data(key(MINT), pinocchio_token::ID, mint),
+        TestAccount::with_data(key(11), uni_ata_owner, vec![0; 165]),
+        TestAccount::with_data(key(12), pinocchio_token::ID, sponsor_ata),
+        TestAccount::wallet(pinocchio_token::ID),
+    ];
+    common::with_account_infos(&mut accounts, &[], |_, accounts, _| {
+        refund_sponsor(student, subject, &accounts[1], &uni, &accounts[0], &accounts[2..])
+    })
+}
+
+fn refund(student: &mut StudentAccount, subject: &SubjectAccount, sponsor: u8) -> Result<(), ProgramError> {
+    refund_with(student, subject, sponsor, pinocchio_token::ID)
+}
+
+fn refund_policy(refund_percent: u8) -> SubjectAccount {
+    let mut subject = subject(0, 0);
+    subject.payment_mint = key(MINT);
+    subject.refund_percent = refund_percent;
+    subject
+}
+
+#[test]
+fn refunds_follow_the_subject_policy() {
+    let student = paid_student(2, 1_500);
+    assert_eq!(student.refund_due(50), 1_500);
+    assert_eq!(student.refund_due(0), 0);
+
+    // a completed semester is refunded from its last payment
+    let student = paid_student(2, 0);
+    assert_eq!(student.refund_due(100), 3_000);
+}
+
+#[test]
+fn refunding_a_semester_in_progress_takes_back_its_payments() {
+    let mut student = paid_student(2, 1_500);
+    student.take_back_refunded_semester();
+    assert_eq!(u64::from_le_bytes(student.semester_paid), 0);
+    assert_eq!(u64::from_le_bytes(student.payment.paid), 0);
+    assert_eq!(u64::from_le_bytes(student.semesters), 2);
+}
+
+#[test]
+fn refunding_a_completed_semester_takes_back_its_credit() {
+    let mut student = paid_student(2, 0);
+    assert_eq!(u64::from_le_bytes(student.semesters), 3);
+    student.take_back_refunded_semester();
+    assert_eq!(u64::from_le_bytes(student.semesters), 2);
+    assert_eq!(u64::from_le_bytes(student.approved_semesters), 1);
+    assert_eq!(u64::from_le_bytes(student.last_payment.paid), 0);
+
+    // a student is always in their first semester at least
+    let mut student = paid_student(0, 0);
+    student.take_back_refunded_semester();
+    assert_eq!(u64::from_le_bytes(student.semesters), 1);
+    assert_eq!(u64::from_le_bytes(student.approved_semesters), 0);
+}
+
+#[test]
+fn sponsors_are_refunded_once() {
+    let mut student = paid_student(2, 0);
+    student.record_refund(student.refund_due(50));
+    assert_eq!(u64::from_le_bytes(student.refunded), 1_500);
+    assert_eq!(u64::from_le_bytes(student.semesters), 2);
+
+    assert_eq!(refund(&mut student, &refund_policy(50), SPONSOR), Err(VireError::RefundAlreadyIssued.into()));
+}
+
+#[test]
+fn refunds_go_to_the_sponsor_in_the_payment_mint() {
+    let mut student = paid_student(2, 0);
+    assert_eq!(refund(&mut student, &refund_policy(50), 8), Err(ProgramError::InvalidAccountData));
+
+    let mut other_mint = refund_policy(50);
+    other_mint.payment_mint = key(9);
+    assert_eq!(refund(&mut student, &other_mint, SPONSOR), Err(VireError::PaymentMintMismatch.into()));
+
+    assert_eq!(refund(&mut student, &refund_policy(0), SPONSOR), Err(VireError::NoRefundDue.into()));
+    assert_eq!(u64::from_le_bytes(student.refunded), 0);
+    assert_eq!(u64::from_le_bytes(student.semesters), 3);
+}
+
+#[test]
+fn refunds_come_out_of_the_university_treasury() {
+    // the treasury is the university admin's associated token account, which is checked before anything moves
+    let mut student = paid_student(2, 0);
+    let result = refund_with(&mut student, &refund_policy(50), SPONSOR, SYSTEM_PROGRAM_ID);
+    assert_eq!(result, Err(ProgramError::InvalidAccountOwner));
+    assert_eq!(u64::from_le_bytes(student.refunded), 0);
+}
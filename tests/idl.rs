@@ -0,0 +1,314 @@
+use std::{fs, path::PathBuf};
+
+use escrow_bytemuck::{
+    discount_account::DiscountAccount,
+    errors::VireError,
+    idl::{IdlField, IdlInstruction, IdlType, IdlTypeDef, ACCOUNTS, INSTRUCTIONS, INTAKE_COHORT, LEAVE_INTERVAL, SEMESTER_PAYMENT, SEMESTER_RECORD, STAFF_MEMBER},
+    instructions::{
+        add_intake_cohort::CohortArgs, add_role::RoleArgs, add_subjects::SubjectArgs, approve_semester::ApproveSemesterArgs, award_scholarship::AwardArgs,
+        create_discount::DiscountArgs, create_waitlist::WaitlistArgs, deposit_scholarship::DepositArgs, expel_student::ExpelArgs, initialize_scholarship_pool::PoolArgs, initialize_student::StudentArgs, initialize_uni::UniArgs,
+        initialize_vire::VireArgs, record_grades::GradeArgs, revoke_credential::RevokeArgs, suspend_student::SuspendArgs, update_payment_mint::PaymentMintArgs,
+        verify_credential::CredentialStatus, vire_instructions::VireInstruction,
+    },
+    scholarship_award::ScholarshipAward,
+    scholarship_pool::ScholarshipPool,
+    student_account::{LeaveInterval, SemesterPayment, StudentAccount},
+    subject_account::{IntakeCohort, SubjectAccount},
+    transcript_account::{SemesterRecord, TranscriptAccount},
+    uni_account::{StaffMember, UniAccount},
+    vire_account::VireAccount,
+    waitlist_account::WaitlistAccount,
+};
+use pinocchio::program_error::ProgramError;
+use serde_json::{json, Value};
+
+// Regenerate the checked-in IDL with: UPDATE_IDL=1 cargo test --test idl
+
+fn idl_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("idl/vire_protocol.json")
+}
+
+fn idl_type_json(ty: &IdlType) -> Value {
+    match ty {
+        IdlType::Bool => json!("bool"),
+        IdlType::U8 => json!("u8"),
+        IdlType::U64 => json!("u64"),
+        IdlType::I64 => json!("i64"),
+        IdlType::Pubkey => json!("pubkey"),
+        IdlType::Array(ty, len) => json!({ "array": [idl_type_json(ty), len] }),
+        IdlType::Defined(def) => json!({ "defined": { "name": def.name } }),
+    }
+}
+
+fn fields_json(fields: &[IdlField]) -> Value {
+    fields
+        .iter()
+        .map(|f| json!({ "name": f.name, "type": idl_type_json(&f.ty) }))
+        .collect()
+}
+
+fn instruction_json(ix: &IdlInstruction) -> Value {
+    let accounts: Vec<Value> = ix
+        .accounts
+        .iter()
+        .map(|a| {
+            let mut item = json!({ "name": a.name });
+            if a.writable {
+                item["writable"] = json!(true);
+            }
+            if a.signer {
+                item["signer"] = json!(true);
+            }
+            if let Some(address) = a.address {
+                item["address"] = json!(bs58::encode(address).into_string());
+            }
+            if a.optional {
+                item["optional"] = json!(true);
+            }
+            item
+        })
+        .collect();
+
+    let mut instruction = json!({
+        "name": ix.name,
+        "discriminator": [ix.instruction as u8],
+        "accounts": accounts,
+        "args": fields_json(ix.args),
+    });
+    if let Some(returns) = ix.returns {
+        instruction["returns"] = json!({ "defined": { "name": returns.name } });
+    }
+    instruction
+}
+
+fn type_json(def: &IdlTypeDef) -> Value {
+    json!({
+        "name": def.name,
+        "serialization": "bytemuck",
+        "repr": { "kind": "c" },
+        "type": { "kind": "struct", "fields": fields_json(def.fields) },
+    })
+}
+
+// Structs nested in account fields, e.g. TranscriptAccount.records
+fn nested_type(ty: &IdlType) -> Option<&'static IdlTypeDef> {
+    match ty {
+        IdlType::Array(ty, _) => nested_type(ty),
+        IdlType::Defined(def) => Some(def),
+        _ => None,
+    }
+}
+
+fn generate_idl() -> Value {
+    let args_types: Vec<Value> = INSTRUCTIONS
+        .iter()
+        .filter_map(|ix| {
+            ix.args_type.map(|name| type_json(&IdlTypeDef { name, fields: ix.args }))
+        })
+        .collect();
+    let return_types = INSTRUCTIONS.iter().filter_map(|ix| ix.returns).map(type_json);
+    let nested_types = ACCOUNTS
+        .iter()
+        .flat_map(|def| def.fields)
+        .filter_map(|f| nested_type(&f.ty))
+        .map(type_json);
+
+    json!({
+        "address": bs58::encode(escrow_bytemuck::ID).into_string(),
+        "metadata": {
+            "name": "vire_protocol",
+            "version": env!("CARGO_PKG_VERSION"),
+            "spec": "0.1.0",
+            "description": "Vire Protocol - Tuition Management System",
+        },
+        "instructions": INSTRUCTIONS.iter().map(instruction_json).collect::<Vec<_>>(),
+        "accounts": ACCOUNTS
+            .iter()
+            .map(|def| json!({ "name": def.name, "discriminator": [] }))
+            .collect::<Vec<_>>(),
+        "errors": VireError::ALL
+            .iter()
+            .map(|e| json!({ "code": *e as u32, "name": e.name(), "msg": e.msg() }))
+            .collect::<Vec<_>>(),
+        "types": ACCOUNTS
+            .iter()
+            .map(type_json)
+            .chain(nested_types)
+            .chain(args_types)
+            .chain(return_types)
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn find_account(name: &str) -> &'static IdlTypeDef {
+    ACCOUNTS.iter().find(|def| def.name == name).unwrap()
+}
+
+fn find_instruction(instruction: VireInstruction) -> &'static IdlInstruction {
+    INSTRUCTIONS.iter().find(|ix| ix.instruction == instruction).unwrap()
+}
+
+fn packed_size(fields: &[IdlField]) -> usize {
+    fields.iter().map(|f| f.ty.size()).sum()
+}
+
+// Checks field names, order, offsets and total size against the bytemuck struct
+macro_rules! assert_layout {
+    ($ty:ty, [$($field:ident),* $(,)?]) => {
+        assert_layout!(find_account(stringify!($ty)), $ty, [$($field),*])
+    };
+    ($def:expr, $ty:ty, [$($field:ident),* $(,)?]) => {{
+        let def: &IdlTypeDef = $def;
+        let names: &[&str] = &[$(stringify!($field)),*];
+        let offsets: &[usize] = &[$(core::mem::offset_of!($ty, $field)),*];
+
+        assert_eq!(def.fields.iter().map(|f| f.name).collect::<Vec<_>>(), names, "{}", def.name);
+
+        let mut offset = 0;
+        for (f, actual) in def.fields.iter().zip(offsets) {
+            assert_eq!(offset, *actual, "{}.{}", def.name, f.name);
+            offset += f.ty.size();
+        }
+        assert_eq!(offset, core::mem::size_of::<$ty>(), "{}", def.name);
+    }};
+}
+
+#[test]
+fn state_layouts_match_idl() {
+    assert_eq!(ACCOUNTS.len(), 9);
+    assert_layout!(VireAccount, [admin_key, uni_number, transaction_fee_uni, transaction_fee_student, payment_mint_count, payment_mints, vire_bump]);
+    assert_layout!(UniAccount, [uni_key, vire_key, uni_id, subject_number, student_number, staff_count, staff, status, uni_bump]);
+    assert_layout!(SubjectAccount, [uni_key, subject_code, tution_fee, max_semester, semester_months, payment_mint, collection_mint, min_grade, requires_approval, payment_window_days, late_fee, installments, max_students, enrolled_count, offered_student, offer_expires_at, cohort_count, cohorts, prerequisite_count, prerequisites, transfer_credit, refund_percent, waitlist_len, subject_bump]);
+    assert_layout!(StudentAccount, [student_key, student_id, time_start, semesters, semester_paid, approved_semesters, payment, last_payment, graduated_at, diploma_mint, revoked_at, revocation_reason, cohort_start, leave_count, leaves, suspended_at, suspended_seconds, expelled_at, refunded, semester_discount, award_remaining, student_bump]);
+    assert_layout!(TranscriptAccount, [student_account, record_count, total_credits, weighted_grade_sum, records, carried_over, transcript_bump]);
+    assert_layout!(ScholarshipPool, [uni_key, subject_key, mint, vault, total_deposited, total_awarded, total_used, pool_bump]);
+    assert_layout!(ScholarshipAward, [pool, student_account, awarded, used, award_bump]);
+    assert_layout!(DiscountAccount, [subject_key, discount_id, kind, value, expires_at, max_uses, uses, student_key, discount_bump]);
+    assert_layout!(WaitlistAccount, [subject_key, offer_seconds, head, len, entries, waitlist_bump]);
+    assert_layout!(&SEMESTER_RECORD, SemesterRecord, [semester, grade, credits, transcript_hash, recorded_at]);
+    assert_layout!(&INTAKE_COHORT, IntakeCohort, [opens_at, closes_at, starts_at]);
+    assert_layout!(&LEAVE_INTERVAL, LeaveInterval, [started_at, ended_at]);
+    assert_layout!(&STAFF_MEMBER, StaffMember, [member, roles]);
+    assert_layout!(&SEMESTER_PAYMENT, SemesterPayment, [sponsor, paid]);
+}
+
+#[test]
+fn instructions_match_idl() {
+    // discriminators aren't reused, so there can be gaps
+    let mut count = 0;
+    for discriminator in 0..=u8::MAX {
+        if let Ok(instruction) = VireInstruction::try_from(&discriminator) {
+            assert_eq!(instruction as u8, discriminator);
+            find_instruction(instruction);
+            count += 1;
+        }
+    }
+    assert_eq!(INSTRUCTIONS.len(), count);
+
+    let args_sizes = [
+        (VireInstruction::InitializeVire, "VireArgs", core::mem::size_of::<VireArgs>()),
+        (VireInstruction::InitializeUni, "UniArgs", core::mem::size_of::<UniArgs>()),
+        (VireInstruction::AddSubjects, "SubjectArgs", core::mem::size_of::<SubjectArgs>()),
+        (VireInstruction::InitializeStudent, "StudentArgs", core::mem::size_of::<StudentArgs>()),
+        (VireInstruction::UpdatePaymentMint, "PaymentMintArgs", core::mem::size_of::<PaymentMintArgs>()),
+        (VireInstruction::RevokeCredential, "RevokeArgs", core::mem::size_of::<RevokeArgs>()),
+        (VireInstruction::RecordGrades, "GradeArgs", core::mem::size_of::<GradeArgs>()),
+        (VireInstruction::ApproveSemester, "ApproveSemesterArgs", core::mem::size_of::<ApproveSemesterArgs>()),
+        (VireInstruction::InitializeScholarshipPool, "PoolArgs", core::mem::size_of::<PoolArgs>()),
+        (VireInstruction::DepositScholarship, "DepositArgs", core::mem::size_of::<DepositArgs>()),
+        (VireInstruction::AwardScholarship, "AwardArgs", core::mem::size_of::<AwardArgs>()),
+        (VireInstruction::CreateDiscount, "DiscountArgs", core::mem::size_of::<DiscountArgs>()),
+        (VireInstruction::CreateWaitlist, "WaitlistArgs", core::mem::size_of::<WaitlistArgs>()),
+        (VireInstruction::AddIntakeCohort, "CohortArgs", core::mem::size_of::<CohortArgs>()),
+        (VireInstruction::TransferEnrollment, "StudentArgs", core::mem::size_of::<StudentArgs>()),
+        (VireInstruction::SuspendStudent, "SuspendArgs", core::mem::size_of::<SuspendArgs>()),
+        (VireInstruction::ExpelStudent, "ExpelArgs", core::mem::size_of::<ExpelArgs>()),
+        (VireInstruction::AddRole, "RoleArgs", core::mem::size_of::<RoleArgs>()),
+        (VireInstruction::RemoveRole, "RoleArgs", core::mem::size_of::<RoleArgs>()),
+    ];
+    for (instruction, name, size) in args_sizes {
+        let ix = find_instruction(instruction);
+        assert_eq!(ix.args_type, Some(name), "{}", ix.name);
+        assert_eq!(packed_size(ix.args), size, "{}", ix.name);
+    }
+
+    // optional accounts are left off the end of the account list
+    for ix in INSTRUCTIONS {
+        let first_optional = ix.accounts.iter().position(|a| a.optional).unwrap_or(ix.accounts.len());
+        assert!(ix.accounts[first_optional..].iter().all(|a| a.optional), "{}", ix.name);
+    }
+
+    let verify = find_instruction(VireInstruction::VerifyCredential);
+    assert_eq!(verify.returns.map(|def| packed_size(def.fields)), Some(CredentialStatus::LEN));
+
+    for ix in INSTRUCTIONS.iter().filter(|ix| ix.args_type.is_none()) {
+        assert!(ix.args.is_empty(), "{}", ix.name);
+    }
+}
+
+// Account names in the order each handler destructures them, a trailing `rest @ ..` allows more in the IDL
+fn handler_accounts(name: &str) -> (Vec<String>, bool) {
+    // instructions that delegate to another handler's account list
+    let file = match name {
+        "suspend_uni" | "reinstate_uni" => "approve_uni",
+        name => name,
+    };
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("src/instructions/{file}.rs"));
+    let source = fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+
+    let end = source
+        .find("] = self")
+        .or_else(|| source.find("] = accounts"))
+        .unwrap_or_else(|| panic!("{file}.rs doesn't destructure its accounts"));
+    let start = source[..end].rfind("let [").unwrap() + "let [".len();
+
+    let mut names = Vec::new();
+    let mut rest = false;
+    for item in source[start..end].split(',').map(str::trim).filter(|item| !item.is_empty()) {
+        if item.ends_with("@ ..") {
+            rest = true;
+        } else {
+            names.push(item.trim_start_matches('_').to_string());
+        }
+    }
+    (names, rest)
+}
+
+#[test]
+fn accounts_match_handlers() {
+    for ix in INSTRUCTIONS {
+        let (names, rest) = handler_accounts(ix.name);
+        let idl_names: Vec<&str> = ix.accounts.iter().map(|a| a.name).collect();
+
+        assert!(idl_names.len() >= names.len(), "{}: the IDL lists fewer accounts than the handler", ix.name);
+        assert_eq!(idl_names[..names.len()], names, "{}", ix.name);
+        if !rest {
+            assert_eq!(idl_names.len(), names.len(), "{}: the handler takes no extra accounts", ix.name);
+        }
+    }
+}
+
+#[test]
+fn errors_match_idl() {
+    for (code, e) in VireError::ALL.iter().enumerate() {
+        assert_eq!(*e as u32, code as u32);
+        assert_eq!(ProgramError::from(*e), ProgramError::Custom(code as u32));
+    }
+}
+
+#[test]
+fn idl_file_is_up_to_date() {
+    let generated = serde_json::to_string_pretty(&generate_idl()).unwrap() + "\n";
+
+    if std::env::var_os("UPDATE_IDL").is_some() {
+        fs::create_dir_all(idl_path().parent().unwrap()).unwrap();
+        fs::write(idl_path(), &generated).unwrap();
+        return;
+    }
+
+    let on_disk = fs::read_to_string(idl_path()).unwrap_or_default();
+    assert!(
+        on_disk == generated,
+        "idl/vire_protocol.json is stale, regenerate it with `UPDATE_IDL=1 cargo test --test idl`"
+    );
+}
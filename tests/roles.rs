@@ -0,0 +1,122 @@
+mod common;
+
+use bytemuck::Zeroable;
+use common::{key, process, TestAccount};
+use escrow_bytemuck::{
+    errors::VireError,
+    instructions::{add_role::RoleArgs, suspend_student::SuspendArgs, vire_instructions::VireInstruction},
+    student_account::StudentAccount,
+    subject_account::SubjectAccount,
+    uni_account::{UniAccount, UniStatus, ALL_ROLES, MAX_STAFF, ROLE_FINANCE, ROLE_GRADER, ROLE_REGISTRAR},
+};
+use pinocchio::program_error::ProgramError;
+
+const UNI: u8 = 2;
+
+
+// <---AddRole/RemoveRole--->
+
+#[test]
+fn roles_need_a_known_bit() {
+    assert_eq!(RoleArgs::new(ROLE_GRADER).check(), Ok(()));
+    assert_eq!(RoleArgs::new(ALL_ROLES).check(), Ok(()));
+    assert_eq!(RoleArgs::new(0).check(), Err(VireError::InvalidRole.into()));
+    assert_eq!(RoleArgs::new(1 << 3).check(), Err(VireError::InvalidRole.into()));
+}
+
+#[test]
+fn graders_are_added_with_add_role() {
+    // SetGrader's discriminator stays unused
+    assert_eq!(VireInstruction::try_from(&9), Err(ProgramError::InvalidInstructionData));
+    assert_eq!(VireInstruction::try_from(&10), Ok(VireInstruction::RecordGrades));
+}
+
+#[test]
+fn granted_roles_add_up() {
+    let mut uni = UniAccount::zeroed();
+    assert_eq!(uni.grant_role(&key(10), ROLE_FINANCE), Ok(()));
+    assert_eq!(uni.grant_role(&key(10), ROLE_GRADER), Ok(()));
+    assert_eq!(uni.staff_count, 1);
+    assert!(uni.staff_holds(&key(10), ROLE_FINANCE | ROLE_GRADER));
+    assert!(!uni.staff_holds(&key(10), ROLE_REGISTRAR));
+    assert!(!uni.staff_holds(&key(11), ROLE_FINANCE));
+}
+
+#[test]
+fn staff_list_is_bounded() {
+    let mut uni = UniAccount::zeroed();
+    for seed in 0..MAX_STAFF as u8 {
+        uni.grant_role(&key(10 + seed), ROLE_FINANCE).unwrap();
+    }
+    assert_eq!(uni.grant_role(&key(100), ROLE_FINANCE), Err(VireError::StaffListFull.into()));
+
+    // members already on the list still get new roles
+    assert_eq!(uni.grant_role(&key(10), ROLE_GRADER), Ok(()));
+}
+
+#[test]
+fn members_without_roles_leave_the_staff_list() {
+    let mut uni = UniAccount::zeroed();
+    uni.grant_role(&key(10), ROLE_FINANCE | ROLE_GRADER).unwrap();
+    uni.grant_role(&key(11), ROLE_REGISTRAR).unwrap();
+
+    assert_eq!(uni.revoke_role(&key(10), ROLE_GRADER), Ok(()));
+    assert!(uni.staff_holds(&key(10), ROLE_FINANCE) && !uni.staff_holds(&key(10), ROLE_GRADER));
+    assert_eq!(uni.staff_count, 2);
+
+    // the last member takes the freed slot
+    assert_eq!(uni.revoke_role(&key(10), ROLE_FINANCE), Ok(()));
+    assert_eq!(uni.staff_count, 1);
+    assert_eq!(uni.staff_position(&key(10)), None);
+    assert_eq!(uni.staff_position(&key(11)), Some(0));
+}
+
+#[test]
+fn only_held_roles_are_revoked() {
+    let mut uni = UniAccount::zeroed();
+    uni.grant_role(&key(10), ROLE_FINANCE).unwrap();
+    assert_eq!(uni.revoke_role(&key(10), ROLE_GRADER), Err(VireError::RoleNotHeld.into()));
+    assert_eq!(uni.revoke_role(&key(11), ROLE_FINANCE), Err(VireError::RoleNotHeld.into()));
+    assert_eq!(uni.staff_count, 1);
+}
+
+#[test]
+fn roles_are_granted_and_revoked_by_a_signer() {
+    for instruction in [VireInstruction::AddRole, VireInstruction::RemoveRole] {
+        let mut accounts = [
+            TestAccount::wallet(key(1)),
+            TestAccount::program_account(key(UNI), &UniAccount::zeroed()),
+            TestAccount::wallet(key(10)),
+        ];
+        let result = process(&mut accounts, instruction, bytemuck::bytes_of(&RoleArgs::new(ROLE_FINANCE)));
+        assert_eq!(result, Err(ProgramError::MissingRequiredSignature));
+    }
+}
+
+
+// <---Acting for the university--->
+
+fn suspend_student(uni: &UniAccount, signer: u8, subject_uni: u8) -> Result<(), ProgramError> {
+    let mut subject = SubjectAccount::zeroed();
+    subject.uni_key = key(subject_uni);
+    let mut accounts = [
+        TestAccount::signer(key(signer)),
+        TestAccount::program_account(key(UNI), uni),
+        TestAccount::program_account(key(3), &subject),
+        TestAccount::program_account(key(4), &StudentAccount::zeroed()),
+    ];
+    process(&mut accounts, VireInstruction::SuspendStudent, bytemuck::bytes_of(&SuspendArgs::new(true, false)))
+}
+
+#[test]
+fn staff_act_with_their_roles() {
+    let mut uni = UniAccount::zeroed();
+    uni.grant_role(&key(10), ROLE_REGISTRAR).unwrap();
+
+    // the registrar gets past the role check to the university's status...
+    assert_eq!(suspend_student(&uni, 10, 9), Err(VireError::UniPending.into()));
+
+    // ...and on an active university to the accounts it acts on
+    uni.status = UniStatus::Active as u8;
+    assert_eq!(suspend_student(&uni, 10, 9), Err(ProgramError::InvalidAccountData));
+}
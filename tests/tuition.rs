@@ -0,0 +1,404 @@
+mod common;
+
+use bytemuck::Zeroable;
+use common::{key, process, TestAccount};
+use escrow_bytemuck::{
+    discount_account::{DiscountAccount, DiscountKind},
+    errors::VireError,
+    instructions::{add_subjects::SubjectArgs, deposit_scholarship::DepositArgs, vire_instructions::VireInstruction},
+    scholarship_award::ScholarshipAward,
+    scholarship_pool::ScholarshipPool,
+    student_account::StudentAccount,
+    subject_account::{SubjectAccount, SECONDS_IN_A_DAY, SECONDS_IN_A_MONTH},
+    token_interface::{self, MintMode, TransferFee},
+    token_metadata::{MetadataArgs, MetadataMode},
+    uni_account::{UniAccount, UniStatus},
+    vire_account::VireAccount,
+};
+use pinocchio::program_error::ProgramError;
+use pinocchio_token::state::Mint;
+
+const START: i64 = 1_700_000_000;
+
+fn subject(tution_fee: u64, max_semester: u64) -> SubjectAccount {
+    let mut subject = SubjectAccount::zeroed();
+    subject.tution_fee = tution_fee.to_le_bytes();
+    subject.max_semester = max_semester.to_le_bytes();
+    subject.semester_months = 6u64.to_le_bytes();
+    subject
+}
+
+
+// <---Token-2022 transfer fees--->
+
+#[test]
+fn transfer_fee_rounds_up_and_is_capped() {
+    let fee = TransferFee { epoch: 0, maximum_fee: 50, basis_points: 150 };
+    assert_eq!(fee.calculate_fee(0), Some(0));
+    assert_eq!(fee.calculate_fee(1_000), Some(15));
+    assert_eq!(fee.calculate_fee(1_001), Some(16));
+    assert_eq!(fee.calculate_fee(1_000_000), Some(50));
+}
+
+#[test]
+fn pre_fee_amount_leaves_the_recipient_the_net_amount() {
+    let fee = TransferFee { epoch: 0, maximum_fee: 1_000_000, basis_points: 150 };
+    for net in [1, 99, 1_000, 123_456, 10_000_000] {
+        let gross = fee.calculate_pre_fee_amount(net).unwrap();
+        assert_eq!(gross - fee.calculate_fee(gross).unwrap(), net, "net {net}");
+    }
+
+    // once the fee is capped the payer adds the maximum fee
+    let capped = TransferFee { maximum_fee: 5, ..fee };
+    assert_eq!(capped.calculate_pre_fee_amount(1_000_000), Some(1_000_005));
+
+    let no_fee = TransferFee { basis_points: 0, ..fee };
+    assert_eq!(no_fee.calculate_pre_fee_amount(1_000), Some(1_000));
+
+    let full_fee = TransferFee { basis_points: 10_000, ..capped };
+    assert_eq!(full_fee.calculate_pre_fee_amount(1_000), Some(1_005));
+}
+
+#[test]
+fn mint_extensions_are_read_from_the_tlv_data() {
+    // base mint padded to the token account size, account type, then TLV entries
+    let mut data = vec![0; 166];
+    data[165] = 1;
+    data.extend_from_slice(&[3, 0, 2, 0, 0xaa, 0xbb]);
+    data.extend_from_slice(&[1, 0, 3, 0, 1, 2, 3]);
+
+    assert_eq!(token_interface::mint_extension(&data, 3), Some(&[0xaa, 0xbb][..]));
+    assert_eq!(token_interface::mint_extension(&data, 1), Some(&[1, 2, 3][..]));
+    assert_eq!(token_interface::mint_extension(&data, 2), None);
+
+    // a truncated entry isn't read past the end of the account
+    data.truncate(data.len() - 1);
+    assert_eq!(token_interface::mint_extension(&data, 1), None);
+}
+
+
+// <---Payment windows and late fees--->
+
+#[test]
+fn semesters_open_a_semester_length_apart() {
+    let subject = subject(12_000, 4);
+    assert_eq!(subject.semester_start(START, 1), START);
+    assert_eq!(subject.semester_start(START, 3), START + 12 * SECONDS_IN_A_MONTH);
+}
+
+#[test]
+fn payment_window_moves_by_a_month_per_installment() {
+    let mut subject = subject(12_000, 4);
+    subject.payment_window_days = 14u64.to_le_bytes();
+
+    assert_eq!(subject.payment_due(START, 0), START + 14 * SECONDS_IN_A_DAY);
+    assert_eq!(subject.payment_due(START, 2), START + 2 * SECONDS_IN_A_MONTH + 14 * SECONDS_IN_A_DAY);
+}
+
+#[test]
+fn late_fee_is_a_percentage_of_the_payment() {
+    let mut subject = subject(12_000, 4);
+    assert_eq!(subject.late_fee_on(3_000), Some(0));
+
+    subject.late_fee = 5u64.to_le_bytes();
+    assert_eq!(subject.late_fee_on(3_000), Some(150));
+
+    // small payments aren't rounded down to nothing before the percentage is taken
+    assert_eq!(subject.late_fee_on(99), Some(4));
+    assert_eq!(subject.late_fee_on(u64::MAX), None);
+}
+
+#[test]
+fn late_fee_is_capped_at_the_payment() {
+    let args = SubjectArgs::new(12_000, 4, 6, MintMode::Frontend, MetadataMode::None, MetadataArgs::zeroed(), 0);
+    assert_eq!(args.with_payment_window(14, 100).check(), Ok(()));
+    assert_eq!(args.with_payment_window(14, 101).check(), Err(VireError::InvalidLateFee.into()));
+}
+
+#[test]
+fn protocol_takes_the_student_fee_rate() {
+    let mut vire = VireAccount::zeroed();
+    vire.transaction_fee_student = 2u64.to_le_bytes();
+    assert_eq!(vire.student_fee(3_000), 60);
+    assert_eq!(vire.student_fee(0), 0);
+}
+
+
+// <---Installments--->
+
+fn subject_args(max_semester: u64, installments: u8) -> SubjectArgs {
+    SubjectArgs::new(12_000, max_semester, 6, MintMode::Frontend, MetadataMode::None, MetadataArgs::zeroed(), 0)
+        .with_installments(installments)
+}
+
+#[test]
+fn subjects_split_their_fee_across_at_least_one_semester() {
+    assert_eq!(subject_args(4, 0).check(), Ok(()));
+    assert_eq!(subject_args(0, 0).check(), Err(VireError::InvalidSemesterCount.into()));
+}
+
+#[test]
+fn installments_are_monthly_at_most() {
+    assert_eq!(subject_args(4, 6).check(), Ok(()));
+    assert_eq!(subject_args(4, 7).check(), Err(VireError::InvalidInstallmentPlan.into()));
+}
+
+#[test]
+fn semester_is_paid_at_once_without_installments() {
+    let subject = subject(12_000, 4);
+    assert_eq!(subject.semester_fee(), 3_000);
+    assert_eq!(subject.next_installment(0), (3_000, 0));
+}
+
+#[test]
+fn last_installment_takes_the_remainder() {
+    let mut subject = subject(10_000, 1);
+    subject.installments = 3;
+
+    assert_eq!(subject.next_installment(0), (3_333, 0));
+    assert_eq!(subject.next_installment(3_333), (3_333, 1));
+    assert_eq!(subject.next_installment(6_666), (3_334, 2));
+}
+
+#[test]
+fn installments_sum_to_the_semester_fee() {
+    for installments in 1..=12 {
+        let mut subject = subject(9_999, 3);
+        subject.installments = installments;
+
+        let mut paid = 0;
+        let mut payments = 0;
+        while paid < subject.semester_fee() {
+            let (amount, index) = subject.next_installment(paid);
+            assert_eq!(index, payments, "{installments} installments");
+            paid += amount;
+            payments += 1;
+        }
+        assert_eq!(paid, subject.semester_fee());
+        assert_eq!(payments, installments as i64);
+    }
+}
+
+
+// <---Semester approval--->
+
+#[test]
+fn next_semester_is_paid_once_the_last_one_is_approved() {
+    let mut student = StudentAccount::zeroed();
+    student.semesters = 1u64.to_le_bytes();
+    assert!(student.paid_semesters_approved());
+
+    // paying the first semester puts the second one on hold until ApproveSemester
+    student.complete_semester();
+    assert!(!student.paid_semesters_approved());
+    assert_eq!(student.approve_semester(1), Ok(()));
+    assert!(student.paid_semesters_approved());
+}
+
+#[test]
+fn semesters_are_approved_in_order_once_paid() {
+    let mut student = StudentAccount::zeroed();
+    student.semesters = 3u64.to_le_bytes();
+    assert_eq!(student.approve_semester(2), Err(VireError::InvalidSemesterApproval.into()));
+    assert_eq!(student.approve_semester(1), Ok(()));
+    assert_eq!(student.approve_semester(1), Err(VireError::InvalidSemesterApproval.into()));
+    assert_eq!(student.approve_semester(2), Ok(()));
+
+    // the semester being paid can't be approved yet
+    assert_eq!(student.approve_semester(3), Err(VireError::InvalidSemesterApproval.into()));
+    assert_eq!(u64::from_le_bytes(student.approved_semesters), 2);
+}
+
+
+// <---Sponsors--->
+
+#[test]
+fn students_sign_their_payments() {
+    // even when a scholarship award would cover the whole payment
+    let mut accounts: Vec<TestAccount> = (1..=12).map(|seed| TestAccount::wallet(key(seed))).collect();
+    let result = process(&mut accounts, VireInstruction::PayTutionFee, &[]);
+    assert_eq!(result, Err(ProgramError::MissingRequiredSignature));
+}
+
+#[test]
+fn sponsors_sign_their_payments() {
+    let mut accounts: Vec<TestAccount> = (1..=11).map(|seed| TestAccount::wallet(key(seed))).collect();
+    let result = process(&mut accounts, VireInstruction::SponsorPayTuition, &[]);
+    assert_eq!(result, Err(ProgramError::MissingRequiredSignature));
+}
+
+#[test]
+fn sponsors_pay_for_enrolled_students() {
+    let mut accounts: Vec<TestAccount> = (1..=11).map(|seed| TestAccount::wallet(key(seed))).collect();
+    accounts[0] = TestAccount::signer(key(1));
+    let result = process(&mut accounts, VireInstruction::SponsorPayTuition, &[]);
+    assert_eq!(result, Err(ProgramError::IncorrectProgramId));
+}
+
+#[test]
+fn payments_are_recorded_for_their_sponsor() {
+    let mut student = StudentAccount::zeroed();
+    assert_eq!(student.record_payment(&key(1), 1_000), Ok(()));
+    assert_eq!(student.record_payment(&key(1), 500), Ok(()));
+    assert_eq!(student.payment.sponsor, key(1));
+    assert_eq!(u64::from_le_bytes(student.payment.paid), 1_500);
+
+    // a semester has one sponsor
+    assert_eq!(student.record_payment(&key(2), 500), Err(VireError::SponsorMismatch.into()));
+    assert_eq!(u64::from_le_bytes(student.payment.paid), 1_500);
+}
+
+#[test]
+fn refunds_go_to_the_last_paid_semester_once_it_is_complete() {
+    let mut student = StudentAccount::zeroed();
+    student.semesters = 1u64.to_le_bytes();
+    student.record_payment(&key(1), 1_000).unwrap();
+    student.semester_paid = 1_000u64.to_le_bytes();
+    assert_eq!(student.refundable_payment().sponsor, key(1));
+
+    student.complete_semester();
+    assert_eq!(u64::from_le_bytes(student.semesters), 2);
+    assert_eq!(u64::from_le_bytes(student.payment.paid), 0);
+    assert_eq!(student.refundable_payment().sponsor, key(1));
+    assert_eq!(u64::from_le_bytes(student.refundable_payment().paid), 1_000);
+
+    // the next semester can have another sponsor
+    assert_eq!(student.record_payment(&key(2), 400), Ok(()));
+    student.semester_paid = 400u64.to_le_bytes();
+    assert_eq!(student.refundable_payment().sponsor, key(2));
+}
+
+
+// <---Scholarships--->
+
+const UNI: u8 = 7;
+
+fn active_uni() -> UniAccount {
+    let mut uni = UniAccount::zeroed();
+    uni.status = UniStatus::Active as u8;
+    uni
+}
+
+fn deposit(uni: &UniAccount, pool: &ScholarshipPool, vault: u8, amount: u64) -> (Result<(), ProgramError>, ScholarshipPool) {
+    let mut mint = vec![0; Mint::LEN];
+    mint[45] = 1;
+    let mut accounts = [
+        TestAccount::signer(key(1)),
+        TestAccount::program_account(key(UNI), uni),
+        TestAccount::program_account(key(2), pool),
+        TestAccount::with_data(key(3), pinocchio_token::ID, mint),
+        TestAccount::with_data(key(4), pinocchio_token::ID, Vec::new()),
+        TestAccount::with_data(key(vault), pinocchio_token::ID, Vec::new()),
+        TestAccount::wallet(pinocchio_token::ID),
+    ];
+    let result = process(&mut accounts, VireInstruction::DepositScholarship, bytemuck::bytes_of(&DepositArgs::new(amount)));
+    (result, *accounts[2].state::<ScholarshipPool>())
+}
+
+fn pool() -> ScholarshipPool {
+    let mut pool = ScholarshipPool::zeroed();
+    pool.uni_key = key(UNI);
+    pool.mint = key(3);
+    pool.vault = key(5);
+    pool
+}
+
+#[test]
+fn donations_add_to_the_pool() {
+    let (result, pool) = deposit(&active_uni(), &pool(), 5, 2_500);
+    assert_eq!(result, Ok(()));
+    let (result, pool) = deposit(&active_uni(), &pool, 5, 500);
+    assert_eq!(result, Ok(()));
+    assert_eq!(u64::from_le_bytes(pool.total_deposited), 3_000);
+
+    // only into the pool's vault
+    let (result, pool) = deposit(&active_uni(), &pool, 6, 500);
+    assert_eq!(result, Err(ProgramError::InvalidAccountData));
+    assert_eq!(u64::from_le_bytes(pool.total_deposited), 3_000);
+}
+
+#[test]
+fn donations_go_to_active_universities() {
+    let mut suspended = active_uni();
+    suspended.status = UniStatus::Suspended as u8;
+    let (result, pool) = deposit(&suspended, &pool(), 5, 2_500);
+    assert_eq!(result, Err(VireError::UniSuspended.into()));
+    assert_eq!(u64::from_le_bytes(pool.total_deposited), 0);
+
+    // ...and to their own pools
+    let mut other = pool;
+    other.uni_key = key(8);
+    let (result, _) = deposit(&active_uni(), &other, 5, 2_500);
+    assert_eq!(result, Err(ProgramError::InvalidAccountData));
+}
+
+fn award(awarded: u64) -> ScholarshipAward {
+    let mut award = ScholarshipAward::zeroed();
+    award.awarded = awarded.to_le_bytes();
+    award
+}
+
+#[test]
+fn award_covers_the_university_first() {
+    let mut award = award(5_000);
+    assert_eq!(award.draw(2_940, 60), (2_940, 60));
+    assert_eq!(award.remaining(), 2_000);
+
+    // what is left covers part of the university's share, the payer pays the rest and the protocol fee
+    assert_eq!(award.draw(2_940, 60), (2_000, 0));
+    assert_eq!(award.remaining(), 0);
+    assert_eq!(award.draw(2_940, 60), (0, 0));
+}
+
+#[test]
+fn award_covers_the_protocol_fee_with_what_is_left() {
+    let mut award = award(3_000);
+    assert_eq!(award.draw(2_960, 60), (2_960, 40));
+    assert_eq!(u64::from_le_bytes(award.used), 3_000);
+}
+
+
+// <---Discounts--->
+
+fn discount(kind: DiscountKind, value: u64) -> DiscountAccount {
+    let mut discount = DiscountAccount::zeroed();
+    discount.subject_key = key(3);
+    discount.kind = kind as u8;
+    discount.value = value.to_le_bytes();
+    discount
+}
+
+#[test]
+fn discounts_reduce_each_payment() {
+    assert_eq!(discount(DiscountKind::Percent, 25).apply(3_000), 2_250);
+    assert_eq!(discount(DiscountKind::Percent, 100).apply(3_000), 0);
+    assert_eq!(discount(DiscountKind::Percent, 150).apply(3_000), 0);
+    assert_eq!(discount(DiscountKind::Fixed, 500).apply(3_000), 2_500);
+    assert_eq!(discount(DiscountKind::Fixed, 5_000).apply(3_000), 0);
+}
+
+#[test]
+fn discounts_are_for_their_subject_and_student() {
+    let mut discount = discount(DiscountKind::Percent, 25);
+    assert!(discount.is_for(&key(3), &key(1)));
+    assert!(!discount.is_for(&key(4), &key(1)));
+
+    discount.student_key = key(1);
+    assert!(discount.is_for(&key(3), &key(1)));
+    assert!(!discount.is_for(&key(3), &key(2)));
+}
+
+#[test]
+fn discount_uses_are_limited() {
+    let mut discount = discount(DiscountKind::Fixed, 500);
+    discount.max_uses = 2u64.to_le_bytes();
+
+    assert_eq!(discount.take_use(), Ok(()));
+    assert_eq!(discount.take_use(), Ok(()));
+    assert_eq!(discount.take_use(), Err(VireError::DiscountUsedUp.into()));
+    assert_eq!(u64::from_le_bytes(discount.uses), 2);
+
+    let mut unlimited = discount;
+    unlimited.max_uses = [0; 8];
+    assert_eq!(unlimited.take_use(), Ok(()));
+}